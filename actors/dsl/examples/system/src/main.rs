@@ -0,0 +1,45 @@
+use gmt_dos_actors::{actorscript, system::Sys};
+use gmt_dos_clients::{
+    integrator::Integrator,
+    logging::Logging,
+    operator::{Left, Operator, Right},
+    signals::{Signal, Signals},
+};
+use interface::UID;
+
+#[derive(UID)]
+pub enum Input {}
+
+#[derive(UID)]
+pub enum Residual {}
+
+#[derive(UID)]
+pub enum Command {}
+
+// A servo loop: the integrated residual is added to the servo input
+actorscript! {
+    #[system(name = Servo, input = plus, output = plus)]
+    #[labels(plus = "Σ", int = "Integral\ncontroller")]
+    1: plus[Residual] -> int[Right<Command>]! -> plus
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let input: Signals = Signals::new(1, 50).channels(Signal::Constant(1.));
+    let servo = Sys::new(Servo::new(
+        Operator::<f64>::new("+"),
+        Integrator::<Residual>::new(1).gain(0.5),
+    ))
+    .build()?;
+    let logging = Logging::<f64>::new(1);
+
+    actorscript! {
+        1: input[Left<Input>] -> {servo}[Residual] -> logging
+    }
+
+    let residual = logging.lock().await;
+    println!("residual: {:?}", residual.chunks().last());
+    assert!(residual.chunks().last().unwrap()[0].abs() < 1e-6);
+
+    Ok(())
+}
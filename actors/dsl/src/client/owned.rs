@@ -53,6 +53,8 @@ pub struct Client {
     pub output_rate: usize,
     // client type
     pub kind: ClientKind,
+    // actor is a field of a system structure
+    pub member: bool,
 }
 impl Client {
     pub fn lit_output_rate(&self) -> LitInt {
@@ -61,24 +63,35 @@ impl Client {
     pub fn lit_input_rate(&self) -> LitInt {
         Literal::usize_unsuffixed(self.input_rate).into()
     }
-    pub fn into_input(&self) -> Expanded {
+    /// Mutable reference to the actor
+    ///
+    /// The actors of a system are fields of the system structure
+    pub fn actor_mut(&self) -> Expanded {
         let actor = &self.actor;
+        if self.member {
+            quote!(&mut self.#actor)
+        } else {
+            quote!(&mut #actor)
+        }
+    }
+    pub fn into_input(&self) -> Expanded {
+        let actor = self.actor_mut();
         match &self.kind {
             ClientKind::Logger(_, None) => quote!(
                 // .log(&mut #actor).await?;
-                gmt_dos_actors::framework::network::IntoLogs::log(output, &mut #actor).await?;
+                gmt_dos_actors::framework::network::IntoLogs::log(output, #actor).await?;
             ),
             ClientKind::Logger(_, Some(size)) => quote!(
                 // .logn(&mut #actor, #size).await?;
-                gmt_dos_actors::framework::network::IntoLogsN::logn(output, &mut #actor, #size).await?;
+                gmt_dos_actors::framework::network::IntoLogsN::logn(output, #actor, #size).await?;
             ),
             ClientKind::SubSystem(System { io: Some(io), .. }) => quote!(
                 // .into_input(&mut #actor)?;
-                gmt_dos_actors::framework::network::TryIntoInputs::into_input::<#io>(output, &mut #actor)?;
+                gmt_dos_actors::framework::network::TryIntoInputs::into_input::<#io>(output, #actor)?;
             ),
             _ => quote!(
                 // .into_input(&mut #actor)?;
-                gmt_dos_actors::framework::network::TryIntoInputs::into_input(output, &mut #actor)?;
+                gmt_dos_actors::framework::network::TryIntoInputs::into_input(output, #actor)?;
             ),
         }
     }
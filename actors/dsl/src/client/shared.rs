@@ -29,6 +29,7 @@ impl SharedClient {
             input_rate: 0,
             output_rate: 0,
             kind: ClientKind::MainScope,
+            member: false,
         })))
     }
     /// Creates a new subsystem actor
@@ -41,6 +42,7 @@ impl SharedClient {
             input_rate: 0,
             output_rate: 0,
             kind: ClientKind::SubSystem(sys),
+            member: false,
         })))
     }
     /// Creates a sampler client from [gmt_dos-clients::Sampler](https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/struct.Sampler.html)
@@ -52,6 +54,7 @@ impl SharedClient {
             input_rate,
             output_rate,
             kind: ClientKind::Sampler,
+            member: false,
         })))
     }
     /// Creates a sampler client from [gmt_dos-clients_arrow](https://docs.rs/gmt_dos-clients_arrow)
@@ -64,6 +67,7 @@ impl SharedClient {
             input_rate,
             output_rate: 0,
             kind: ClientKind::Logger(model_name.clone(), size),
+            member: false,
         })))
     }
     /// Creates a scope client from [gmt_dos-clients_scope](https://docs.rs/gmt_dos-clients_scope)
@@ -88,6 +92,7 @@ impl SharedClient {
             kind: ClientKind::Scope {
                 signal: scope_signal,
             },
+            member: false,
         })))
     }
     // pub fn name(&self) -> Ident {
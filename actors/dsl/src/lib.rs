@@ -198,7 +198,39 @@ and
 *logging_10.lock().await
 ```
 
+## Systems
+
+A group of clients can be hidden behind a [gmt_dos-actors] [system] by adding the `system` attribute to the script:
+```ignore
+actorscript! {
+    #[system(name = Servo, input = plus, output = plus)]
+    1: plus[Residual] -> int[Right<Command>]! -> plus
+};
+```
+Instead of a model, the flows are expanded into the declaration of the structure `Servo`,
+with a field for each actor, and into the implementation of the [system] traits.
+The clients `input` and `output` are the gateways of the system i.e.
+the clients that receive the inputs from and send the outputs to the clients outside of the system.
+If the inputs (resp. outputs) of a gateway are not connected within the system,
+their sampling rate is set to the rate of the gateway outputs (resp. inputs).
+As the system is a declaration, it can be written at the module level and reused.
+
+The structure is generic over the type of the clients and a new system is created with
+```ignore
+let servo = Sys::new(Servo::new(plus, int)).build()?;
+```
+where the clients are given in the order they appear in the flows.
+The system can then be used as any other client in a model with
+```ignore
+actorscript! {
+    1: input[Left<Input>] -> {servo}[Residual] -> logging
+};
+```
+
+Logging, scopes and sub-systems are not allowed within a system.
+
 [gmt_dos-actors]: https://docs.rs/gmt_dos-actors
+[system]: https://docs.rs/gmt_dos-actors/latest/gmt_dos_actors/system
 [Domain Specific Language]: https://en.wikipedia.org/wiki/Domain-specific_language
 [`Write<A2B>`]: https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/interface/trait.Write.html
 [`Read<A2B>`]: https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/interface/trait.Read.html
@@ -262,6 +294,8 @@ if it preceded by another client-output pair it must also implement the `Read<Pr
   * `${n}`: same as above but also specifies the data size,
   * `..`: unbounded output
   * `~`: stream the output to a [gmt_dos-clients_scope] client
+  * `@policy`: policy applied to the output data when an input channel is full,
+    one of `block` (default), `drop_oldest`, `drop_newest` or `keep_latest` (e.g. `[Output]@keep_latest`)
 
 ### Attributes
 
@@ -275,6 +309,16 @@ if it preceded by another client-output pair it must also implement the `Read<Pr
  * `state`: model state identifier: `ready`, `running` or `completed` (default: `completed`)
  * `flowchart`: flowchart string literal name (default: `"model"`)
 
+#### `system`
+
+```ignore
+#[system(key = param, ...)]
+```
+#####  keys
+ * `name`: system structure identifier
+ * `input`: client identifier of the system inputs gateway
+ * `output`: client identifier of the system outputs gateway
+
 #### `labels`
 
 ```ignore
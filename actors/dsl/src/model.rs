@@ -21,6 +21,9 @@ use modelstate::ModelState;
 mod scope;
 pub use scope::{Scope, ScopeSignal};
 
+mod system;
+use system::SystemDeclaration;
+
 #[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
 pub struct ModelAttributes {
     pub labels: Option<KeyParams>,
@@ -49,16 +52,21 @@ pub(super) struct Model {
     pub flows: Vec<Flow>,
     pub scope: Scope,
     pub attributes: Arc<ModelAttributes>,
+    pub system: Option<SystemDeclaration>,
 }
 
 impl Display for Model {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "Model {} [{}] :",
-            self.name().to_string().to_uppercase(),
-            self.state
-        )?;
+        if let Some(system) = &self.system {
+            writeln!(f, "System {} :", system.name)?;
+        } else {
+            writeln!(
+                f,
+                "Model {} [{}] :",
+                self.name().to_string().to_uppercase(),
+                self.state
+            )?;
+        }
         for flow in self.flows.iter() {
             writeln!(f, "{flow}")?;
         }
@@ -115,9 +123,12 @@ impl Model {
                 Some("images") => {
                     model_attributes.images = Some(attr.parse_args::<KeyParams>()?);
                 }
+                Some("system") => {
+                    self.system = Some(attr.parse_args::<KeyParams>()?.try_into()?);
+                }
                 Some("scope") => (),
                 Some(value) => {
-                    panic!("found model attribute: {value}, expected model, labels or images")
+                    panic!("found model attribute: {value}, expected model, system, labels or images")
                 }
                 None => panic!("expected Some model attribute, found None"),
             }
@@ -166,6 +177,9 @@ impl Parse for Model {
 
 impl TryExpand for Model {
     fn try_expand(&self) -> syn::Result<Expanded> {
+        if let Some(system) = &self.system {
+            return (system, self).try_expand();
+        }
         let mut client_defs = vec![];
         let mut actors = vec![];
         let mut actor_defs = vec![];
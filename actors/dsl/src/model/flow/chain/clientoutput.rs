@@ -71,7 +71,7 @@ impl Parse for ClientOutputPair {
 impl TryExpand for ClientOutputPair {
     fn try_expand(&self) -> syn::Result<Expanded> {
         if let Some(output) = self.output.as_ref() {
-            let actor = self.client.actor();
+            let actor = self.client.borrow().actor_mut();
             let Output {
                 options,
                 rate_transition,
@@ -87,18 +87,18 @@ impl TryExpand for ClientOutputPair {
                         let i = self.client.borrow().input_rate.max(1);
                         let o = self.client.borrow().output_rate;
                         quote! {
-                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::<#io,#i,#o>::add_output(&mut # actor);
+                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::<#io,#i,#o>::add_output(#actor);
                             let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);
                         }
                     } else {
                         quote!{
-                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #actor);
+                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#actor);
                             let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);
                         }
                     }
                 },
                 (None, Some(client)) => {
-                    let sampler = client.actor();
+                    let sampler = client.borrow().actor_mut();
                     quote! {
                         // #actor
                         // .add_output()
@@ -109,10 +109,10 @@ impl TryExpand for ClientOutputPair {
                         // .build::<#name>()
                         ::gmt_dos_actors::framework::network::TryIntoInputs::into_input(
                             ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(
-                                ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #actor)),
-                            &mut #sampler
+                                ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#actor)),
+                            #sampler
                         )?;
-                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #sampler);
+                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#sampler);
                         let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);
 
                     }
@@ -126,20 +126,20 @@ impl TryExpand for ClientOutputPair {
                         let i = self.client.borrow().input_rate.max(1);
                         let o = self.client.borrow().output_rate;
                         quote! {
-                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::<#io,#i,#o>::add_output(&mut # actor);
+                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::<#io,#i,#o>::add_output(#actor);
                             #(let actor_output = ::gmt_dos_actors::framework::network::AddOuput::#options(actor_output);)*
                             let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);
                         }
                     } else {
                         quote!{
-                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #actor);
+                            let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#actor);
                             #(let actor_output = ::gmt_dos_actors::framework::network::AddOuput::#options(actor_output);)*
                             let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);
                         }
                     }
                 },
                 (Some(options), Some(client)) => {
-                    let sampler = client.actor();
+                    let sampler = client.borrow().actor_mut();
                     quote! {
                         // #actor
                         // .add_output()
@@ -149,14 +149,14 @@ impl TryExpand for ClientOutputPair {
                         // #sampler
                         // .add_output()
                         // .build::<#name>()
-                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #actor);
+                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#actor);
                         #(let actor_output = ::gmt_dos_actors::framework::network::AddOuput::#options(actor_output);)*
                         let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output); 
                         ::gmt_dos_actors::framework::network::TryIntoInputs::into_input(
                             output,
-                            &mut #sampler
+                            #sampler
                         )?;
-                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(&mut #sampler);
+                        let actor_output = ::gmt_dos_actors::framework::network::AddActorOutput::add_output(#sampler);
                         let output = ::gmt_dos_actors::framework::network::AddOuput::build::<#name>(actor_output);                
                   }
                 }
@@ -167,3 +167,34 @@ impl TryExpand for ClientOutputPair {
         .ok_or(syn::Error::new(Span::call_site(), "no output to quote"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Script, TryExpand};
+
+    #[test]
+    fn policies() {
+        for policy in ["block", "drop_oldest", "drop_newest", "keep_latest"] {
+            let expanded = syn::parse_str::<Script>(&format!("1: a[U]@{policy} -> b"))
+                .and_then(|script| script.try_expand())
+                .unwrap()
+                .to_string();
+            let option = format!(
+                ":: gmt_dos_actors :: framework :: network :: AddOuput :: {policy} (actor_output)"
+            );
+            assert!(
+                expanded.contains(&option),
+                "{option} not found in {expanded}"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_policy() {
+        let error = syn::parse_str::<Script>("1: a[U]@drop_all -> b").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected output policy: block, drop_oldest, drop_newest or keep_latest"
+        );
+    }
+}
@@ -21,7 +21,7 @@ pub struct Output {
     // output type
     pub ty: Type,
     pub name: String,
-    // ouput options: bootstrap, unbounded
+    // ouput options: bootstrap, unbounded, block, drop_oldest, drop_newest, keep_latest
     pub options: Option<Vec<Ident>>,
     // need a rate transition
     pub rate_transition: Option<SharedClient>,
@@ -128,8 +128,8 @@ impl Parse for MaybeOutput {
             let content;
             let _ = bracketed!(content in input);
             let mut output = Output::try_from(content)?;
-            // checking out for output options either !, .. or $ ,
-            // or any combination of the 3 after the output i.e.
+            // checking out for output options either !, .., $, ~ or @policy,
+            // or any combination of them after the output i.e.
             // client[output_name]!$
             loop {
                 match (
@@ -137,13 +137,14 @@ impl Parse for MaybeOutput {
                     input.peek(Token![$]),
                     input.peek(Token![..]),
                     input.peek(Token![~]),
+                    input.peek(Token![@]),
                 ) {
-                    (true, false, false, false) => {
+                    (true, false, false, false, false) => {
                         input
                             .parse::<Token![!]>()
                             .map(|_| output.add_option("bootstrap"))?;
                     }
-                    (false, true, false, false) => {
+                    (false, true, false, false, false) => {
                         input.parse::<Token![$]>().map(|_| {
                             let size = if input.peek(Brace) {
                                 let content;
@@ -156,17 +157,32 @@ impl Parse for MaybeOutput {
                             Ok(())
                         })??;
                     }
-                    (false, false, true, false) => {
+                    (false, false, true, false, false) => {
                         input
                             .parse::<Token![..]>()
                             .map(|_| output.add_option("unbounded"))?;
                     }
-                    (false, false, false, true) => {
+                    (false, false, false, true, false) => {
                         input.parse::<Token![~]>().map(|_| output.add_scope())?;
                     }
-                    (false, false, false, false) => break,
+                    (false, false, false, false, true) => {
+                        input.parse::<Token![@]>()?;
+                        let policy = input.parse::<Ident>()?;
+                        match policy.to_string().as_str() {
+                            option @ ("block" | "drop_oldest" | "drop_newest" | "keep_latest") => {
+                                output.add_option(option)
+                            }
+                            _ => {
+                                return Err(syn::Error::new(
+                                    policy.span(),
+                                    "expected output policy: block, drop_oldest, drop_newest or keep_latest",
+                                ))
+                            }
+                        }
+                    }
+                    (false, false, false, false, false) => break,
                     _ => panic!(
-                        "only the following combination of tokens is allowed: !, $, .. and ~"
+                        "only the following combination of tokens is allowed: !, $, .., ~ and @"
                     ),
                 }
             }
@@ -0,0 +1,493 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Ident, LitStr};
+
+use crate::{
+    client::{ClientKind, SharedClient},
+    Expand, Expanded, TryExpand,
+};
+
+use super::{
+    keyparam::{KeyParam, KeyParams},
+    Model,
+};
+
+/// System declaration
+///
+/// The flows of a model declared as a system are expanded into
+/// a structure implementing the [System](https://docs.rs/gmt_dos-actors/latest/gmt_dos_actors/system/trait.System.html) trait.
+///
+/// #[system(name = SystemType, input = client, output = client)]
+#[derive(Debug, Clone)]
+pub struct SystemDeclaration {
+    // system structure type
+    pub name: Ident,
+    // input gateway client
+    pub input: Option<Ident>,
+    // output gateway client
+    pub output: Option<Ident>,
+}
+
+impl TryFrom<KeyParams> for SystemDeclaration {
+    type Error = syn::Error;
+
+    fn try_from(key_params: KeyParams) -> Result<Self, Self::Error> {
+        let mut name = None;
+        let mut input = None;
+        let mut output = None;
+        for KeyParam { key, param } in key_params {
+            match key.to_string().as_str() {
+                "name" => {
+                    name = Some(Ident::try_from(&param)?);
+                }
+                "input" => {
+                    input = Some(Ident::try_from(&param)?);
+                }
+                "output" => {
+                    output = Some(Ident::try_from(&param)?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                            r#"expected system attributes "name", "input" or "output", found {key}"#
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            name: name.ok_or(syn::Error::new(
+                Span::call_site(),
+                r#"missing system attribute "name""#,
+            ))?,
+            input,
+            output,
+        })
+    }
+}
+
+/// System actor: the client, its type parameter and the type of the sampler
+struct Member {
+    client: SharedClient,
+    generic: Option<Ident>,
+    sampler: Option<syn::Type>,
+}
+
+impl SystemDeclaration {
+    /// Collects the system clients in the order they appear in the flows
+    fn members(&self, model: &Model) -> syn::Result<Vec<Member>> {
+        let mut members: Vec<Member> = vec![];
+        for pair in model.flows.iter().flat_map(|flow| flow.chain.iter()) {
+            let (client, output) = (&pair.client, &pair.output);
+            let name = client.borrow().name.clone();
+            match &client.borrow().kind {
+                ClientKind::MainScope => {
+                    if !members.iter().any(|m| m.client == *client) {
+                        let generic = format_ident!("{}Client", camel_case(&name));
+                        members.push(Member {
+                            client: client.clone(),
+                            generic: Some(generic),
+                            sampler: None,
+                        });
+                    }
+                }
+                ClientKind::Sampler => (),
+                _ => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!(
+                        "{name}: logging, scopes and sub-systems are not allowed within a system"
+                    ),
+                    ))
+                }
+            }
+            if let Some(output) = output {
+                if output.logging.is_some() || output.scope {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("{name}: logging and scopes are not allowed within a system"),
+                    ));
+                }
+                if let Some(sampler) = output.rate_transition.as_ref() {
+                    members.push(Member {
+                        client: sampler.clone(),
+                        generic: None,
+                        sampler: Some(output.ty.clone()),
+                    });
+                }
+            }
+        }
+        Ok(members)
+    }
+    /// Returns the gateway client given by the system attribute
+    fn gateway<'a>(&self, members: &'a [Member], gateway: &Ident) -> syn::Result<&'a Member> {
+        members
+            .iter()
+            .find(|m| m.client.borrow().name == *gateway)
+            .ok_or(syn::Error::new(
+                gateway.span(),
+                format!("system gateway {gateway} is not a client of the system"),
+            ))
+    }
+}
+
+impl TryExpand for (&SystemDeclaration, &Model) {
+    fn try_expand(&self) -> syn::Result<Expanded> {
+        let (system, model) = *self;
+        let members = system.members(model)?;
+        // the actors are the fields of the system structure
+        for Member { client, .. } in members.iter() {
+            let mut client = client.borrow_mut();
+            client.actor = client.name.clone();
+            client.member = true;
+        }
+        // setting the rate of the inputs and outputs that are exposed to other clients
+        if let Some(input) = system.input.as_ref() {
+            let mut client = system.gateway(&members, input)?.client.borrow_mut();
+            if client.input_rate == 0 {
+                client.input_rate = client.output_rate.max(1);
+            }
+        }
+        if let Some(output) = system.output.as_ref() {
+            let mut client = system.gateway(&members, output)?.client.borrow_mut();
+            if client.output_rate == 0 {
+                client.output_rate = client.input_rate.max(1);
+            }
+        }
+
+        let update = quote!(::gmt_dos_actors::interface::Update);
+        let actor_type = |member: &Member| {
+            let client = member.client.borrow();
+            let (i, o) = (client.lit_input_rate(), client.lit_output_rate());
+            match (&member.generic, &member.sampler) {
+                (Some(generic), _) => quote!(::gmt_dos_actors::prelude::Actor<#generic, #i, #o>),
+                (None, Some(ty)) => quote!(
+                    ::gmt_dos_actors::prelude::Actor<
+                        ::gmt_dos_clients::sampler::Sampler<
+                            <#ty as ::gmt_dos_actors::interface::UniqueIdentifier>::DataType,
+                            #ty,
+                        >,
+                        #i,
+                        #o,
+                    >
+                ),
+                (None, None) => unreachable!(),
+            }
+        };
+
+        let ty = &system.name;
+        let ty_name = LitStr::new(&ty.to_string(), ty.span());
+        let generics: Vec<_> = members.iter().filter_map(|m| m.generic.clone()).collect();
+        let fields: Vec<_> = members.iter().map(|m| m.client.actor()).collect();
+        let (clients, samplers): (Vec<_>, Vec<_>) =
+            members.iter().partition(|m| m.generic.is_some());
+        let client_fields: Vec<_> = clients.iter().map(|m| m.client.actor()).collect();
+        let client_types: Vec<_> = clients.iter().map(|m| actor_type(m)).collect();
+        let sampler_fields: Vec<_> = samplers.iter().map(|m| m.client.actor()).collect();
+        let sampler_types: Vec<_> = samplers.iter().map(|m| actor_type(m)).collect();
+        let client_defs: Vec<_> = members.iter().map(|m| m.client.expand()).collect();
+
+        // Write/Read traits bounds of the clients
+        let generic_of: HashMap<_, _> = clients
+            .iter()
+            .map(|m| (m.client.borrow().name.clone(), m.generic.clone().unwrap()))
+            .collect();
+        let mut bounds = vec![];
+        for flow in model.flows.iter() {
+            for (pair, next) in flow.chain.iter().zip(flow.chain.iter().skip(1)) {
+                let Some(output) = pair.output.as_ref() else {
+                    continue;
+                };
+                let uid = &output.ty;
+                if let Some(generic) = generic_of.get(&pair.client.borrow().name) {
+                    bounds.push(quote!(#generic: ::gmt_dos_actors::interface::Write<#uid>));
+                }
+                if let Some(generic) = generic_of.get(&next.client.borrow().name) {
+                    bounds.push(quote!(#generic: ::gmt_dos_actors::interface::Read<#uid>));
+                }
+            }
+        }
+        let mut unique = HashSet::new();
+        bounds.retain(|bound| unique.insert(bound.to_string()));
+
+        let labels: Vec<_> = model
+            .attributes
+            .labels
+            .iter()
+            .flat_map(|labels| labels.iter())
+            .map(|KeyParam { key, param }| {
+                let p = param.expand();
+                quote!(#key.set_label(#p);)
+            })
+            .collect();
+        let images: Vec<_> = model
+            .attributes
+            .images
+            .iter()
+            .flat_map(|images| images.iter())
+            .map(|KeyParam { key, param }| {
+                let p = param.expand();
+                quote!(#key.set_image(#p);)
+            })
+            .collect();
+
+        let flows: Vec<_> = model.flows.iter().map(|flow| flow.expand()).collect();
+
+        let gateway_impl = |gateway: &Ident, trait_name: Ident, method: Ident| {
+            let member = system.gateway(&members, gateway)?;
+            let Some(generic) = member.generic.as_ref() else {
+                return Err(syn::Error::new(
+                    gateway.span(),
+                    format!("system gateway {gateway} must be a client of the main scope"),
+                ));
+            };
+            let client = member.client.borrow();
+            let (i, o) = (client.lit_input_rate(), client.lit_output_rate());
+            let field = &client.actor;
+            let actor = actor_type(member);
+            Ok(quote! {
+                impl<#(#generics),*> ::gmt_dos_actors::system::#trait_name<#generic, #i, #o> for #ty<#(#generics),*>
+                where
+                    #(#generics: #update + 'static,)*
+                {
+                    fn #method(&mut self) -> &mut #actor {
+                        &mut self.#field
+                    }
+                }
+            })
+        };
+        let input_impl = system
+            .input
+            .as_ref()
+            .map(|input| {
+                gateway_impl(
+                    input,
+                    Ident::new("SystemInput", Span::call_site()),
+                    Ident::new("input", Span::call_site()),
+                )
+            })
+            .transpose()?;
+        let output_impl = system
+            .output
+            .as_ref()
+            .map(|output| {
+                gateway_impl(
+                    output,
+                    Ident::new("SystemOutput", Span::call_site()),
+                    Ident::new("output", Span::call_site()),
+                )
+            })
+            .transpose()?;
+        let plain_inputs = system.input.as_ref().map(|input| {
+            quote!(.inputs(::gmt_dos_actors::actor::PlainActor::from(&self.#input).inputs().unwrap_or_default()))
+        });
+        let plain_outputs = system.output.as_ref().map(|output| {
+            quote!(.outputs(::gmt_dos_actors::actor::PlainActor::from(&self.#output).outputs().unwrap_or_default()))
+        });
+
+        let doc = LitStr::new(
+            &format!(" `{ty}` system generated by `actorscript`"),
+            Span::call_site(),
+        );
+        let clients_args: Vec<_> = clients
+            .iter()
+            .map(|m| {
+                let name = m.client.borrow().name.clone();
+                let generic = m.generic.clone().unwrap();
+                quote!(#name: #generic)
+            })
+            .collect();
+
+        Ok(quote! {
+            #[doc = #doc]
+            pub struct #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                #(pub #client_fields: #client_types,)*
+                #(#sampler_fields: #sampler_types,)*
+            }
+
+            impl<#(#generics),*> #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                /// Creates a new system from its clients
+                #[allow(unused_mut)]
+                pub fn new(#(#clients_args),*) -> Self {
+                    #(#client_defs)*
+                    #(#labels)*
+                    #(#images)*
+                    Self {
+                        #(#fields: ::gmt_dos_actors::prelude::Actor::from(&#fields),)*
+                    }
+                }
+            }
+
+            impl<#(#generics),*> Clone for #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                fn clone(&self) -> Self {
+                    Self {
+                        #(#fields: self.#fields.clone(),)*
+                    }
+                }
+            }
+
+            impl<#(#generics),*> ::std::fmt::Display for #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    write!(f, "{}", #ty_name)
+                }
+            }
+
+            impl<'a, #(#generics),*> IntoIterator for &'a #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                type Item = Box<&'a dyn ::gmt_dos_actors::framework::model::Check>;
+
+                type IntoIter = std::vec::IntoIter<Self::Item>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    vec![
+                        #(Box::new(&self.#fields as &dyn ::gmt_dos_actors::framework::model::Check),)*
+                    ]
+                    .into_iter()
+                }
+            }
+
+            impl<#(#generics),*> IntoIterator for Box<#ty<#(#generics),*>>
+            where
+                #(#generics: #update + 'static,)*
+            {
+                type Item = Box<dyn ::gmt_dos_actors::framework::model::Task>;
+
+                type IntoIter = std::vec::IntoIter<Self::Item>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    vec![
+                        #(Box::new(self.#fields) as Box<dyn ::gmt_dos_actors::framework::model::Task>,)*
+                    ]
+                    .into_iter()
+                }
+            }
+
+            impl<#(#generics),*> ::gmt_dos_actors::system::System for #ty<#(#generics),*>
+            where
+                #(#generics: #update + 'static,)*
+                #(#bounds,)*
+            {
+                fn name(&self) -> String {
+                    String::from(#ty_name)
+                }
+
+                fn build(&mut self) -> Result<&mut Self, ::gmt_dos_actors::system::SystemError> {
+                    #(#flows)*
+                    Ok(self)
+                }
+
+                fn plain(&self) -> ::gmt_dos_actors::actor::PlainActor {
+                    ::gmt_dos_actors::actor::PlainActor::new(::gmt_dos_actors::system::System::name(self))
+                        #plain_inputs
+                        #plain_outputs
+                        .graph(::gmt_dos_actors::prelude::FlowChart::graph(self))
+                        .build()
+                }
+            }
+
+            #input_impl
+            #output_impl
+        })
+    }
+}
+
+/// Converts a snake case client name into a camel case type parameter name
+fn camel_case(name: &Ident) -> String {
+    name.to_string()
+        .split('_')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Script, TryExpand};
+
+    fn expand(script: &str) -> syn::Result<String> {
+        syn::parse_str::<Script>(script)?
+            .try_expand()
+            .map(|expanded| expanded.to_string())
+    }
+
+    #[test]
+    fn expansion() {
+        let expanded = expand(
+            "#[system(name = Servo, input = plus, output = plus)]
+            1: plus[Residual] -> int[Right<Command>]! -> plus",
+        )
+        .unwrap();
+        for item in [
+            "pub struct Servo < PlusClient , IntClient >",
+            "pub fn new (plus : PlusClient , int : IntClient) -> Self",
+            ":: gmt_dos_actors :: system :: System for Servo < PlusClient , IntClient >",
+            ":: gmt_dos_actors :: system :: SystemInput < PlusClient , 1 , 1 > for Servo",
+            ":: gmt_dos_actors :: system :: SystemOutput < PlusClient , 1 , 1 > for Servo",
+            "AddOuput :: bootstrap (actor_output)",
+        ] {
+            assert!(expanded.contains(item), "{item} not found in {expanded}");
+        }
+    }
+
+    #[test]
+    fn rates() {
+        let expanded = expand(
+            "#[system(name = Sys, input = a, output = c)]
+            1: a[U] -> b
+            10: b[V] -> c",
+        )
+        .unwrap();
+        assert!(
+            expanded.contains("pub b : :: gmt_dos_actors :: prelude :: Actor < BClient , 1 , 10 >")
+        );
+        assert!(expanded
+            .contains(":: gmt_dos_actors :: system :: SystemOutput < CClient , 10 , 10 > for Sys"));
+    }
+
+    #[test]
+    fn errors() {
+        for (script, error) in [
+            (
+                "#[system(input = a)] 1: a[U] -> b",
+                r#"missing system attribute "name""#,
+            ),
+            (
+                "#[system(name = Sys, gateway = a)] 1: a[U] -> b",
+                r#"expected system attributes "name", "input" or "output", found gateway"#,
+            ),
+            (
+                "#[system(name = Sys, input = c)] 1: a[U] -> b",
+                "system gateway c is not a client of the system",
+            ),
+            (
+                "#[system(name = Sys, input = a)] 1: a[U]$ -> b",
+                "a: logging and scopes are not allowed within a system",
+            ),
+        ] {
+            assert_eq!(expand(script).unwrap_err().to_string(), error);
+        }
+    }
+}
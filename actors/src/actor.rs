@@ -47,7 +47,11 @@ let sink = Terminator::<_>::new(logging.clone());
 [Logging]: crate::clients::Logging
 */
 
+use tokio::sync::{Mutex, MutexGuard};
+
 pub(crate) mod actor;
+#[cfg(feature = "filing")]
+pub(crate) mod codec;
 #[cfg(feature = "serde")]
 mod serde_with;
 
@@ -60,6 +64,14 @@ mod check;
 pub mod io;
 pub mod task;
 
+/// Locks the client [Mutex] from a synchronous context
+///
+/// The lock is not subjected to the cooperative scheduling budget of tokio
+/// so it can be used inside or outside a tokio runtime
+pub(crate) fn lock_client<C>(client: &Mutex<C>) -> MutexGuard<'_, C> {
+    futures::executor::block_on(tokio::task::unconstrained(client.lock()))
+}
+
 /// Type alias for an actor without outputs
 pub type Terminator<C, const NI: usize = 1> = Actor<C, NI, 0>;
 /// Type alias for an actor without inputs
@@ -1,10 +1,19 @@
 use super::io::{Input, InputObject, OutputObject};
+#[cfg(feature = "profiling")]
+use crate::model::profiling::ActorTimer;
 use crate::{
-    framework::network::{ActorOutput, ActorOutputBuilder, AddActorInput, AddActorOutput},
+    clock::{ClockDomain, Clocks, Schedule},
+    framework::{
+        model::StepCursor,
+        network::{ActorOutput, ActorOutputBuilder, AddActorInput, AddActorOutput},
+    },
+    model::watchdog::Probe,
     Result,
 };
-use futures::{future::join_all, stream::FuturesUnordered};
-use interface::{Data, Read, UniqueIdentifier, Update, Who};
+use futures::future::{join_all, select_all};
+use interface::{Data, Read, Size, UniqueIdentifier, Update, Who};
+#[cfg(feature = "profiling")]
+use std::time::Instant;
 use std::{
     fmt::{self, Debug},
     sync::Arc,
@@ -12,6 +21,10 @@ use std::{
 use tokio::sync::Mutex;
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "C: serde::Deserialize<'de>"))
+)]
 /// Actor model implementation
 pub struct Actor<C, const NI: usize = 1, const NO: usize = 1>
 where
@@ -34,6 +47,20 @@ where
     pub(crate) client: Arc<Mutex<C>>,
     pub(crate) name: Option<String>,
     pub(crate) image: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) clocks: Option<Clocks>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) event: bool,
+    #[cfg(feature = "filing")]
+    #[serde(skip, default)]
+    pub(crate) codec: Option<super::codec::Codec<Arc<Mutex<C>>>>,
+    #[cfg(feature = "profiling")]
+    #[serde(skip)]
+    pub(crate) timer: ActorTimer,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) probe: Option<Arc<Probe>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) cursor: StepCursor,
 }
 
 /// Clone trait implementation
@@ -48,6 +75,14 @@ impl<C: Update, const NI: usize, const NO: usize> Clone for Actor<C, NI, NO> {
             client: self.client.clone(),
             name: self.name.clone(),
             image: self.image.clone(),
+            clocks: self.clocks.clone(),
+            event: self.event,
+            #[cfg(feature = "filing")]
+            codec: self.codec,
+            #[cfg(feature = "profiling")]
+            timer: Default::default(),
+            probe: None,
+            cursor: Default::default(),
         }
     }
 }
@@ -142,6 +177,14 @@ where
             client,
             name: None,
             image: None,
+            clocks: None,
+            event: false,
+            #[cfg(feature = "filing")]
+            codec: None,
+            #[cfg(feature = "profiling")]
+            timer: Default::default(),
+            probe: None,
+            cursor: Default::default(),
         }
     }
     pub fn name<S: Into<String>>(self, name: S) -> Self {
@@ -156,27 +199,285 @@ where
             ..self
         }
     }
+    /// Assigns the actor inputs and outputs to a [clock domain](crate::clock)
+    pub fn clock(self, domain: &ClockDomain) -> Self {
+        self.transition(domain, domain)
+    }
+    /// Makes the actor a rate transition from the [clock domain](crate::clock) of the inputs
+    /// to the clock domain of the outputs
+    pub fn transition(self, inputs: &ClockDomain, outputs: &ClockDomain) -> Self {
+        Self {
+            clocks: Some(Clocks {
+                inputs: inputs.clone(),
+                outputs: outputs.clone(),
+            }),
+            ..self
+        }
+    }
+    /// Makes the actor event-driven
+    ///
+    /// An event-driven actor waits for new data on any of its inputs,
+    /// then reads the latest data of the inputs that have received some,
+    /// updates its client and sends its outputs.
+    /// The inputs of rate-based actors connected to the outputs of an event-driven actor
+    /// must be [sample-and-hold](Actor::hold) inputs
+    pub fn event_driven(self) -> Self {
+        Self {
+            event: true,
+            ..self
+        }
+    }
+    /// Returns the outputs schedule if the actor is a rate transition between clock domains
+    pub(super) fn schedule(&self) -> Option<Schedule> {
+        self.clocks
+            .as_ref()
+            .filter(|clocks| clocks.is_transition())
+            .map(|Clocks { inputs, outputs }| {
+                Schedule::new(
+                    inputs.frequency() / NI as f64,
+                    outputs.frequency() / NO as f64,
+                )
+            })
+    }
     /// Returns a pointer to the actor's client
     pub fn client(&self) -> Arc<Mutex<C>> {
         Arc::clone(&self.client)
     }
+    /// Allows the client state to be saved into a [model checkpoint](crate::model::checkpoint)
+    #[cfg(feature = "filing")]
+    pub fn checkpointable(self) -> Self
+    where
+        C: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        Self {
+            codec: Some(super::codec::Codec::client()),
+            ..self
+        }
+    }
+    /// Sets the number of elements of the data of the input `U` to the [Size] of the client
+    ///
+    /// The size is checked against the size of the output the input is connected to
+    /// when the [model](crate::model) is checked
+    pub fn sized_input<U>(&mut self) -> Result<&mut Self>
+    where
+        C: Read<U> + Size<U> + 'static,
+        U: 'static + UniqueIdentifier,
+    {
+        let len = <C as Size<U>>::len(&super::lock_client(&self.client));
+        let consumer = self.who();
+        let Some(input) = self.inputs.iter_mut().flatten().find_map(|input| {
+            input
+                .as_mut_any()
+                .downcast_mut::<Input<C, U, NI>>()
+        }) else {
+            return Err(crate::ActorError::NoInput(
+                consumer,
+                std::any::type_name::<U>().to_string(),
+            ));
+        };
+        input.sized(len);
+        Ok(self)
+    }
+    /// Makes the input `U` a sample-and-hold input
+    ///
+    /// A sample-and-hold input does not wait for new data, instead it reads the latest data received
+    /// or, if no new data has been received, the data last received.
+    /// Nothing is read until some data has been received and the data last received is still read
+    /// after the output the input is connected to has been dropped.
+    /// A rate-based actor must have at least one input that is not a sample-and-hold input,
+    /// the actor waits for new data on that input and stops when it is disconnected.
+    /// It is the boundary between the outputs of an [event-driven](Actor::event_driven) actor
+    /// and a rate-based actor
+    pub fn hold<U>(&mut self) -> Result<&mut Self>
+    where
+        C: Read<U> + 'static,
+        U: 'static + UniqueIdentifier,
+    {
+        let consumer = self.who();
+        let Some(input) = self
+            .inputs
+            .iter_mut()
+            .flatten()
+            .find_map(|input| input.as_mut_any().downcast_mut::<Input<C, U, NI>>())
+        else {
+            return Err(crate::ActorError::NoInput(
+                consumer,
+                std::any::type_name::<U>().to_string(),
+            ));
+        };
+        input.hold();
+        Ok(self)
+    }
+    /// Sets the number of elements of the data of the output `U` to the [Size] of the client
+    ///
+    /// The size is checked against the size of the inputs the output is connected to
+    /// when the [model](crate::model) is checked
+    pub fn sized_output<U>(&mut self) -> Result<&mut Self>
+    where
+        C: interface::Write<U> + Size<U> + 'static,
+        U: 'static + UniqueIdentifier,
+    {
+        let len = <C as Size<U>>::len(&super::lock_client(&self.client));
+        let producer = self.who();
+        let Some(output) = self.outputs.iter_mut().flatten().find_map(|output| {
+            output
+                .as_mut_any()
+                .downcast_mut::<super::io::Output<C, U, NO>>()
+        }) else {
+            return Err(crate::ActorError::NoOutput(
+                producer,
+                std::any::type_name::<U>().to_string(),
+            ));
+        };
+        output.sized(len);
+        Ok(self)
+    }
+    /// Records the data sent by the output `U` with a [Recorder](crate::model::record::Recorder)
+    ///
+    /// The output must have been added to the actor beforehand
+    #[cfg(feature = "filing")]
+    pub fn record<U>(&mut self, recorder: &crate::model::record::Recorder) -> Result<&mut Self>
+    where
+        C: interface::Write<U> + 'static,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let producer = self.who();
+        let Some(output) = self.outputs.iter_mut().flatten().find_map(|output| {
+            output
+                .as_mut_any()
+                .downcast_mut::<super::io::Output<C, U, NO>>()
+        }) else {
+            return Err(crate::ActorError::NoOutput(
+                producer,
+                std::any::type_name::<U>().to_string(),
+            ));
+        };
+        let output_name = format!("output {} from {}", std::any::type_name::<U>(), producer);
+        output
+            .recorded(recorder, producer)
+            .map_err(|e| crate::ActorError::Record(output_name, e))?;
+        Ok(self)
+    }
     /// Gathers all the inputs from other [Actor] outputs
     pub(super) async fn collect(&mut self) -> Result<&mut Self> {
+        #[cfg(feature = "profiling")]
+        let now = Instant::now();
+        let probe = self.probe.as_deref();
         if let Some(inputs) = &mut self.inputs {
-            let futures: FuturesUnordered<_> =
-                inputs.iter_mut().map(|input| input.recv()).collect();
+            if let Some(probe) = probe {
+                probe.collecting(inputs.len());
+            }
+            let futures: Vec<_> = inputs
+                .iter_mut()
+                .enumerate()
+                .map(|(k, input)| async move {
+                    let result = input.recv().await;
+                    if let Some(probe) = probe {
+                        probe.received(k);
+                    }
+                    result
+                })
+                .collect();
             join_all(futures)
                 .await
                 .into_iter()
                 .collect::<Result<Vec<_>>>()?;
         }
+        #[cfg(feature = "profiling")]
+        {
+            self.timer.recv += now.elapsed();
+        }
+        Ok(self)
+    }
+    /// Waits for new data on any of the inputs and reads the latest data of all the inputs
+    ///
+    /// Returns an error once all the inputs have been disconnected
+    pub(super) async fn wait(&mut self) -> Result<&mut Self> {
+        #[cfg(feature = "profiling")]
+        let now = Instant::now();
+        let probe = self.probe.as_deref();
+        if let Some(inputs) = &mut self.inputs {
+            if let Some(probe) = probe {
+                probe.collecting(inputs.len());
+            }
+            loop {
+                let mut error = None;
+                let mut connected = vec![];
+                let mut received = false;
+                for (k, input) in inputs.iter_mut().enumerate() {
+                    match input.try_latest() {
+                        Ok(true) => received = true,
+                        Ok(false) => connected.push(k),
+                        Err(e) => error = Some(e),
+                    }
+                }
+                if received {
+                    break;
+                }
+                if connected.is_empty() {
+                    return Err(error.unwrap_or(crate::ActorError::NoInputs));
+                }
+                let futures: Vec<_> = inputs
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(k, _)| connected.contains(k))
+                    .map(|(_, input)| input.wait())
+                    .collect();
+                // a disconnected input is found by the next non-blocking receive
+                if let (Err(e @ crate::ActorError::Cancelled(_)), ..) = select_all(futures).await {
+                    return Err(e);
+                }
+            }
+            for (k, input) in inputs.iter_mut().enumerate() {
+                input.flush();
+                if let Some(probe) = probe {
+                    probe.received(k);
+                }
+            }
+        }
+        #[cfg(feature = "profiling")]
+        {
+            self.timer.recv += now.elapsed();
+        }
         Ok(self)
     }
+    /// Updates the client state
+    pub(super) async fn update(&mut self) {
+        if let Some(probe) = self.probe.as_deref() {
+            probe.updating();
+        }
+        let mut client = self.client.lock().await;
+        #[cfg(feature = "profiling")]
+        let now = Instant::now();
+        client.update();
+        #[cfg(feature = "profiling")]
+        {
+            self.timer.update += now.elapsed();
+            self.timer.n_update += 1;
+        }
+        if let Some(probe) = self.probe.as_deref() {
+            probe.updated();
+        }
+    }
     /// Sends the outputs to other [Actor] inputs
     pub(super) async fn distribute(&mut self) -> Result<&mut Self> {
+        let probe = self.probe.as_deref();
         if let Some(outputs) = &mut self.outputs {
-            let futures: FuturesUnordered<_> =
-                outputs.iter_mut().map(|output| output.send()).collect();
+            if let Some(probe) = probe {
+                probe.distributing(outputs.len());
+            }
+            let futures: Vec<_> = outputs
+                .iter_mut()
+                .enumerate()
+                .map(|(k, output)| async move {
+                    let result = output.send().await;
+                    if let Some(probe) = probe {
+                        probe.sent(k);
+                    }
+                    result
+                })
+                .collect();
             join_all(futures)
                 .await
                 .into_iter()
@@ -184,39 +485,104 @@ where
         }
         Ok(self)
     }
+    /// Sends the bootstrapped outputs once
+    ///
+    /// Returns `false` if none of the outputs is bootstrapped
+    pub(super) async fn send_bootstrap(&mut self) -> Result<bool> {
+        let Some(outputs) = &mut self.outputs else {
+            return Ok(false);
+        };
+        let futures: Vec<_> = outputs
+            .iter_mut()
+            .filter(|output| output.bootstrap())
+            .inspect(|output| {
+                interface::print_info(
+                    format!("{} bootstrapped", output.highlight()),
+                    None::<&dyn std::error::Error>,
+                )
+            })
+            .map(|output| output.send())
+            .collect();
+        join_all(futures)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(|result| !result.is_empty())
+    }
     /// Invokes outputs senders
     pub(super) async fn bootstrap(&mut self) -> Result<bool> {
-        if let Some(outputs) = &mut self.outputs {
-            async fn inner(outputs: &mut Vec<Box<dyn OutputObject>>) -> Result<Vec<()>> {
-                let futures: Vec<_> = outputs
-                    .iter_mut()
-                    .filter(|output| output.bootstrap())
-                    .inspect(|output| {
-                        interface::print_info(
-                            format!("{} bootstrapped", output.highlight()),
-                            None::<&dyn std::error::Error>,
-                        )
-                    })
-                    .map(|output| output.send())
-                    .collect();
-                join_all(futures)
-                    .await
-                    .into_iter()
-                    .collect::<Result<Vec<_>>>()
+        if NO >= NI {
+            self.send_bootstrap().await
+        } else {
+            let mut a = true;
+            for _ in 0..NI / NO {
+                a = a && self.send_bootstrap().await?;
+            }
+            Ok(a)
+        }
+    }
+}
+
+impl<C, const NI: usize, const NO: usize> Actor<C, NI, NO>
+where
+    C: Update,
+{
+    /// Gathers all the inputs from other [Actor] outputs without blocking
+    ///
+    /// Returns `false` if some inputs have not received their data yet
+    pub(super) fn try_collect(&mut self) -> Result<bool> {
+        if let Some(inputs) = &mut self.inputs {
+            let mut ready = true;
+            for input in inputs.iter_mut() {
+                ready &= input.try_recv()?;
+            }
+            if !ready {
+                return Ok(false);
             }
-            if NO >= NI {
-                inner(outputs).await.map(|result| !result.is_empty())
-            } else {
-                let mut a = true;
-                for _ in 0..NI / NO {
-                    a = a && inner(outputs).await.map(|result| !result.is_empty())?;
+            inputs.iter_mut().for_each(|input| input.flush());
+        }
+        Ok(true)
+    }
+    /// Sends the outputs to other [Actor] inputs without blocking
+    ///
+    /// `sent` keeps track of the outputs that have already been sent
+    /// and only the bootstrapped outputs are sent if `bootstrap` is `true`.
+    /// Returns `false` if some outputs are still waiting to be sent
+    pub(super) fn try_distribute(&mut self, sent: &mut Vec<bool>, bootstrap: bool) -> Result<bool> {
+        let Some(outputs) = &mut self.outputs else {
+            return Ok(true);
+        };
+        sent.resize(outputs.len(), false);
+        for (output, sent) in outputs.iter_mut().zip(sent.iter_mut()) {
+            if !*sent {
+                *sent = (bootstrap && !output.bootstrap()) || output.try_send()?;
+                if *sent && bootstrap && output.bootstrap() {
+                    interface::print_info(
+                        format!("{} bootstrapped", output.highlight()),
+                        None::<&dyn std::error::Error>,
+                    )
                 }
-                Ok(a)
             }
+        }
+        if sent.iter().all(|sent| *sent) {
+            sent.clear();
+            Ok(true)
         } else {
             Ok(false)
         }
     }
+    /// Updates the client state
+    pub(super) fn blocking_update(&mut self) {
+        super::lock_client(&self.client).update();
+    }
+    /// Sets the sample the outputs are sent at
+    #[cfg(feature = "filing")]
+    pub(super) fn at(&mut self, sample: usize) {
+        self.outputs
+            .iter_mut()
+            .flatten()
+            .for_each(|output| output.at(sample));
+    }
 }
 
 /* impl<C, const NI: usize, const NO: usize> Actor<C, NI, NO>
@@ -237,14 +603,18 @@ where
         ActorOutput::new(self, ActorOutputBuilder::new(1))
     }
 }
-impl<U, C, const NI: usize, const NO: usize> AddActorInput<U, C, NI, NO> for Actor<C, NI, NO>
+impl<C, const NI: usize, const NO: usize> Actor<C, NI, NO>
 where
-    C: Read<U> + 'static,
-    U: 'static + UniqueIdentifier,
+    C: Update + 'static,
 {
-    /// Adds an input to an actor
-    fn add_input(&mut self, rx: flume::Receiver<Data<U>>, hash: u64) {
-        let input: Input<C, U, NI> = Input::new(rx, self.client.clone(), hash);
+    /// Adds an input to an actor which data is received from a channel of `Data<R>`
+    pub(crate) fn add_input_from<U, R>(&mut self, rx: flume::Receiver<Data<R>>, hash: u64)
+    where
+        C: Read<U>,
+        U: 'static + UniqueIdentifier,
+        R: 'static + UniqueIdentifier<DataType = U::DataType>,
+    {
+        let input: Input<C, U, NI, R> = Input::new(rx, self.client.clone(), hash);
         if let Some(ref mut inputs) = self.inputs {
             inputs.push(Box::new(input));
         } else {
@@ -252,6 +622,16 @@ where
         }
     }
 }
+impl<U, C, const NI: usize, const NO: usize> AddActorInput<U, C, NI, NO> for Actor<C, NI, NO>
+where
+    C: Read<U> + 'static,
+    U: 'static + UniqueIdentifier,
+{
+    /// Adds an input to an actor
+    fn add_input(&mut self, rx: flume::Receiver<Data<U>>, hash: u64) {
+        self.add_input_from::<U, U>(rx, hash);
+    }
+}
 /*
 impl<C, const NI: usize, const NO: usize> Drop for Actor<C, NI, NO>
 where
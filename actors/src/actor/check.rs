@@ -17,6 +17,10 @@ where
         match self.inputs {
             Some(_) if NI == 0 => Err(ActorError::SomeInputsZeroRate(Who::who(self))),
             None if NI > 0 => Err(ActorError::NoInputsPositiveRate(Who::who(self))),
+            // a rate-based actor with only sample-and-hold inputs would never wait for data
+            Some(ref inputs) if !self.event && inputs.iter().all(|input| input.is_held()) => {
+                Err(ActorError::HoldOnly(Who::who(self)))
+            }
             _ => Ok(()),
         }?;
         Ok(())
@@ -0,0 +1,62 @@
+//! Type-erased serialization of clients and data for model checkpoints
+
+use bincode::{
+    config,
+    error::{DecodeError, EncodeError},
+};
+use interface::Update;
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::serde_with::{Deserialized, Serialized};
+
+/// Serializer and deserializer of a value of type `T`
+pub(crate) struct Codec<T> {
+    encode: fn(&T) -> Result<Vec<u8>, EncodeError>,
+    decode: fn(&[u8]) -> Result<T, DecodeError>,
+}
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Codec<T> {}
+impl<T> Codec<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn new() -> Self {
+        Self {
+            encode: |value| bincode::serde::encode_to_vec(value, config::standard()),
+            decode: |bytes| {
+                bincode::serde::decode_from_slice(bytes, config::standard()).map(|(value, _)| value)
+            },
+        }
+    }
+}
+impl<C> Codec<Arc<Mutex<C>>>
+where
+    C: Update + Serialize + DeserializeOwned,
+{
+    /// Creates the codec of the client of an actor
+    ///
+    /// The client is serialized with the [serde](super::serde_with) functions of the actor
+    pub fn client() -> Self {
+        Self {
+            encode: |client| bincode::serde::encode_to_vec(Serialized(client), config::standard()),
+            decode: |bytes| {
+                bincode::serde::decode_from_slice(bytes, config::standard())
+                    .map(|(Deserialized(client), _)| client)
+            },
+        }
+    }
+}
+impl<T> Codec<T> {
+    pub fn encode(&self, value: &T) -> Result<Vec<u8>, EncodeError> {
+        (self.encode)(value)
+    }
+    pub fn decode(&self, bytes: &[u8]) -> Result<T, DecodeError> {
+        (self.decode)(bytes)
+    }
+}
@@ -1,53 +1,110 @@
 use super::S;
-use crate::{ActorError, Result};
+use crate::{actor::lock_client, model::cancel::Cancellation, ActorError, Result};
 use async_trait::async_trait;
-use flume::Receiver;
+use flume::{Receiver, TryRecvError};
 use interface::{Read, UniqueIdentifier, Who};
-use std::any::type_name;
+use std::any::{type_name, Any};
 use std::fmt::Debug;
 use std::{fmt::Display, sync::Arc};
 use tokio::sync::Mutex;
 
 /// [Actor](crate::Actor)s input
+///
+/// The data is received from a channel of `Data<R>`, `R` defaults to the input UID
 #[derive(Clone)]
-pub(crate) struct Input<C, U, const N: usize>
+pub(crate) struct Input<C, U, const N: usize, R = U>
 where
     U: UniqueIdentifier,
     C: Read<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
-    rx: Receiver<S<U>>,
+    rx: Receiver<S<R>>,
     client: Arc<Mutex<C>>,
     hash: u64,
+    buffer: Option<S<U>>,
+    len: Option<usize>,
+    hold: Option<Option<S<U>>>,
 }
-impl<C, U, const N: usize> Input<C, U, N>
+impl<C, U, const N: usize, R> Input<C, U, N, R>
 where
     U: UniqueIdentifier,
     C: Read<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     /// Creates a new intput from a [Receiver], an [Actor] client and an identifier [hash]
-    pub fn new(rx: Receiver<S<U>>, client: Arc<Mutex<C>>, hash: u64) -> Self {
-        Self { rx, client, hash }
+    pub fn new(rx: Receiver<S<R>>, client: Arc<Mutex<C>>, hash: u64) -> Self {
+        Self {
+            rx,
+            client,
+            hash,
+            buffer: None,
+            len: <U as UniqueIdentifier>::LEN,
+            hold: None,
+        }
+    }
+    /// Sets the number of elements of the input data
+    pub fn sized(&mut self, len: usize) -> &mut Self {
+        self.len = Some(len);
+        self
+    }
+    /// Makes the input a sample-and-hold input
+    pub fn hold(&mut self) -> &mut Self {
+        self.hold.get_or_insert(None);
+        self
+    }
+    /// Drains the channel, keeping only the latest data
+    ///
+    /// Returns `None` if the channel is empty
+    fn latest(&mut self) -> Result<Option<S<U>>> {
+        let mut latest = None;
+        loop {
+            match self.rx.try_recv() {
+                Ok(data) => latest = Some(data.transmute()),
+                Err(TryRecvError::Empty) => return Ok(latest),
+                Err(TryRecvError::Disconnected) if latest.is_some() => return Ok(latest),
+                Err(TryRecvError::Disconnected) => {
+                    return Err(ActorError::DropRecv {
+                        msg: format!("input {} to {}", type_name::<U>(), type_name::<C>()),
+                        source: flume::RecvError::Disconnected,
+                    })
+                }
+            }
+        }
+    }
+    /// Returns the data of a sample-and-hold input: the latest data or the data last received
+    ///
+    /// The data last received is held after the channel has been disconnected
+    fn held(&mut self) -> Option<S<U>> {
+        let latest = self.latest().ok().flatten();
+        let last = self.hold.get_or_insert(None);
+        if latest.is_some() {
+            *last = latest;
+        }
+        last.clone()
     }
 }
-impl<C, U, const N: usize> Who<U> for Input<C, U, N>
+impl<C, U, const N: usize, R> Who<U> for Input<C, U, N, R>
 where
     C: Read<U>,
     U: UniqueIdentifier,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
 }
-impl<C, U, const N: usize> Display for Input<C, U, N>
+impl<C, U, const N: usize, R> Display for Input<C, U, N, R>
 where
     C: Read<U>,
     U: UniqueIdentifier,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "#{:>19}: {}", self.hash, Who::who(self))
     }
 }
-impl<C, U, const N: usize> Debug for Input<C, U, N>
+impl<C, U, const N: usize, R> Debug for Input<C, U, N, R>
 where
     C: Read<U> + Debug,
     U: UniqueIdentifier,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Input")
@@ -64,9 +121,32 @@ pub(crate) trait InputObject: Display + Send + Sync {
     async fn recv(&mut self) -> Result<()>;
     /// Returns the input UID
     fn who(&self) -> String;
+    /// Returns the physical units of the input data
+    fn units(&self) -> Option<&'static str>;
+    /// Returns the number of elements of the input data, if known
+    fn size(&self) -> Option<usize>;
     /// Gets the input hash
     fn get_hash(&self) -> u64;
     fn capacity(&self) -> Option<usize>;
+    /// Receives output data without blocking
+    ///
+    /// The data is kept in a buffer until [InputObject::flush] is called,
+    /// returns `false` if no data has been received yet
+    fn try_recv(&mut self) -> Result<bool>;
+    /// Reads the buffered data into the client
+    fn flush(&mut self);
+    /// Receives the latest output data without blocking
+    ///
+    /// The data is kept in a buffer until [InputObject::flush] is called,
+    /// returns `true` if some new data has been received
+    fn try_latest(&mut self) -> Result<bool>;
+    /// Waits for new output data
+    ///
+    /// The data is kept in a buffer until [InputObject::flush] is called
+    async fn wait(&mut self) -> Result<()>;
+    /// Returns `true` if the input is a sample-and-hold input
+    fn is_held(&self) -> bool;
+    fn as_mut_any(&mut self) -> &mut dyn Any;
 }
 
 impl Debug for Box<dyn InputObject> {
@@ -76,25 +156,37 @@ impl Debug for Box<dyn InputObject> {
 }
 
 #[async_trait]
-impl<C, U, const N: usize> InputObject for Input<C, U, N>
+impl<C, U, const N: usize, R> InputObject for Input<C, U, N, R>
 where
-    C: Read<U>,
-    U: UniqueIdentifier,
+    C: Read<U> + 'static,
+    U: UniqueIdentifier + 'static,
+    R: UniqueIdentifier<DataType = U::DataType> + 'static,
 {
     async fn recv(&mut self) -> Result<()> {
+        if self.hold.is_some() {
+            if let Some(data) = self.held() {
+                self.client.lock().await.read(data);
+            }
+            return Ok(());
+        }
         // log::debug!("{} receiving", Who::highlight(self));
+        let data = Cancellation::until_cancelled(self.rx.recv_async())
+            .await
+            .ok_or_else(|| {
+                ActorError::Cancelled(format!(
+                    "input {} to {}",
+                    type_name::<U>(),
+                    type_name::<C>()
+                ))
+            })?
+            .map_err(|e| ActorError::DropRecv {
+                msg: format!("input {} to {}", type_name::<U>(), type_name::<C>()), //Who::lite(self),
+                source: e,
+            })?;
         // log::debug!("{} receiving (locking client)", Who::who(self));
         let mut client = self.client.lock().await;
         // log::debug!("{} receiving (client locked)", Who::who(self));
-        (*client).read(
-            self.rx
-                .recv_async()
-                .await
-                .map_err(|e| ActorError::DropRecv {
-                    msg: format!("input {} to {}", type_name::<U>(), type_name::<C>()), //Who::lite(self),
-                    source: e,
-                })?,
-        );
+        (*client).read(data.transmute());
         log::debug!(
             "{} RECV@{N}: {} - {}",
             self.hash,
@@ -106,10 +198,85 @@ where
     fn who(&self) -> String {
         Who::who(self)
     }
+    fn units(&self) -> Option<&'static str> {
+        <U as UniqueIdentifier>::UNITS
+    }
+    fn size(&self) -> Option<usize> {
+        self.len
+    }
     fn get_hash(&self) -> u64 {
         self.hash
     }
     fn capacity(&self) -> Option<usize> {
         self.rx.capacity()
     }
+    fn try_recv(&mut self) -> Result<bool> {
+        if self.buffer.is_some() {
+            return Ok(true);
+        }
+        if self.hold.is_some() {
+            self.buffer = self.held();
+            return Ok(true);
+        }
+        match self.rx.try_recv() {
+            Ok(data) => {
+                self.buffer = Some(data.transmute());
+                Ok(true)
+            }
+            Err(TryRecvError::Empty) => Ok(false),
+            Err(TryRecvError::Disconnected) => Err(ActorError::DropRecv {
+                msg: format!("input {} to {}", type_name::<U>(), type_name::<C>()),
+                source: flume::RecvError::Disconnected,
+            }),
+        }
+    }
+    fn flush(&mut self) {
+        if let Some(data) = self.buffer.take() {
+            lock_client(&self.client).read(data);
+            log::debug!(
+                "{} RECV@{N}: {} - {}",
+                self.hash,
+                type_name::<U>(),
+                type_name::<C>()
+            );
+        }
+    }
+    fn try_latest(&mut self) -> Result<bool> {
+        match self.latest() {
+            Ok(Some(data)) => {
+                self.buffer = Some(data);
+                Ok(true)
+            }
+            Ok(None) => Ok(self.buffer.is_some()),
+            Err(_) if self.buffer.is_some() => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+    async fn wait(&mut self) -> Result<()> {
+        if self.buffer.is_none() {
+            self.buffer = Some(
+                Cancellation::until_cancelled(self.rx.recv_async())
+                    .await
+                    .ok_or_else(|| {
+                        ActorError::Cancelled(format!(
+                            "input {} to {}",
+                            type_name::<U>(),
+                            type_name::<C>()
+                        ))
+                    })?
+                    .map_err(|e| ActorError::DropRecv {
+                        msg: format!("input {} to {}", type_name::<U>(), type_name::<C>()),
+                        source: e,
+                    })?
+                    .transmute(),
+            );
+        }
+        Ok(())
+    }
+    fn is_held(&self) -> bool {
+        self.hold.is_some()
+    }
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
 }
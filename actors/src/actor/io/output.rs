@@ -1,90 +1,262 @@
 use super::S;
-use crate::{ActorError, Result};
+#[cfg(feature = "profiling")]
+use crate::model::profiling::{OutputProfile, OutputTimer};
+#[cfg(feature = "filing")]
+use crate::{
+    actor::codec::Codec,
+    model::{
+        checkpoint::{CheckpointError, OutputCheckpoint},
+        record::{RecordError, Recorder, Tap},
+    },
+};
+use crate::{
+    actor::lock_client, framework::network::OutputPolicy, model::cancel::Cancellation, ActorError,
+    Result,
+};
 use async_trait::async_trait;
-use flume::Sender;
+#[cfg(feature = "filing")]
+use bincode::error::EncodeError;
+use flume::{Receiver, Sender, TrySendError};
 use futures::future::join_all;
 use futures::stream::FuturesUnordered;
 use interface::{Assoc, UniqueIdentifier, Who, Write};
 use std::any::{type_name, Any};
+#[cfg(feature = "filing")]
+use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::{fmt::Display, sync::Arc};
+use std::marker::PhantomData;
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 use tokio::sync::Mutex;
 
-pub(crate) struct OutputBuilder<C, U, const N: usize>
+pub(crate) struct OutputBuilder<C, U, const N: usize, R = U>
 where
     U: UniqueIdentifier,
     C: Write<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
-    tx: Vec<Sender<S<U>>>,
+    tx: Vec<Sender<S<R>>>,
     client: Arc<Mutex<C>>,
     bootstrap: bool,
+    policy: OutputPolicy,
+    rx: Vec<Receiver<S<R>>>,
+    uid: PhantomData<U>,
 }
-impl<C, U, const N: usize> OutputBuilder<C, U, N>
+impl<C, U, const N: usize, R> OutputBuilder<C, U, N, R>
 where
     U: UniqueIdentifier,
     C: Write<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     pub fn new(client: Arc<Mutex<C>>) -> Self {
         Self {
             tx: Vec::new(),
             client,
             bootstrap: false,
+            policy: OutputPolicy::Block,
+            rx: Vec::new(),
+            uid: PhantomData,
         }
     }
-    pub fn senders(self, tx: Vec<Sender<S<U>>>) -> Self {
+    pub fn senders(self, tx: Vec<Sender<S<R>>>) -> Self {
         Self { tx, ..self }
     }
     pub fn bootstrap(self, bootstrap: bool) -> Self {
         Self { bootstrap, ..self }
     }
-    pub fn build(self) -> Output<C, U, N> {
+    /// Sets the drop policy, the receivers are used to drop the oldest data of the channels
+    pub fn policy(self, policy: OutputPolicy, rx: &[Receiver<S<R>>]) -> Self {
+        Self {
+            policy,
+            rx: rx.to_vec(),
+            ..self
+        }
+    }
+    pub fn build(self) -> Output<C, U, N, R> {
+        let rx = match self.policy {
+            OutputPolicy::DropOldest | OutputPolicy::KeepLatest => self.rx,
+            _ => Vec::new(),
+        };
         Output {
             data: None,
             tx: self.tx,
             client: self.client,
             bootstrap: self.bootstrap,
+            policy: self.policy,
+            rx,
+            dropped: Arc::new(AtomicUsize::new(0)),
             hash: 0,
+            unsent: Vec::new(),
+            len: <U as UniqueIdentifier>::LEN,
+            #[cfg(feature = "filing")]
+            codec: None,
+            #[cfg(feature = "filing")]
+            history: VecDeque::new(),
+            #[cfg(feature = "filing")]
+            tap: None,
+            #[cfg(feature = "filing")]
+            sample: None,
+            #[cfg(feature = "profiling")]
+            timer: Default::default(),
         }
     }
 }
 
 /// [Actor](crate::Actor)s output
+///
+/// The data is sent through channels of `Data<R>`, `R` defaults to the output UID
 #[derive(Clone)]
-pub(crate) struct Output<C, U, const N: usize>
+pub(crate) struct Output<C, U, const N: usize, R = U>
 where
     U: UniqueIdentifier,
     C: Write<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     data: Option<S<U>>,
-    tx: Vec<Sender<S<U>>>,
+    tx: Vec<Sender<S<R>>>,
     client: Arc<Mutex<C>>,
     bootstrap: bool,
+    policy: OutputPolicy,
+    /// receivers of the channels the oldest data is dropped from
+    rx: Vec<Receiver<S<R>>>,
+    /// number of data dropped because a channel was full
+    dropped: Arc<AtomicUsize>,
     hash: u64,
+    unsent: Vec<usize>,
+    len: Option<usize>,
+    #[cfg(feature = "filing")]
+    codec: Option<Codec<Assoc<U>>>,
+    /// the last data sent, as many as the data waiting in the fullest channel
+    #[cfg(feature = "filing")]
+    history: VecDeque<S<U>>,
+    /// the [recorder](crate::model::record) of the data sent
+    #[cfg(feature = "filing")]
+    tap: Option<Tap<Assoc<U>>>,
+    /// the sample the actor is at
+    #[cfg(feature = "filing")]
+    sample: Option<usize>,
+    #[cfg(feature = "profiling")]
+    timer: OutputTimer,
 }
-impl<C, U, const N: usize> Output<C, U, N>
+impl<C, U, const N: usize, R> Output<C, U, N, R>
 where
     U: UniqueIdentifier,
     C: Write<U>,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
     /// Creates a new output from a [Sender] and data [Default]
-    pub fn builder(client: Arc<Mutex<C>>) -> OutputBuilder<C, U, N> {
+    pub fn builder(client: Arc<Mutex<C>>) -> OutputBuilder<C, U, N, R> {
         OutputBuilder::new(client)
     }
-    pub fn tx_push(&mut self, mut tx: Vec<Sender<S<U>>>) -> &mut Self {
+    pub fn tx_push(&mut self, mut tx: Vec<Sender<S<R>>>, rx: &[Receiver<S<R>>]) -> &mut Self {
         self.tx.append(&mut tx);
+        if !self.rx.is_empty() {
+            self.rx.extend_from_slice(rx);
+        }
+        self
+    }
+    /// Sends the data to all the receivers without blocking, according to the drop policy
+    fn offer(&self, data: &S<U>) -> Result<()> {
+        let disconnected = || ActorError::DropSend {
+            msg: format!("output {} from {}", type_name::<U>(), type_name::<C>()),
+            source: flume::SendError(()),
+        };
+        for (i, tx) in self.tx.iter().enumerate() {
+            // the output holds a receiver of the channel, the input is gone if it is the only one
+            if !self.rx.is_empty() && tx.receiver_count() < 2 {
+                return Err(disconnected());
+            }
+            let mut data = data.clone().transmute::<R>();
+            loop {
+                match tx.try_send(data) {
+                    Ok(_) => break,
+                    Err(TrySendError::Full(rejected)) => {
+                        match self.rx.get(i) {
+                            Some(rx) => {
+                                // the oldest data may have been received in the meantime
+                                if rx.try_recv().is_ok() {
+                                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                                }
+                                data = rejected;
+                            }
+                            None => {
+                                self.dropped.fetch_add(1, Ordering::Relaxed);
+                                break;
+                            }
+                        }
+                    }
+                    Err(TrySendError::Disconnected(_)) => return Err(disconnected()),
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Sets the number of elements of the output data
+    pub fn sized(&mut self, len: usize) -> &mut Self {
+        self.len = Some(len);
+        self
+    }
+    /// Allows the data in flight to be saved into a [model checkpoint](crate::model::checkpoint)
+    #[cfg(feature = "filing")]
+    pub fn checkpointable(&mut self) -> &mut Self
+    where
+        Assoc<U>: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.codec = Some(Codec::new());
         self
     }
+    /// Records the data sent with a [Recorder]
+    #[cfg(feature = "filing")]
+    pub fn recorded(
+        &mut self,
+        recorder: &Recorder,
+        producer: String,
+    ) -> std::result::Result<&mut Self, RecordError>
+    where
+        Assoc<U>: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.tap = Some(recorder.tap::<U>(producer, N)?);
+        Ok(self)
+    }
+    /// Keeps track of the data that is about to be sent
+    #[cfg(feature = "filing")]
+    fn record(&mut self) -> Result<()> {
+        if let (Some(tap), Some(data)) = (self.tap.as_mut(), self.data.as_ref()) {
+            tap.record(data, self.sample).map_err(|e| {
+                ActorError::Record(
+                    format!("output {} from {}", type_name::<U>(), type_name::<C>()),
+                    e,
+                )
+            })?;
+        }
+        let (Some(_), Some(data)) = (self.codec.as_ref(), self.data.as_ref()) else {
+            return Ok(());
+        };
+        let n = self.tx.iter().map(|tx| tx.len()).max().unwrap_or_default();
+        self.history.push_back(data.clone());
+        while self.history.len() > n + 1 {
+            self.history.pop_front();
+        }
+        Ok(())
+    }
 }
-impl<C, U, const N: usize> Who<U> for Output<C, U, N>
+impl<C, U, const N: usize, R> Who<U> for Output<C, U, N, R>
 where
     C: Write<U>,
     U: UniqueIdentifier,
+    R: UniqueIdentifier<DataType = U::DataType>,
 {
 }
-impl<C, U, const N: usize> Display for Output<C, U, N>
+impl<C, U, const N: usize, R> Display for Output<C, U, N, R>
 where
     C: Write<U> + Send + 'static,
     U: UniqueIdentifier + 'static,
+    R: UniqueIdentifier<DataType = U::DataType> + 'static,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -97,10 +269,11 @@ where
         )
     }
 }
-impl<C, U, const N: usize> Debug for Output<C, U, N>
+impl<C, U, const N: usize, R> Debug for Output<C, U, N, R>
 where
     C: Write<U> + Debug,
     U: UniqueIdentifier,
+    R: UniqueIdentifier<DataType = U::DataType>,
     <U as UniqueIdentifier>::DataType: std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -120,9 +293,39 @@ pub(crate) trait OutputObject: Any + Display + Send + Sync {
     fn bootstrap(&self) -> bool;
     fn len(&self) -> usize;
     fn who(&self) -> String;
+    /// Returns the physical units of the output data
+    fn units(&self) -> Option<&'static str>;
+    /// Returns the number of elements of the output data, if known
+    fn size(&self) -> Option<usize>;
     fn highlight(&self) -> String;
+    /// Returns the drop policy and the number of data dropped so far,
+    /// [None] if the output is blocking
+    fn dropped(&self) -> Option<(OutputPolicy, Arc<AtomicUsize>)>;
     fn set_hash(&mut self, hash: u64);
     fn get_hash(&self) -> u64;
+    /// Sends output data without blocking
+    ///
+    /// The data is written once and sent to the receivers with an empty slot in their channel,
+    /// returns `true` when all the receivers have been served
+    fn try_send(&mut self) -> Result<bool>;
+    /// Sets the sample the actor is at, the sample of the [recorded](crate::model::record) data
+    #[cfg(feature = "filing")]
+    fn at(&mut self, sample: usize);
+    /// Returns `true` if some data has been sent but not received yet
+    #[cfg(feature = "filing")]
+    fn in_flight(&self) -> bool;
+    /// Serializes the data in flight
+    ///
+    /// Returns [None] if the output is not checkpointable
+    #[cfg(feature = "filing")]
+    fn checkpoint(&self) -> Option<std::result::Result<OutputCheckpoint, EncodeError>>;
+    /// Sends the data in flight saved in the checkpoint
+    #[cfg(feature = "filing")]
+    fn restore(&mut self, checkpoint: OutputCheckpoint)
+        -> std::result::Result<(), CheckpointError>;
+    /// Returns the output timings
+    #[cfg(feature = "profiling")]
+    fn profile(&self) -> OutputProfile;
     // fn as_any(&self) -> &dyn Any;
     fn as_mut_any(&mut self) -> &mut dyn Any;
 }
@@ -134,30 +337,51 @@ impl Debug for Box<dyn OutputObject> {
 }
 
 #[async_trait]
-impl<C, U, const N: usize> OutputObject for Output<C, U, N>
+impl<C, U, const N: usize, R> OutputObject for Output<C, U, N, R>
 where
     C: Write<U> + 'static,
     U: UniqueIdentifier + 'static,
+    R: UniqueIdentifier<DataType = U::DataType> + 'static,
     Assoc<U>: Send + Sync,
 {
     /// Sends output data
     async fn send(&mut self) -> Result<()> {
         self.data = (*self.client.lock().await).write();
+        #[cfg(feature = "filing")]
+        self.record()?;
         if let Some(data) = &self.data {
             // log::debug!("{} sending", Who::highlight(self));
-            let futures: FuturesUnordered<_> = self
-                .tx
-                .iter()
-                .map(|tx| tx.send_async(data.clone()))
-                .collect();
-            join_all(futures)
-                .await
-                .into_iter()
-                .collect::<std::result::Result<Vec<()>, flume::SendError<_>>>()
-                .map_err(|_| ActorError::DropSend {
-                    msg: format!("output {} from {}", type_name::<U>(), type_name::<C>()), //Who::lite(self),
-                    source: flume::SendError(()),
-                })?;
+            #[cfg(feature = "profiling")]
+            let now = std::time::Instant::now();
+            if self.policy == OutputPolicy::Block {
+                let futures: FuturesUnordered<_> = self
+                    .tx
+                    .iter()
+                    .map(|tx| tx.send_async(data.clone().transmute()))
+                    .collect();
+                Cancellation::until_cancelled(join_all(futures))
+                    .await
+                    .ok_or_else(|| {
+                        ActorError::Cancelled(format!(
+                            "output {} from {}",
+                            type_name::<U>(),
+                            type_name::<C>()
+                        ))
+                    })?
+                    .into_iter()
+                    .collect::<std::result::Result<Vec<()>, flume::SendError<_>>>()
+                    .map_err(|_| ActorError::DropSend {
+                        msg: format!("output {} from {}", type_name::<U>(), type_name::<C>()), //Who::lite(self),
+                        source: flume::SendError(()),
+                    })?;
+            } else {
+                self.offer(data)?;
+            }
+            #[cfg(feature = "profiling")]
+            {
+                self.timer.send += now.elapsed();
+                self.timer.count += 1;
+            }
             log::debug!(
                 "{} SEND@{N}: {} - {}",
                 self.hash,
@@ -189,9 +413,18 @@ where
     fn who(&self) -> String {
         Who::who(self)
     }
+    fn units(&self) -> Option<&'static str> {
+        <U as UniqueIdentifier>::UNITS
+    }
+    fn size(&self) -> Option<usize> {
+        self.len
+    }
     fn highlight(&self) -> String {
         Who::highlight(self)
     }
+    fn dropped(&self) -> Option<(OutputPolicy, Arc<AtomicUsize>)> {
+        (self.policy != OutputPolicy::Block).then(|| (self.policy, self.dropped.clone()))
+    }
     fn len(&self) -> usize {
         self.tx.len()
     }
@@ -201,6 +434,125 @@ where
     fn get_hash(&self) -> u64 {
         self.hash
     }
+    fn try_send(&mut self) -> Result<bool> {
+        if self.unsent.is_empty() {
+            self.data = lock_client(&self.client).write();
+            if self.data.is_none() {
+                log::debug!(
+                    "{} SEND-DROP: {} - {}",
+                    self.hash,
+                    type_name::<U>(),
+                    type_name::<C>()
+                );
+                for tx in std::mem::take(&mut self.tx) {
+                    drop(tx);
+                }
+                return Err(ActorError::Disconnected(format!(
+                    "output {} from {}",
+                    type_name::<U>(),
+                    type_name::<C>()
+                )));
+            }
+            #[cfg(feature = "filing")]
+            self.record()?;
+            if self.policy != OutputPolicy::Block {
+                if let Some(data) = &self.data {
+                    self.offer(data)?;
+                }
+                return Ok(true);
+            }
+            self.unsent = (0..self.tx.len()).collect();
+        }
+        let Some(data) = &self.data else {
+            return Ok(true);
+        };
+        let mut unsent = vec![];
+        for i in std::mem::take(&mut self.unsent) {
+            match self.tx[i].try_send(data.clone().transmute()) {
+                Ok(_) => (),
+                Err(TrySendError::Full(_)) => unsent.push(i),
+                Err(TrySendError::Disconnected(_)) => {
+                    return Err(ActorError::DropSend {
+                        msg: format!("output {} from {}", type_name::<U>(), type_name::<C>()),
+                        source: flume::SendError(()),
+                    })
+                }
+            }
+        }
+        self.unsent = unsent;
+        if self.unsent.is_empty() {
+            log::debug!(
+                "{} SEND@{N}: {} - {}",
+                self.hash,
+                type_name::<U>(),
+                type_name::<C>()
+            );
+        }
+        Ok(self.unsent.is_empty())
+    }
+    #[cfg(feature = "profiling")]
+    fn profile(&self) -> OutputProfile {
+        OutputProfile::new(Who::who(self), self.hash, &self.timer)
+    }
+    #[cfg(feature = "filing")]
+    fn at(&mut self, sample: usize) {
+        self.sample = Some(sample);
+    }
+    #[cfg(feature = "filing")]
+    fn in_flight(&self) -> bool {
+        self.tx.iter().any(|tx| !tx.is_empty())
+    }
+    #[cfg(feature = "filing")]
+    fn checkpoint(&self) -> Option<std::result::Result<OutputCheckpoint, EncodeError>> {
+        let codec = self.codec.as_ref()?;
+        let lens: Vec<_> = self.tx.iter().map(|tx| tx.len()).collect();
+        let n = lens.iter().max().copied().unwrap_or_default();
+        let history = self
+            .history
+            .iter()
+            .skip(self.history.len().saturating_sub(n))
+            .map(|data| codec.encode(data))
+            .collect::<std::result::Result<Vec<_>, _>>();
+        Some(history.map(|history| OutputCheckpoint {
+            hash: self.hash,
+            history,
+            lens,
+        }))
+    }
+    #[cfg(feature = "filing")]
+    fn restore(
+        &mut self,
+        checkpoint: OutputCheckpoint,
+    ) -> std::result::Result<(), CheckpointError> {
+        let mismatch = || {
+            CheckpointError::Mismatch(format!(
+                "output {} from {} cannot be restored",
+                type_name::<U>(),
+                type_name::<C>()
+            ))
+        };
+        let codec = self.codec.as_ref().ok_or_else(mismatch)?;
+        if checkpoint.lens.len() != self.tx.len() {
+            return Err(mismatch());
+        }
+        let history = checkpoint
+            .history
+            .iter()
+            .map(|bytes| codec.decode(bytes).map(S::<U>::new))
+            .collect::<std::result::Result<VecDeque<_>, _>>()?;
+        for (tx, &n) in self.tx.iter().zip(&checkpoint.lens) {
+            if n > history.len() {
+                return Err(mismatch());
+            }
+            for data in history.iter().skip(history.len() - n) {
+                tx.try_send(data.clone().transmute())
+                    .map_err(|_| mismatch())?;
+            }
+        }
+        self.data = history.back().cloned();
+        self.history = history;
+        Ok(())
+    }
     // fn as_any(&self) -> &dyn Any {
     //     self
     // }
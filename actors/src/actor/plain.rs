@@ -2,6 +2,7 @@ use interface::{Update, Who};
 
 use crate::{
     actor::io::{InputObject, OutputObject},
+    clock::Clocks,
     graph::Graph,
     trim,
 };
@@ -15,6 +16,9 @@ pub struct IOData {
     pub(crate) hash: u64,
     pub(crate) n: usize,
     pub(crate) rate: usize,
+    pub(crate) units: Option<String>,
+    pub(crate) size: Option<usize>,
+    pub(crate) hold: bool,
 }
 impl IOData {
     /// Creates a plain input or output instance
@@ -24,8 +28,26 @@ impl IOData {
             hash,
             n,
             rate,
+            units: None,
+            size: None,
+            hold: false,
         }
     }
+    /// Sets the physical units of the data
+    pub fn units(mut self, units: Option<&str>) -> Self {
+        self.units = units.map(|units| units.to_string());
+        self
+    }
+    /// Sets the number of elements of the data
+    pub fn size(mut self, size: Option<usize>) -> Self {
+        self.size = size;
+        self
+    }
+    /// Flags a sample-and-hold input
+    pub fn hold(mut self, hold: bool) -> Self {
+        self.hold = hold;
+        self
+    }
     /// Returns the I/O hash #
     pub fn hash(&self) -> u64 {
         self.hash
@@ -90,8 +112,38 @@ impl IO {
             IO::Unbounded(data) => data.rate,
         }
     }
+    /// Returns the [IO] physical units
+    pub fn units(&self) -> Option<&str> {
+        match self {
+            IO::Bootstrap(data) => data.units.as_deref(),
+            IO::Regular(data) => data.units.as_deref(),
+            IO::Unbounded(data) => data.units.as_deref(),
+        }
+    }
+    /// Returns the number of elements of the [IO] data
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            IO::Bootstrap(data) => data.size,
+            IO::Regular(data) => data.size,
+            IO::Unbounded(data) => data.size,
+        }
+    }
+    /// Returns `true` if the [IO] is a sample-and-hold input
+    pub fn is_held(&self) -> bool {
+        match self {
+            IO::Bootstrap(data) => data.hold,
+            IO::Regular(data) => data.hold,
+            IO::Unbounded(data) => data.hold,
+        }
+    }
 }
 impl IO {
+    /// Appends a note to the [IO] name
+    #[cfg(feature = "profiling")]
+    pub(crate) fn annotate(&mut self, note: impl std::fmt::Display) {
+        let (IO::Bootstrap(data) | IO::Regular(data) | IO::Unbounded(data)) = self;
+        data.name = format!("{}\\n{}", data.name, note);
+    }
     pub fn hash(&self) -> u64 {
         match self {
             IO::Bootstrap(data) => data.hash(),
@@ -120,6 +172,8 @@ pub struct PlainActorBuilder {
     pub(crate) hash: u64,
     pub(crate) image: Option<String>,
     pub(crate) graph: Option<Graph>,
+    pub(crate) clocks: Option<Clocks>,
+    pub(crate) event: bool,
 }
 
 impl PlainActorBuilder {
@@ -143,6 +197,16 @@ impl PlainActorBuilder {
         self.image = Some(image.to_string());
         self
     }
+    /// Sets the [clock domains](crate::clock) of the inputs and outputs
+    pub fn clocks(mut self, clocks: Clocks) -> Self {
+        self.clocks = Some(clocks);
+        self
+    }
+    /// Flags an [event-driven](crate::actor::Actor::event_driven) actor
+    pub fn event(mut self, event: bool) -> Self {
+        self.event = event;
+        self
+    }
     /// Builds a [PlainActor]
     pub fn build(self) -> PlainActor {
         PlainActor {
@@ -152,6 +216,8 @@ impl PlainActorBuilder {
             graph: self.graph,
             hash: self.hash,
             image: self.image,
+            clocks: self.clocks,
+            event: self.event,
         }
     }
 }
@@ -164,6 +230,8 @@ pub struct PlainActor {
     pub(crate) hash: u64,
     pub(crate) image: Option<String>,
     pub(crate) graph: Option<Graph>,
+    pub(crate) clocks: Option<Clocks>,
+    pub(crate) event: bool,
 }
 impl PlainActor {
     /// Creates a new [PlainActorBuilder]
@@ -219,26 +287,35 @@ where
             hash: 0,
             image: actor.image.as_ref().cloned(),
             graph: None,
+            clocks: actor.clocks.clone(),
+            event: actor.event,
         }
     }
 }
 
 impl From<(&Box<dyn InputObject>, usize)> for IO {
     fn from((value, r): (&Box<dyn InputObject>, usize)) -> Self {
+        let data = IOData::new(value.who(), value.get_hash(), 1, r)
+            .units(value.units())
+            .size(value.size())
+            .hold(value.is_held());
         if value.capacity().is_some() {
-            IO::Regular(IOData::new(value.who(), value.get_hash(), 1, r))
+            IO::Regular(data)
         } else {
-            IO::Unbounded(IOData::new(value.who(), value.get_hash(), 1, r))
+            IO::Unbounded(data)
         }
     }
 }
 
 impl From<(&Box<dyn OutputObject>, usize)> for IO {
     fn from((value, r): (&Box<dyn OutputObject>, usize)) -> Self {
+        let data = IOData::new(value.who(), value.get_hash(), value.len(), r)
+            .units(value.units())
+            .size(value.size());
         if value.bootstrap() {
-            IO::Bootstrap(IOData::new(value.who(), value.get_hash(), value.len(), r))
+            IO::Bootstrap(data)
         } else {
-            IO::Regular(IOData::new(value.who(), value.get_hash(), value.len(), r))
+            IO::Regular(data)
         }
     }
 }
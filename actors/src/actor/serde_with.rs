@@ -1,13 +1,13 @@
 use interface::Update;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::sync::Arc;
-use tokio::{sync::Mutex, task};
+use tokio::sync::Mutex;
 
 pub fn serialize<S, C: Update + Serialize>(client: &Arc<Mutex<C>>, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    task::block_in_place(move || client.blocking_lock().serialize(s))
+    super::lock_client(client).serialize(s)
 }
 
 pub fn deserialize<'de, D, C: Update + Deserialize<'de>>(
@@ -19,6 +19,24 @@ where
     Ok(Arc::new(Mutex::new(C::deserialize(deserializer)?)))
 }
 
+/// Client serialized with [serialize]
+#[cfg(feature = "filing")]
+pub(crate) struct Serialized<'a, C>(pub &'a Arc<Mutex<C>>);
+#[cfg(feature = "filing")]
+impl<C: Update + Serialize> Serialize for Serialized<'_, C> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, s)
+    }
+}
+
+/// Client deserialized with [deserialize]
+#[cfg(feature = "filing")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "C: Update + Deserialize<'de>"))]
+pub(crate) struct Deserialized<C: Update>(
+    #[serde(deserialize_with = "deserialize")] pub Arc<Mutex<C>>,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
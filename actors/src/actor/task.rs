@@ -1,9 +1,17 @@
 use std::any::type_name;
 
 use async_trait::async_trait;
-use interface::Update;
+use interface::{Seed, Update};
 
-use crate::framework::model::{Task, TaskError};
+use crate::framework::model::{Stage, Step, StepCursor, Task, TaskError};
+use crate::model::cancel::Cancellation;
+#[cfg(feature = "filing")]
+use crate::model::checkpoint::{ActorCheckpoint, CheckpointError, Checkpointer};
+#[cfg(feature = "profiling")]
+use crate::model::profiling::{ActorProfile, Profiler};
+use crate::model::watchdog::Monitor;
+use crate::ActorError;
+use interface::Who;
 
 use super::{Actor, PlainActor};
 
@@ -35,58 +43,439 @@ where
                 }
             }
         } */
-        self.async_run().await
+        #[cfg(feature = "profiling")]
+        let (plain, start) = (self.as_plain(), std::time::Instant::now());
+        let dropped = self
+            .outputs
+            .iter()
+            .flatten()
+            .filter_map(|output| {
+                output
+                    .dropped()
+                    .map(|(policy, count)| (crate::trim(&output.who()), policy, count))
+            })
+            .collect();
+        self.probe = Monitor::register(self.as_plain(), dropped);
+        let result = self.async_run().await;
+        if let Some(probe) = self.probe.as_deref() {
+            probe.done();
+        }
+        #[cfg(feature = "filing")]
+        Checkpointer::done();
+        #[cfg(feature = "profiling")]
+        Profiler::record(ActorProfile::new(
+            plain,
+            start.elapsed(),
+            &self.timer,
+            self.outputs
+                .iter()
+                .flatten()
+                .map(|output| output.profile())
+                .collect(),
+        ));
+        result
     }
 
     /// Starts the actor infinite loop
+    ///
+    /// Apart from event-driven actors and rate transitions, the loop follows the same sequence
+    /// of samples than [Task::step], starting from the cursor set with [Task::start_from].
+    /// The beginning of each sample is a boundary the model can be paused at
+    /// to take a [checkpoint](crate::model::checkpoint)
     async fn async_run(&mut self) -> Result<()> {
         log::debug!("ACTOR LOOP ({NI}/{NO}): {}", type_name::<C>());
-        let bootstrap = self.bootstrap().await?;
-        match (self.inputs.as_ref(), self.outputs.as_ref()) {
-            (Some(_), Some(_)) => {
-                if NO >= NI {
-                    // Decimation
-                    if !bootstrap {
-                        // bootstrap is applied when decimation is used
-                        // in conjunction with averaging
-                        // When averaging there is a delay of `NO` samples
-                        // to account for the time to iterate and a default
-                        // values is used for the 1st output
-                        // For decimation of the input signal there is no delay
-                        // and the 1st sample goes through unimpeded
-                        self.collect().await?.client.lock().await.update();
-                        self.distribute().await?;
+        if self.event && self.inputs.is_some() {
+            #[cfg(feature = "filing")]
+            Checkpointer::unpausable(Who::who(self));
+            self.bootstrap().await?;
+            // Event-driven
+            loop {
+                self.wait().await?.update().await;
+                self.distribute().await?;
+            }
+        }
+        if let (Some(_), Some(_), Some(mut schedule)) =
+            (self.inputs.as_ref(), self.outputs.as_ref(), self.schedule())
+        {
+            #[cfg(feature = "filing")]
+            Checkpointer::unpausable(Who::who(self));
+            self.bootstrap().await?;
+            // Rate transition between clock domains
+            loop {
+                self.collect().await?.update().await;
+                for _ in 0..schedule.n_outputs() {
+                    self.distribute().await?;
+                }
+            }
+        }
+        fn due(sample: usize, rate: usize) -> bool {
+            rate > 0 && sample.is_multiple_of(rate)
+        }
+        let bootstrap = self
+            .outputs
+            .as_ref()
+            .is_some_and(|outputs| outputs.iter().any(|output| output.bootstrap()));
+        let mut cursor = std::mem::take(&mut self.cursor);
+        loop {
+            let sample = cursor.sample;
+            match cursor.stage {
+                Stage::Start => {
+                    if self.inputs.is_none() && self.outputs.is_none() {
+                        return Ok(());
                     }
-                    loop {
-                        for _ in 0..NO / NI {
-                            self.collect().await?.client.lock().await.update();
+                    // bootstrap is applied when decimation is used
+                    // in conjunction with averaging
+                    // When averaging there is a delay of `NO` samples
+                    // to account for the time to iterate and a default
+                    // values is used for the 1st output
+                    // For decimation of the input signal there is no delay
+                    // and the 1st sample goes through unimpeded
+                    cursor.stage = match (bootstrap, NO >= NI) {
+                        (false, _) => Stage::Bootstrap(0),
+                        (true, true) => Stage::Bootstrap(1),
+                        (true, false) => Stage::Bootstrap(NI / NO),
+                    };
+                }
+                Stage::Bootstrap(0) => cursor.stage = Stage::Collect,
+                Stage::Bootstrap(n) => {
+                    #[cfg(feature = "filing")]
+                    self.at(sample);
+                    self.send_bootstrap().await?;
+                    cursor.stage = Stage::Bootstrap(n - 1);
+                }
+                Stage::Collect => {
+                    #[cfg(feature = "filing")]
+                    Checkpointer::boundary(sample, &*self).await;
+                    match (self.inputs.as_ref(), self.outputs.as_ref()) {
+                        (Some(_), _) if due(sample, NI) => {
+                            self.collect().await?.update().await;
                         }
-                        self.distribute().await?;
-                    }
-                } else {
-                    // Upsampling
-                    loop {
-                        self.collect().await?.client.lock().await.update();
-                        for _ in 0..NI / NO {
-                            self.distribute().await?;
+                        // Initiator
+                        (None, Some(_)) if due(sample, NO) => {
+                            if Cancellation::current() {
+                                log::debug!("{} cancelled", type_name::<C>());
+                                return Ok(());
+                            }
+                            self.update().await;
                         }
+                        _ => (),
+                    }
+                    cursor.stage = Stage::Distribute;
+                }
+                Stage::Distribute => {
+                    let distribute = match (self.inputs.as_ref(), self.outputs.as_ref()) {
+                        (_, None) => false,
+                        // Decimation with bootstrap: the outputs are sent after
+                        // the last update of each outputs sample
+                        (Some(_), Some(_)) if bootstrap && NO >= NI => due(sample + NI, NO),
+                        _ => due(sample, NO),
+                    };
+                    if distribute {
+                        #[cfg(feature = "filing")]
+                        self.at(sample);
+                        self.distribute().await?;
                     }
+                    cursor.sample += 1;
+                    cursor.stage = Stage::Collect;
                 }
             }
-            (None, Some(_)) => loop {
-                // Initiator
-                self.client.lock().await.update();
-                self.distribute().await?;
-            },
-            (Some(_), None) => loop {
-                // Terminator
-                self.collect().await?.client.lock().await.update();
-            },
-            (None, None) => Ok(()),
         }
     }
 
     fn as_plain(&self) -> PlainActor {
         self.into()
     }
+
+    /// Runs the actor loop one sample at a time
+    ///
+    /// At each sample, the inputs are collected and the client is updated if the sample
+    /// is a multiple of the inputs rate `NI`, then the outputs are distributed according
+    /// to the same decimation and upsampling rules than [Task::async_run]
+    fn step(&mut self, k: usize, cursor: &mut StepCursor) -> Result<Step> {
+        fn due(sample: usize, rate: usize) -> bool {
+            rate > 0 && sample.is_multiple_of(rate)
+        }
+        if self.schedule().is_some() {
+            return Err(ActorError::Transition(Who::who(self)).into());
+        }
+        if self.event {
+            return Err(ActorError::EventDriven(Who::who(self)).into());
+        }
+        let bootstrap = self
+            .outputs
+            .as_ref()
+            .is_some_and(|outputs| outputs.iter().any(|output| output.bootstrap()));
+        let mut step = Step::Blocked;
+        while cursor.sample <= k {
+            let sample = cursor.sample;
+            match cursor.stage {
+                Stage::Start => {
+                    if self.inputs.is_none() && self.outputs.is_none() {
+                        return Ok(Step::Done);
+                    }
+                    cursor.stage = match (bootstrap, NO >= NI) {
+                        (false, _) => Stage::Bootstrap(0),
+                        (true, true) => Stage::Bootstrap(1),
+                        (true, false) => Stage::Bootstrap(NI / NO),
+                    };
+                }
+                Stage::Bootstrap(0) => cursor.stage = Stage::Collect,
+                Stage::Bootstrap(n) => {
+                    #[cfg(feature = "filing")]
+                    self.at(sample);
+                    if !self.try_distribute(&mut cursor.sent, true)? {
+                        return Ok(step);
+                    }
+                    cursor.stage = Stage::Bootstrap(n - 1);
+                }
+                Stage::Collect => {
+                    match (self.inputs.as_ref(), self.outputs.as_ref()) {
+                        (Some(_), _) if due(sample, NI) => {
+                            if !self.try_collect()? {
+                                return Ok(step);
+                            }
+                            self.blocking_update();
+                        }
+                        // Initiator
+                        (None, Some(_)) if due(sample, NO) => self.blocking_update(),
+                        _ => (),
+                    }
+                    cursor.stage = Stage::Distribute;
+                }
+                Stage::Distribute => {
+                    let distribute = match (self.inputs.as_ref(), self.outputs.as_ref()) {
+                        (_, None) => false,
+                        // Decimation with bootstrap: the outputs are sent after
+                        // the last update of each outputs sample
+                        (Some(_), Some(_)) if bootstrap && NO >= NI => due(sample + NI, NO),
+                        _ => due(sample, NO),
+                    };
+                    #[cfg(feature = "filing")]
+                    self.at(sample);
+                    if distribute && !self.try_distribute(&mut cursor.sent, false)? {
+                        return Ok(step);
+                    }
+                    cursor.sample += 1;
+                    cursor.stage = Stage::Collect;
+                }
+            }
+            step = Step::Progress;
+        }
+        Ok(step)
+    }
+
+    fn start_from(&mut self, cursor: StepCursor) {
+        self.cursor = cursor;
+    }
+
+    fn into_tasks(self: Box<Self>) -> Vec<Box<dyn Task>> {
+        vec![self]
+    }
+
+    fn seed(&mut self, seed: Seed) {
+        super::lock_client(&self.client).seed(seed.derive(&Who::who(self)));
+    }
+
+    #[cfg(feature = "filing")]
+    fn checkpoint(&self) -> std::result::Result<ActorCheckpoint, CheckpointError> {
+        let name = Who::who(self);
+        let Some(codec) = self.codec.as_ref() else {
+            return Err(CheckpointError::NotSerializable(name));
+        };
+        let client = codec.encode(&self.client)?;
+        let mut outputs = vec![];
+        for output in self
+            .outputs
+            .iter()
+            .flatten()
+            .filter(|output| output.in_flight())
+        {
+            match output.checkpoint() {
+                Some(checkpoint) => outputs.push(checkpoint?),
+                None => {
+                    return Err(CheckpointError::InFlight {
+                        actor: name,
+                        output: output.who(),
+                    })
+                }
+            }
+        }
+        Ok(ActorCheckpoint {
+            name,
+            client,
+            outputs,
+        })
+    }
+
+    #[cfg(feature = "filing")]
+    fn restore(&mut self, checkpoint: ActorCheckpoint) -> std::result::Result<(), CheckpointError> {
+        let Some(codec) = self.codec.as_ref() else {
+            return Err(CheckpointError::NotSerializable(Who::who(self)));
+        };
+        // the client is shared with the inputs of the actor and, possibly, with the application
+        let client = codec.decode(&checkpoint.client)?;
+        std::mem::swap(
+            &mut *super::lock_client(&self.client),
+            &mut *super::lock_client(&client),
+        );
+        for output_checkpoint in checkpoint.outputs {
+            let Some(output) = self
+                .outputs
+                .iter_mut()
+                .flatten()
+                .find(|output| output.get_hash() == output_checkpoint.hash)
+            else {
+                return Err(CheckpointError::Mismatch(format!(
+                    "no output #{} in {}",
+                    output_checkpoint.hash, checkpoint.name
+                )));
+            };
+            output.restore(output_checkpoint)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+
+    enum Command {}
+    impl UniqueIdentifier for Command {
+        type DataType = f64;
+    }
+    enum SetPoint {}
+    impl UniqueIdentifier for SetPoint {
+        type DataType = f64;
+    }
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = f64;
+    }
+
+    // sends `n` commands as fast as possible
+    struct Commands(usize, usize);
+    impl Update for Commands {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Command> for Commands {
+        fn write(&mut self) -> Option<Data<Command>> {
+            (self.0 <= self.1).then(|| Data::new(self.0 as f64))
+        }
+    }
+
+    #[derive(Default)]
+    struct Scale(f64, usize);
+    impl Update for Scale {
+        fn update(&mut self) {
+            self.1 += 1;
+        }
+    }
+    impl Read<Command> for Scale {
+        fn read(&mut self, data: Data<Command>) {
+            self.0 = 10. * *data;
+        }
+    }
+    impl Write<SetPoint> for Scale {
+        fn write(&mut self) -> Option<Data<SetPoint>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    struct Counter(usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            (self.0 <= 50).then(|| Data::new(self.0 as f64))
+        }
+    }
+
+    #[derive(Default)]
+    struct Plant(f64, f64, Vec<(f64, f64)>);
+    impl Update for Plant {
+        fn update(&mut self) {
+            self.2.push((self.0, self.1));
+        }
+    }
+    impl Read<Count> for Plant {
+        fn read(&mut self, data: Data<Count>) {
+            self.0 = *data;
+        }
+    }
+    impl Read<SetPoint> for Plant {
+        fn read(&mut self, data: Data<SetPoint>) {
+            self.1 = *data;
+        }
+    }
+
+    #[tokio::test]
+    async fn event_driven() {
+        let scale = Scale::default().into_arcx();
+        let plant = Plant::default().into_arcx();
+        let mut commands: Initiator<_> = Commands(0, 3).into();
+        let mut event: Actor<_> = Actor::new(scale.clone()).event_driven();
+        let mut clock: Initiator<_> = Counter(0).into();
+        let mut sink: Terminator<_> = Actor::new(plant.clone());
+        commands
+            .add_output()
+            .build::<Command>()
+            .into_input(&mut event)
+            .unwrap();
+        event
+            .add_output()
+            .build::<SetPoint>()
+            .into_input(&mut sink)
+            .unwrap();
+        clock
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut sink)
+            .unwrap();
+        sink.hold::<SetPoint>().unwrap();
+        model!(commands, event, clock, sink)
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        let n_event = scale.lock().await.1;
+        assert!((1..=3).contains(&n_event));
+        let samples = plant.lock().await.2.clone();
+        assert_eq!(samples.len(), 50);
+        assert!(samples
+            .iter()
+            .enumerate()
+            .all(|(i, (c, _))| *c == 1. + i as f64));
+        assert!(samples.windows(2).all(|x| x[0].1 <= x[1].1));
+        assert_eq!(samples.last().unwrap().1, 30.);
+    }
+
+    #[test]
+    fn hold_only() {
+        let mut clock: Initiator<_> = Counter(0).into();
+        let mut sink: Terminator<_> = Plant::default().into();
+        clock
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut sink)
+            .unwrap();
+        sink.hold::<Count>().unwrap();
+        let err = model!(clock, sink).quiet().check().err().unwrap();
+        let chain: Vec<_> =
+            std::iter::successors(Some(&err as &dyn std::error::Error), |e| e.source())
+                .map(|e| e.to_string())
+                .collect();
+        assert!(
+            chain.iter().any(|e| e.contains("only sample-and-hold inputs")),
+            "{chain:?}"
+        );
+    }
 }
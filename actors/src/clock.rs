@@ -0,0 +1,203 @@
+/*!
+# Clock domains
+
+The sampling rates `NI` and `NO` of the inputs and outputs of an [Actor] are integer divisors
+of a base sampling frequency that is common to all the actors of a model.
+A [ClockDomain] gives its own base sampling frequency to the actors that are assigned to it
+with [Actor::clock], so that models mixing physical frequencies that are not integer multiples
+of each other, say a 8kHz FEM, a 1.2kHz control loop and a 48Hz camera, can be written directly.
+
+Actors in different clock domains can only be connected through a rate transition actor,
+an actor which inputs belong to one domain and outputs to another one, see [Actor::transition].
+For each input, a rate transition actor sends all the output samples which time is less than or equal to
+the time of the input, e.g. from 8kHz to 1.2kHz, 3 outputs are sent every 20 inputs.
+The client of the rate transition actor sets the kind of transition:
+ * zero-order hold with [Sampler](https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/sampler/struct.Sampler.html),
+ * first-order hold with [FirstOrderHold](https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/foh/struct.FirstOrderHold.html),
+ * averaging with [Average](https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/average/struct.Average.html).
+
+The actors that are not assigned to a clock domain inherit the domain of the actors they are connected to
+and [Model::check](crate::model::Model::check) verifies that there is no connection across clock domains
+other than through rate transitions.
+
+```
+use gmt_dos_actors::{clock::ClockDomain, prelude::*};
+use gmt_dos_clients::{logging::Logging, sampler::Sampler, signals::{Signal, Signals}};
+use interface::UID;
+
+#[derive(UID)]
+enum Data {}
+
+# tokio_test::block_on(async {
+let fem = ClockDomain::new("FEM", 8e3);
+let asm = ClockDomain::new("ASM", 1.2e3);
+
+let mut source: Initiator<_> = Actor::from(Signals::new(1, 40).channels(Signal::Ramp { a: 1., b: 0. }))
+    .clock(&fem);
+let mut zoh: Actor<_> = Actor::from(Sampler::<Vec<f64>, Data>::default()).transition(&fem, &asm);
+let logging = Logging::<f64>::new(1).into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone()).clock(&asm);
+
+source.add_output().build::<Data>().into_input(&mut zoh)?;
+zoh.add_output().build::<Data>().into_input(&mut sink)?;
+
+model!(source, zoh, sink).quiet().check()?.run().await?;
+
+let data: Vec<_> = logging.lock().await.chunks().map(|x| x[0]).collect();
+assert_eq!(data, vec![0., 7., 14., 20., 27., 34.]);
+# Ok::<(), anyhow::Error>(())
+# });
+```
+
+[Actor]: crate::actor::Actor
+[Actor::clock]: crate::actor::Actor::clock
+[Actor::transition]: crate::actor::Actor::transition
+*/
+
+use std::{fmt::Display, hash::Hash};
+
+/// Clock domain
+///
+/// A named base sampling frequency
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClockDomain {
+    name: String,
+    frequency: f64,
+}
+
+impl ClockDomain {
+    /// Creates a new clock domain with the given sampling frequency in Hz
+    ///
+    /// Panics if the frequency is not strictly positive
+    pub fn new(name: impl Into<String>, frequency: f64) -> Self {
+        assert!(
+            frequency > 0.,
+            "the sampling frequency of a clock domain must be strictly positive"
+        );
+        Self {
+            name: name.into(),
+            frequency,
+        }
+    }
+    /// Returns the clock domain name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the clock domain sampling frequency in Hz
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+}
+
+impl PartialEq for ClockDomain {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.frequency.to_bits() == other.frequency.to_bits()
+    }
+}
+impl Eq for ClockDomain {}
+
+impl Hash for ClockDomain {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.frequency.to_bits().hash(state);
+    }
+}
+
+impl Display for ClockDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}Hz", self.name, self.frequency)
+    }
+}
+
+/// Clock domains of the inputs and of the outputs of an actor
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clocks {
+    pub inputs: ClockDomain,
+    pub outputs: ClockDomain,
+}
+
+impl Clocks {
+    /// Returns true if the inputs and the outputs belong to different clock domains
+    pub fn is_transition(&self) -> bool {
+        self.inputs != self.outputs
+    }
+}
+
+/// Outputs schedule of a rate transition actor
+///
+/// Gives the number of outputs to send after each input,
+/// the output sampling frequency over the input sampling frequency
+/// is reduced to the ratio of 2 integers `p/q`
+#[derive(Debug, Clone)]
+pub(crate) struct Schedule {
+    p: u64,
+    q: u64,
+    phase: Option<u64>,
+}
+
+impl Schedule {
+    /// Creates the outputs schedule from the input and output sampling frequencies
+    pub fn new(input: f64, output: f64) -> Self {
+        let (p, q) = ratio(output, input);
+        Self { p, q, phase: None }
+    }
+    /// Returns the number of outputs to send after the next input
+    pub fn n_outputs(&mut self) -> usize {
+        match self.phase {
+            // the first output is sent with the first input
+            None => {
+                self.phase = Some(0);
+                1
+            }
+            Some(phase) => {
+                let phase = phase + self.p;
+                self.phase = Some(phase % self.q);
+                (phase / self.q) as usize
+            }
+        }
+    }
+}
+
+/// Reduces the ratio of 2 frequencies to the ratio of 2 integers
+fn ratio(num: f64, den: f64) -> (u64, u64) {
+    fn is_integer(x: f64) -> bool {
+        (x - x.round()).abs() < 1e-6 * x.abs().max(1.)
+    }
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+    let mut scale = 1f64;
+    while scale < 1e9 && !(is_integer(num * scale) && is_integer(den * scale)) {
+        scale *= 10.;
+    }
+    let (p, q) = ((num * scale).round() as u64, (den * scale).round() as u64);
+    let d = gcd(p, q).max(1);
+    (p / d, q / d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule() {
+        assert_eq!(ratio(1.2e3, 8e3), (3, 20));
+        assert_eq!(ratio(48., 1.2e3), (1, 25));
+        assert_eq!(ratio(2.5, 0.1), (25, 1));
+        let mut schedule = Schedule::new(8e3, 1.2e3);
+        let counts: Vec<_> = (0..41).map(|_| schedule.n_outputs()).collect();
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts[1..21].iter().sum::<usize>(), 3);
+        assert_eq!(counts[21..].iter().sum::<usize>(), 3);
+        assert!(counts.iter().all(|&n| n <= 1));
+        let mut schedule = Schedule::new(2., 3.);
+        let counts: Vec<_> = (0..5).map(|_| schedule.n_outputs()).collect();
+        assert_eq!(counts, vec![1, 1, 2, 1, 2]);
+    }
+}
@@ -0,0 +1,850 @@
+/*!
+# Dynamic models
+
+The connections between actors are usually resolved at compile time with
+[build::<U>()](crate::framework::network::AddOuput::build) and
+[into_input](crate::framework::network::TryIntoInputs::into_input).
+With the `dynamic` feature, a [Model] can instead be assembled at runtime
+from a description of the actors and of the edges between them.
+
+The clients register named ports with [Ports], each port is bound to the [UniqueIdentifier] of the data
+it carries and, optionally, to the size of the data given by the [Size] trait.
+The ports are type-erased: an output can be connected to any input which data has the same type,
+e.g. `Vec<f64>`, whatever the [UniqueIdentifier]s of the output and of the input are.
+The model description, a [ModelSpec], lists the actors, their sampling rates and the edges
+between the ports of the actors; it is usually loaded from a YAML or a TOML file:
+```yaml
+name: demo
+actors:
+  - name: source
+    outputs_rate: 1
+  - name: sink
+    inputs_rate: 1
+edges:
+  - from: source.signal
+    to: sink.signal
+```
+The actors listed in the description are matched by name with the actors added to the [DynModel].
+The actors, the ports, the ports data types and sizes and the actors rates are [validated](DynModel::validate)
+when the [DynModel] is checked, before the actors are connected to each other.
+
+```
+use gmt_dos_actors::{dynamic::{DynModel, ModelSpec, Ports}, prelude::*};
+use gmt_dos_clients::{logging::Logging, signals::{Signal, Signals}};
+use interface::UID;
+
+#[derive(UID)]
+enum Sig {}
+
+let spec = ModelSpec::from_yaml(
+    r#"
+    actors: [{name: source}, {name: sink}]
+    edges: [{from: source.signal, to: sink.signal}]
+    "#,
+)?;
+let source: Initiator<_> = Signals::new(1, 10).channel(0, Signal::Constant(1.)).into();
+let sink: Terminator<_> = Logging::<f64>::new(1).into();
+let model = DynModel::new(spec)
+    .actor("source", Ports::new(source).output::<Sig>("signal"))
+    .actor("sink", Ports::new(sink).input::<Sig>("signal"))
+    .check()?;
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+*/
+
+use crate::{
+    actor::{lock_client, Actor},
+    framework::{
+        model::Task,
+        network::{add_output, ActorOutputBuilder},
+    },
+    model::{Model, ModelError, Ready, Unknown},
+    trim,
+};
+use interface::{Data, Quote, Read, Size, UniqueIdentifier, Update, Write};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    path::Path,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DynamicError {
+    #[error("failed to read the model description")]
+    IO(#[from] std::io::Error),
+    #[error("failed to parse the YAML model description")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("failed to parse the TOML model description")]
+    Toml(#[from] toml::de::Error),
+    #[error("unknown model description format: {0:?}, expected yaml or toml")]
+    Format(String),
+    #[error("no actor {0} has been added to the model")]
+    MissingActor(String),
+    #[error("actor {0} is not in the model description")]
+    UnknownActor(String),
+    #[error("{0:?} is not a port, expected `actor.port`")]
+    Endpoint(String),
+    #[error("{actor} has no {kind} port {port}")]
+    NoPort {
+        actor: String,
+        kind: &'static str,
+        port: String,
+    },
+    #[error("the {kind} rate of {actor} is {found}, expected {expected}")]
+    Rate {
+        actor: String,
+        kind: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("{from} ({output}) cannot be connected to {to} ({input})")]
+    Type {
+        from: String,
+        to: String,
+        output: String,
+        input: String,
+    },
+    #[error("the size of {from} ({output}) does not match the size of {to} ({input})")]
+    Size {
+        from: String,
+        to: String,
+        output: usize,
+        input: usize,
+    },
+    #[error("the edges from {0} have different bootstrap or unbounded flags")]
+    Options(String),
+}
+
+type Result<T> = std::result::Result<T, DynamicError>;
+
+/// UID of the data sent between type-erased ports
+///
+/// The data of any UID which data type is `T` is sent as `Data<Payload<T>>`
+struct Payload<T>(PhantomData<T>);
+impl<T: Send + Sync> UniqueIdentifier for Payload<T> {
+    type DataType = T;
+}
+
+type Rx<T> = flume::Receiver<Data<Payload<T>>>;
+
+/// Type-erased receivers of an output
+struct Link {
+    hash: u64,
+    uid: String,
+    rxs: Box<dyn Any + Send>,
+}
+
+/// UID, data type and size of a port
+struct PortInfo {
+    uid: String,
+    type_id: TypeId,
+    size: Option<usize>,
+}
+
+struct OutputPort<C: Update, const NI: usize, const NO: usize> {
+    name: String,
+    uid: String,
+    type_id: TypeId,
+    size: Option<fn(&C) -> usize>,
+    build: fn(&mut Actor<C, NI, NO>, ActorOutputBuilder) -> Link,
+}
+
+struct InputPort<C: Update, const NI: usize, const NO: usize> {
+    name: String,
+    uid: String,
+    type_id: TypeId,
+    size: Option<fn(&C) -> usize>,
+    add: fn(&mut Actor<C, NI, NO>, &mut Link) -> bool,
+}
+
+/// Returns the UID and the data type of a port as `UID: DataType`
+fn uid<U: UniqueIdentifier>() -> String {
+    format!(
+        "{}: {}",
+        <U as Quote>::quote(),
+        trim(type_name::<<U as UniqueIdentifier>::DataType>())
+    )
+}
+
+fn build<U, C, const NI: usize, const NO: usize>(
+    actor: &mut Actor<C, NI, NO>,
+    builder: ActorOutputBuilder,
+) -> Link
+where
+    U: 'static + UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: 'static,
+    C: 'static + Write<U>,
+{
+    let (hash, rxs, _) =
+        add_output::<U, Payload<<U as UniqueIdentifier>::DataType>, C, NI, NO>(actor, builder);
+    Link {
+        hash,
+        uid: uid::<U>(),
+        rxs: Box::new(rxs),
+    }
+}
+
+/// Connects an input to one of the receivers of the link
+///
+/// Returns `false` if the data types of the link and of the input do not match
+fn add<U, C, const NI: usize, const NO: usize>(
+    actor: &mut Actor<C, NI, NO>,
+    link: &mut Link,
+) -> bool
+where
+    U: 'static + UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: 'static,
+    C: 'static + Read<U>,
+{
+    let Some(rx) = link
+        .rxs
+        .downcast_mut::<Vec<Rx<<U as UniqueIdentifier>::DataType>>>()
+        .and_then(|rxs| rxs.pop())
+    else {
+        return false;
+    };
+    actor.add_input_from::<U, Payload<<U as UniqueIdentifier>::DataType>>(rx, link.hash);
+    true
+}
+
+fn size<U: UniqueIdentifier, C: Size<U>>(client: &C) -> usize {
+    <C as Size<U>>::len(client)
+}
+
+/// Named ports of an [Actor]
+///
+/// Each port is bound to the [UniqueIdentifier] of the data it carries,
+/// ports are connected according to the type of the data
+pub struct Ports<C: Update, const NI: usize = 1, const NO: usize = 1> {
+    actor: Actor<C, NI, NO>,
+    inputs: Vec<InputPort<C, NI, NO>>,
+    outputs: Vec<OutputPort<C, NI, NO>>,
+}
+impl<C, const NI: usize, const NO: usize> Ports<C, NI, NO>
+where
+    C: 'static + Update,
+{
+    /// Creates an actor without ports
+    pub fn new(actor: Actor<C, NI, NO>) -> Self {
+        Self {
+            actor,
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+    fn push_input<U>(mut self, name: String, size: Option<fn(&C) -> usize>) -> Self
+    where
+        C: Read<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.inputs.push(InputPort {
+            name,
+            uid: uid::<U>(),
+            type_id: TypeId::of::<<U as UniqueIdentifier>::DataType>(),
+            size,
+            add: add::<U, C, NI, NO>,
+        });
+        self
+    }
+    fn push_output<U>(mut self, name: String, size: Option<fn(&C) -> usize>) -> Self
+    where
+        C: Write<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.outputs.push(OutputPort {
+            name,
+            uid: uid::<U>(),
+            type_id: TypeId::of::<<U as UniqueIdentifier>::DataType>(),
+            size,
+            build: build::<U, C, NI, NO>,
+        });
+        self
+    }
+    /// Adds an input port
+    pub fn input<U>(self, name: impl Into<String>) -> Self
+    where
+        C: Read<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.push_input::<U>(name.into(), None)
+    }
+    /// Adds an input port which size is checked against the size of the output it is connected to
+    pub fn sized_input<U>(self, name: impl Into<String>) -> Self
+    where
+        C: Read<U> + Size<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.push_input::<U>(name.into(), Some(size::<U, C>))
+    }
+    /// Adds an output port
+    pub fn output<U>(self, name: impl Into<String>) -> Self
+    where
+        C: Write<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.push_output::<U>(name.into(), None)
+    }
+    /// Adds an output port which size is checked against the size of the inputs it is connected to
+    pub fn sized_output<U>(self, name: impl Into<String>) -> Self
+    where
+        C: Write<U> + Size<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: 'static,
+    {
+        self.push_output::<U>(name.into(), Some(size::<U, C>))
+    }
+}
+
+/// Type-erased interface to [Ports]
+trait Node: Send {
+    fn rename(&mut self, name: &str);
+    fn rates(&self) -> (usize, usize);
+    fn input(&self, port: &str) -> Option<PortInfo>;
+    fn output(&self, port: &str) -> Option<PortInfo>;
+    fn build_output(&mut self, port: &str, builder: ActorOutputBuilder) -> Option<Link>;
+    /// Connects an input to a link
+    ///
+    /// Returns [None] if there is no such input port and
+    /// `Some(false)` if the data types of the link and of the input do not match
+    fn add_input(&mut self, port: &str, link: &mut Link) -> Option<bool>;
+    fn into_task(self: Box<Self>) -> Box<dyn Task>;
+}
+
+impl<C, const NI: usize, const NO: usize> Node for Ports<C, NI, NO>
+where
+    C: 'static + Update,
+{
+    fn rename(&mut self, name: &str) {
+        self.actor.name.get_or_insert_with(|| name.to_string());
+    }
+    fn rates(&self) -> (usize, usize) {
+        (NI, NO)
+    }
+    fn input(&self, port: &str) -> Option<PortInfo> {
+        self.inputs
+            .iter()
+            .find(|input| input.name == port)
+            .map(|input| PortInfo {
+                uid: input.uid.clone(),
+                type_id: input.type_id,
+                size: input
+                    .size
+                    .map(|size| size(&lock_client(&self.actor.client))),
+            })
+    }
+    fn output(&self, port: &str) -> Option<PortInfo> {
+        self.outputs
+            .iter()
+            .find(|output| output.name == port)
+            .map(|output| PortInfo {
+                uid: output.uid.clone(),
+                type_id: output.type_id,
+                size: output
+                    .size
+                    .map(|size| size(&lock_client(&self.actor.client))),
+            })
+    }
+    fn build_output(&mut self, port: &str, builder: ActorOutputBuilder) -> Option<Link> {
+        let output = self.outputs.iter().find(|output| output.name == port)?;
+        Some((output.build)(&mut self.actor, builder))
+    }
+    fn add_input(&mut self, port: &str, link: &mut Link) -> Option<bool> {
+        let input = self.inputs.iter().find(|input| input.name == port)?;
+        Some((input.add)(&mut self.actor, link))
+    }
+    fn into_task(self: Box<Self>) -> Box<dyn Task> {
+        Box::new(self.actor)
+    }
+}
+
+/// Type-erased [Actor] with named ports
+pub struct DynActor(Box<dyn Node>);
+impl<C, const NI: usize, const NO: usize> From<Ports<C, NI, NO>> for DynActor
+where
+    C: 'static + Update,
+{
+    fn from(ports: Ports<C, NI, NO>) -> Self {
+        Self(Box::new(ports))
+    }
+}
+
+/// Actor description
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorSpec {
+    /// actor name
+    pub name: String,
+    /// expected inputs rate
+    #[serde(default)]
+    pub inputs_rate: Option<usize>,
+    /// expected outputs rate
+    #[serde(default)]
+    pub outputs_rate: Option<usize>,
+}
+
+/// Edge description
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeSpec {
+    /// output port as `actor.port`
+    pub from: String,
+    /// input port as `actor.port`
+    pub to: String,
+    /// bootstrapped output
+    #[serde(default)]
+    pub bootstrap: bool,
+    /// unbounded output channel
+    #[serde(default)]
+    pub unbounded: bool,
+}
+
+/// Model description
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelSpec {
+    /// model name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// actors
+    pub actors: Vec<ActorSpec>,
+    /// edges between the actors ports
+    #[serde(default)]
+    pub edges: Vec<EdgeSpec>,
+}
+impl ModelSpec {
+    /// Parses a YAML model description
+    pub fn from_yaml(spec: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(spec)?)
+    }
+    /// Parses a TOML model description
+    pub fn from_toml(spec: &str) -> Result<Self> {
+        Ok(toml::from_str(spec)?)
+    }
+    /// Loads a model description from a YAML (`.yaml` or `.yml`) or a TOML (`.toml`) file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let spec = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::from_yaml(&spec),
+            Some("toml") => Self::from_toml(&spec),
+            ext => Err(DynamicError::Format(ext.unwrap_or_default().to_string())),
+        }
+    }
+}
+
+/// Splits `actor.port` into the actor index and the port name
+fn endpoint<'a>(index: &HashMap<&str, usize>, endpoint: &'a str) -> Result<(usize, &'a str)> {
+    let (actor, port) = endpoint
+        .split_once('.')
+        .ok_or_else(|| DynamicError::Endpoint(endpoint.to_string()))?;
+    let &i = index
+        .get(actor)
+        .ok_or_else(|| DynamicError::MissingActor(actor.to_string()))?;
+    Ok((i, port))
+}
+
+/// Model assembled at runtime from a [ModelSpec]
+pub struct DynModel {
+    spec: ModelSpec,
+    actors: HashMap<String, DynActor>,
+}
+impl DynModel {
+    /// Creates a new model from its description
+    pub fn new(spec: ModelSpec) -> Self {
+        Self {
+            spec,
+            actors: HashMap::new(),
+        }
+    }
+    /// Adds an actor to the model
+    ///
+    /// The name of the actor must match the name of an actor of the model description
+    pub fn actor(mut self, name: impl Into<String>, actor: impl Into<DynActor>) -> Self {
+        self.actors.insert(name.into(), actor.into());
+        self
+    }
+    /// Returns the output and the input ports of an edge
+    fn ports(&self, edge: &EdgeSpec) -> Result<(PortInfo, PortInfo)> {
+        let port = |endpoint: &str, kind: &'static str| -> Result<PortInfo> {
+            let (actor, port) = endpoint
+                .split_once('.')
+                .ok_or_else(|| DynamicError::Endpoint(endpoint.to_string()))?;
+            let DynActor(node) = self
+                .actors
+                .get(actor)
+                .ok_or_else(|| DynamicError::MissingActor(actor.to_string()))?;
+            let info = match kind {
+                "output" => node.output(port),
+                _ => node.input(port),
+            };
+            info.ok_or_else(|| DynamicError::NoPort {
+                actor: actor.to_string(),
+                kind,
+                port: port.to_string(),
+            })
+        };
+        Ok((port(&edge.from, "output")?, port(&edge.to, "input")?))
+    }
+    /// Validates the model against its description
+    ///
+    /// Checks that:
+    ///  - the actors of the model and of the description are the same,
+    ///  - the rates of the actors are the expected ones,
+    ///  - the ports of the edges exist,
+    ///  - the data types and, if known, the sizes of the connected ports match,
+    ///  - the edges from the same output have the same options
+    pub fn validate(&self) -> Result<()> {
+        if let Some(name) = self
+            .actors
+            .keys()
+            .find(|name| !self.spec.actors.iter().any(|actor| &actor.name == *name))
+        {
+            return Err(DynamicError::UnknownActor(name.clone()));
+        }
+        for ActorSpec {
+            name,
+            inputs_rate,
+            outputs_rate,
+        } in &self.spec.actors
+        {
+            let DynActor(node) = self
+                .actors
+                .get(name)
+                .ok_or_else(|| DynamicError::MissingActor(name.clone()))?;
+            let (ni, no) = node.rates();
+            for (kind, expected, found) in
+                [("inputs", *inputs_rate, ni), ("outputs", *outputs_rate, no)]
+            {
+                if let Some(expected) = expected.filter(|&expected| expected != found) {
+                    return Err(DynamicError::Rate {
+                        actor: name.clone(),
+                        kind,
+                        expected,
+                        found,
+                    });
+                }
+            }
+        }
+        for edge in &self.spec.edges {
+            let (output, input) = self.ports(edge)?;
+            if output.type_id != input.type_id {
+                return Err(DynamicError::Type {
+                    from: edge.from.clone(),
+                    to: edge.to.clone(),
+                    output: output.uid,
+                    input: input.uid,
+                });
+            }
+            if let (Some(n), Some(m)) = (output.size, input.size) {
+                if n != m {
+                    return Err(DynamicError::Size {
+                        from: edge.from.clone(),
+                        to: edge.to.clone(),
+                        output: n,
+                        input: m,
+                    });
+                }
+            }
+            if self.spec.edges.iter().any(|other| {
+                other.from == edge.from
+                    && (other.bootstrap, other.unbounded) != (edge.bootstrap, edge.unbounded)
+            }) {
+                return Err(DynamicError::Options(edge.from.clone()));
+            }
+        }
+        Ok(())
+    }
+    /// [Validates](DynModel::validate) the model and connects the actors
+    pub fn build(self) -> Result<Model<Unknown>> {
+        self.validate()?;
+        self.connect()
+    }
+    /// Connects the actors
+    fn connect(mut self) -> Result<Model<Unknown>> {
+        let mut nodes = vec![];
+        for ActorSpec { name, .. } in &self.spec.actors {
+            let DynActor(mut node) = self
+                .actors
+                .remove(name)
+                .ok_or_else(|| DynamicError::MissingActor(name.clone()))?;
+            node.rename(name);
+            nodes.push(node);
+        }
+        let index: HashMap<&str, usize> = self
+            .spec
+            .actors
+            .iter()
+            .enumerate()
+            .map(|(i, actor)| (actor.name.as_str(), i))
+            .collect();
+        // edges grouping by output
+        let mut groups: Vec<(&EdgeSpec, Vec<&EdgeSpec>)> = vec![];
+        for edge in &self.spec.edges {
+            match groups.iter_mut().find(|(group, _)| group.from == edge.from) {
+                Some((_, edges)) => edges.push(edge),
+                None => groups.push((edge, vec![edge])),
+            }
+        }
+        // actors connections
+        for (output, edges) in groups {
+            let (i, output_port) = endpoint(&index, &output.from)?;
+            let mut builder = ActorOutputBuilder::new(edges.len());
+            if output.bootstrap {
+                builder.bootstrap();
+            }
+            if output.unbounded {
+                builder.unbounded();
+            }
+            let mut link = nodes[i].build_output(output_port, builder).ok_or_else(|| {
+                DynamicError::NoPort {
+                    actor: self.spec.actors[i].name.clone(),
+                    kind: "output",
+                    port: output_port.to_string(),
+                }
+            })?;
+            for edge in edges {
+                let (j, input_port) = endpoint(&index, &edge.to)?;
+                match nodes[j].add_input(input_port, &mut link) {
+                    Some(true) => (),
+                    Some(false) => {
+                        return Err(DynamicError::Type {
+                            from: edge.from.clone(),
+                            to: edge.to.clone(),
+                            output: link.uid,
+                            input: nodes[j]
+                                .input(input_port)
+                                .map(|input| input.uid)
+                                .unwrap_or_default(),
+                        })
+                    }
+                    None => {
+                        return Err(DynamicError::NoPort {
+                            actor: self.spec.actors[j].name.clone(),
+                            kind: "input",
+                            port: input_port.to_string(),
+                        })
+                    }
+                }
+            }
+        }
+        let model = Model::new(nodes.into_iter().map(|node| node.into_task()).collect());
+        Ok(match self.spec.name {
+            Some(name) => model.name(name),
+            None => model,
+        })
+    }
+    /// [Validates](DynModel::validate) the model, connects the actors and checks the model
+    pub fn check(self) -> std::result::Result<Model<Ready>, ModelError> {
+        self.validate()?;
+        self.connect()?.check()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = Vec<f64>;
+    }
+    enum Other {}
+    impl UniqueIdentifier for Other {
+        type DataType = Vec<f64>;
+    }
+    enum Scalar {}
+    impl UniqueIdentifier for Scalar {
+        type DataType = f64;
+    }
+
+    struct Counter(usize, usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            (self.0 <= 5).then(|| Data::new(vec![self.0 as f64; self.1]))
+        }
+    }
+    impl Size<Count> for Counter {
+        fn len(&self) -> usize {
+            self.1
+        }
+    }
+
+    #[derive(Default)]
+    struct Logger(Vec<f64>, usize);
+    impl Update for Logger {}
+    impl Read<Count> for Logger {
+        fn read(&mut self, data: Data<Count>) {
+            self.0.extend(data.iter());
+        }
+    }
+    impl Read<Other> for Logger {
+        fn read(&mut self, data: Data<Other>) {
+            self.0.extend(data.iter());
+        }
+    }
+    impl Read<Scalar> for Logger {
+        fn read(&mut self, _: Data<Scalar>) {}
+    }
+    impl Size<Count> for Logger {
+        fn len(&self) -> usize {
+            self.1
+        }
+    }
+
+    const YAML: &str = r#"
+name: dynamic
+actors:
+  - name: counter
+    outputs_rate: 1
+  - name: log
+edges:
+  - from: counter.count
+    to: log.count
+"#;
+
+    const TOML: &str = r#"
+name = "dynamic"
+
+[[actors]]
+name = "counter"
+outputs_rate = 1
+
+[[actors]]
+name = "log"
+
+[[edges]]
+from = "counter.count"
+to = "log.count"
+"#;
+
+    fn model(spec: ModelSpec, logger: Arc<Mutex<Logger>>) -> DynModel {
+        let counter: Initiator<_> = Counter(0, 2).into();
+        let log: Terminator<_> = Actor::new(logger);
+        DynModel::new(spec)
+            .actor(
+                "counter",
+                Ports::new(counter).sized_output::<Count>("count"),
+            )
+            .actor(
+                "log",
+                Ports::new(log)
+                    .sized_input::<Count>("count")
+                    .input::<Other>("other")
+                    .input::<Scalar>("scalar"),
+            )
+    }
+
+    #[tokio::test]
+    async fn run() {
+        for spec in [ModelSpec::from_yaml(YAML), ModelSpec::from_toml(TOML)] {
+            let logger = Logger(vec![], 2).into_arcx();
+            model(spec.unwrap(), logger.clone())
+                .build()
+                .unwrap()
+                .quiet()
+                .check()
+                .unwrap()
+                .run()
+                .await
+                .unwrap();
+            assert_eq!(
+                logger.lock().await.0,
+                vec![1., 1., 2., 2., 3., 3., 4., 4., 5., 5.]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn type_erased() {
+        let mut spec = ModelSpec::from_yaml(YAML).unwrap();
+        spec.edges[0].to = "log.other".into();
+        let logger = Logger(vec![], 2).into_arcx();
+        model(spec, logger.clone())
+            .build()
+            .unwrap()
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(
+            logger.lock().await.0,
+            vec![1., 1., 2., 2., 3., 3., 4., 4., 5., 5.]
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let logger = Logger(vec![], 3).into_arcx();
+        let err = model(ModelSpec::from_yaml(YAML).unwrap(), logger)
+            .check()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            ModelError::Dynamic(DynamicError::Size {
+                output: 2,
+                input: 3,
+                ..
+            })
+        ));
+
+        let mut spec = ModelSpec::from_yaml(YAML).unwrap();
+        spec.edges[0].to = "log.scalar".into();
+        let err = model(spec, Logger(vec![], 2).into_arcx())
+            .validate()
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "counter.count (Count: Vec<f64>) cannot be connected to log.scalar (Scalar: f64)"
+        );
+
+        let mut spec = ModelSpec::from_yaml(YAML).unwrap();
+        spec.actors[0].outputs_rate = Some(10);
+        let err = model(spec, Logger(vec![], 2).into_arcx())
+            .check()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            ModelError::Dynamic(DynamicError::Rate { found: 1, .. })
+        ));
+
+        let mut spec = ModelSpec::from_yaml(YAML).unwrap();
+        spec.edges[0].to = "log.unknown".into();
+        let err = model(spec, Logger(vec![], 2).into_arcx())
+            .check()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            ModelError::Dynamic(DynamicError::NoPort { kind: "input", .. })
+        ));
+
+        let mut spec = ModelSpec::from_yaml(YAML).unwrap();
+        spec.actors.pop();
+        spec.edges.clear();
+        let err = model(spec, Logger(vec![], 2).into_arcx())
+            .check()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            ModelError::Dynamic(DynamicError::UnknownActor(name)) if name == "log"
+        ));
+    }
+}
@@ -9,6 +9,8 @@
 use std::path::PathBuf;
 
 use crate::graph::GraphError;
+#[cfg(feature = "filing")]
+use crate::model::checkpoint::{ActorCheckpoint, CheckpointError};
 use crate::model::{Model, UnknownOrReady};
 use crate::system::System;
 use crate::{
@@ -31,8 +33,9 @@ pub enum CheckError {
 pub trait Check {
     /// Validates the inputs
     ///
-    /// Returns en error if there are some inputs but the inputs rate is zero
-    /// or if there are no inputs and the inputs rate is positive
+    /// Returns en error if there are some inputs but the inputs rate is zero,
+    /// if there are no inputs and the inputs rate is positive
+    /// or if all the inputs of a rate-based actor are sample-and-hold inputs
     fn check_inputs(&self) -> std::result::Result<(), CheckError>;
     /// Validates the outputs
     ///
@@ -51,6 +54,10 @@ pub trait Check {
     fn is_system(&self) -> bool {
         false
     }
+    /// Returns the number of actors the task is made of
+    fn n_actors(&self) -> usize {
+        1
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -59,6 +66,47 @@ pub enum TaskError {
     FromActor(#[from] ActorError),
     #[error("error in Task from Model")]
     FromModel(#[from] model::ModelError),
+    #[error("system {0} must be flattened into its actors (see Task::into_tasks)")]
+    System(String),
+}
+
+/// Outcome of a call to [Task::step]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// the task has moved forward
+    Progress,
+    /// the task is waiting for data or for space in a channel
+    Blocked,
+    /// the task has nothing left to do
+    Done,
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum Stage {
+    #[default]
+    Start,
+    Bootstrap(usize),
+    Collect,
+    Distribute,
+}
+
+/// Position of a [Task] within the deterministic executor
+///
+/// The cursor records the next simulation sample the task has to process
+/// and where the task stands within this sample
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepCursor {
+    pub(crate) sample: usize,
+    pub(crate) stage: Stage,
+    pub(crate) sent: Vec<bool>,
+}
+impl StepCursor {
+    /// Returns the next sample to be processed
+    pub fn sample(&self) -> usize {
+        self.sample
+    }
 }
 
 /// Interface for running model components
@@ -79,6 +127,24 @@ pub trait Task: Check + std::fmt::Display + Send + Sync {
     /// Run the actor loop
     async fn task(self: Box<Self>) -> std::result::Result<(), TaskError>;
     fn as_plain(&self) -> PlainActor;
+    /// Runs the actor loop, without blocking, up to and including the simulation sample `k`
+    ///
+    /// The progress of the loop is recorded in the `cursor`
+    fn step(&mut self, k: usize, cursor: &mut StepCursor) -> std::result::Result<Step, TaskError>;
+    /// Sets the position the [Task::async_run] loop starts from
+    ///
+    /// The default implementation does nothing and the loop starts from the beginning
+    fn start_from(&mut self, _cursor: StepCursor) {}
+    /// Flattens the task into the actors it is made of
+    fn into_tasks(self: Box<Self>) -> Vec<Box<dyn Task>>;
+    /// Seeds the random number generators of the clients with [Update::seed](interface::Update::seed)
+    fn seed(&mut self, seed: interface::Seed);
+    /// Saves the client state and the data in flight from the outputs
+    #[cfg(feature = "filing")]
+    fn checkpoint(&self) -> std::result::Result<ActorCheckpoint, CheckpointError>;
+    /// Restores the client state and the data in flight from the outputs
+    #[cfg(feature = "filing")]
+    fn restore(&mut self, checkpoint: ActorCheckpoint) -> std::result::Result<(), CheckpointError>;
 }
 
 /// Flowchart name
@@ -23,7 +23,9 @@ pub use inputs::{AddActorInput, TryIntoInputs};
 
 /// Interface for actors outputs
 mod outputs;
-pub use outputs::{ActorOutput, ActorOutputBuilder, AddActorOutput, AddOuput};
+pub use outputs::{ActorOutput, ActorOutputBuilder, AddActorOutput, AddOuput, OutputPolicy};
+#[cfg(feature = "dynamic")]
+pub(crate) use outputs::add_output;
 
 /// Definition of the payload between outputs and inputs
 mod output_rx;
@@ -9,7 +9,7 @@ use crate::actor::{
 };
 
 pub mod builder;
-pub use builder::ActorOutputBuilder;
+pub use builder::{ActorOutputBuilder, OutputPolicy};
 
 mod outputs;
 use interface::{Quote, UniqueIdentifier, Update, Who, Write};
@@ -43,6 +43,50 @@ where
         self.get_output_builder().unbounded();
         self
     }
+    /// Sets the channel capacity
+    fn bounded(mut self, capacity: usize) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.get_output_builder().bounded(capacity);
+        self
+    }
+    /// Sets the [policy](OutputPolicy) applied to the data when a channel is full
+    fn policy(mut self, policy: OutputPolicy) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.get_output_builder().policy(policy);
+        self
+    }
+    /// Blocks the output until there is room in the channels (default)
+    fn block(self) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.policy(OutputPolicy::Block)
+    }
+    /// Drops the oldest data when a channel is full
+    fn drop_oldest(self) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.policy(OutputPolicy::DropOldest)
+    }
+    /// Drops the new data when a channel is full
+    fn drop_newest(self) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.policy(OutputPolicy::DropNewest)
+    }
+    /// Keeps only the latest data in the channels
+    fn keep_latest(self) -> Self
+    where
+        Self: Sized + OutputBuilder,
+    {
+        self.policy(OutputPolicy::KeepLatest)
+    }
     /// Flags the output to be bootstrapped
     fn bootstrap(mut self) -> Self
     where
@@ -64,72 +108,89 @@ where
     where
         C: Write<U>,
         U: 'static + UniqueIdentifier;
+    /// Builds a new output which data in flight is saved into [model checkpoints](crate::model::checkpoint)
+    #[cfg(feature = "filing")]
+    fn build_checkpointable<U>(self) -> std::result::Result<(), OutputRx<U, C, NI, NO>>
+    where
+        C: Write<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: serde::Serialize + serde::de::DeserializeOwned;
     fn build_output<U>(
-        actor: &'a mut Actor<C, NI, NO>,
+        actor: &mut Actor<C, NI, NO>,
         builder: ActorOutputBuilder,
     ) -> std::result::Result<(), OutputRx<U, C, NI, NO>>
     where
         C: Write<U>,
         U: 'static + UniqueIdentifier,
     {
-        let mut txs = vec![];
-        let mut rxs = vec![];
-        for &cap in builder.capacity() {
-            let (tx, rx) = if cap == usize::MAX {
-                flume::unbounded::<S<U>>()
-            } else {
-                flume::bounded::<S<U>>(cap)
-            };
-            txs.push(tx);
-            rxs.push(rx);
-        }
-
-        // Check if this output already exists
-        if let Some(outputs) = &mut actor.outputs {
-            if let Some(output) = outputs
-                .iter_mut()
-                .find_map(|output| output.as_mut_any().downcast_mut::<Output<C, U, NO>>())
-            {
-                output.tx_push(txs);
-                let output_name = Who::who(output);
-                return Err(OutputRx {
-                    hash: output.get_hash(),
-                    rxs,
-                    client: std::sync::Arc::clone(&actor.client),
-                    actor: actor.who(),
-                    output: output_name,
-                });
-            }
-        }
-
-        let mut output: Output<C, U, NO> = Output::builder(actor.client.clone())
-            .bootstrap(builder.is_bootstrap())
-            .senders(txs)
-            .build();
-
-        let mut hasher = DefaultHasher::new();
-        actor.who().hash(&mut hasher);
-        let output_name = Who::who(&output);
-        <U as Quote>::quote().hash(&mut hasher);
-        let hash = hasher.finish();
-        <Output<C, U, NO> as OutputObject>::set_hash(&mut output, hash);
-
-        if let Some(ref mut outputs) = actor.outputs {
-            outputs.push(Box::new(output));
-        } else {
-            actor.outputs = Some(vec![Box::new(output)]);
-        }
-
+        let (hash, rxs, output) = add_output::<U, U, C, NI, NO>(actor, builder);
         Err(OutputRx {
             hash,
             rxs,
             client: std::sync::Arc::clone(&actor.client),
             actor: actor.who(),
-            output: output_name,
+            output,
         })
     }
 }
 
+/// Adds an output to an actor which data is sent through channels of `Data<R>`
+///
+/// Returns the output hash, the receivers of the channels and the output name
+pub(crate) fn add_output<U, R, C, const NI: usize, const NO: usize>(
+    actor: &mut Actor<C, NI, NO>,
+    builder: ActorOutputBuilder,
+) -> (u64, Vec<flume::Receiver<S<R>>>, String)
+where
+    C: 'static + Write<U>,
+    U: 'static + UniqueIdentifier,
+    R: 'static + UniqueIdentifier<DataType = U::DataType>,
+{
+    let mut txs = vec![];
+    let mut rxs = vec![];
+    for &cap in builder.capacity() {
+        let (tx, rx) = if cap == usize::MAX {
+            flume::unbounded::<S<R>>()
+        } else {
+            flume::bounded::<S<R>>(cap)
+        };
+        txs.push(tx);
+        rxs.push(rx);
+    }
+
+    // Check if this output already exists
+    if let Some(outputs) = &mut actor.outputs {
+        if let Some(output) = outputs
+            .iter_mut()
+            .find_map(|output| output.as_mut_any().downcast_mut::<Output<C, U, NO, R>>())
+        {
+            output.tx_push(txs, &rxs);
+            return (output.get_hash(), rxs, Who::who(output));
+        }
+    }
+
+    let mut output: Output<C, U, NO, R> = Output::builder(actor.client.clone())
+        .bootstrap(builder.is_bootstrap())
+        .senders(txs)
+        .policy(builder.output_policy(), &rxs)
+        .build();
+
+    let mut hasher = DefaultHasher::new();
+    actor.who().hash(&mut hasher);
+    let output_name = Who::who(&output);
+    <U as Quote>::quote().hash(&mut hasher);
+    let hash = hasher.finish();
+    <Output<C, U, NO, R> as OutputObject>::set_hash(&mut output, hash);
+
+    if let Some(ref mut outputs) = actor.outputs {
+        outputs.push(Box::new(output));
+    } else {
+        actor.outputs = Some(vec![Box::new(output)]);
+    }
+
+    (hash, rxs, output_name)
+}
+
 /* impl<'a, C, const NI: usize, const NO: usize> AddOuput<'a, C, NI, NO>
     for (&'a mut Actor<C, NI, NO>, ActorOutputBuilder)
 where
@@ -221,7 +282,7 @@ where
                     .as_mut_any()
                     .downcast_mut::<Output<C, Assoc<U>, U, NO>>()
             }) {
-                output.tx_push(txs);
+                output.tx_push(txs, &rxs);
                 let output_name = Who::who(output);
                 return Err(OutputRx {
                     hash: output.get_hash(),
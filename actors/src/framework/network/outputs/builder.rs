@@ -1,8 +1,23 @@
+/// Policy applied to the data of an output when the channel to an input is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OutputPolicy {
+    /// The actor waits for the input to receive the data in the channel
+    #[default]
+    Block,
+    /// The oldest data in the channel is dropped to make room for the new data
+    DropOldest,
+    /// The new data is dropped
+    DropNewest,
+    /// The channel holds a single data, which is replaced by the new data
+    KeepLatest,
+}
+
 /// Actor outputs builder
 #[derive(Default)]
 pub struct ActorOutputBuilder {
     capacity: Vec<usize>,
     bootstrap: bool,
+    policy: OutputPolicy,
 }
 
 impl ActorOutputBuilder {
@@ -21,10 +36,27 @@ impl ActorOutputBuilder {
     pub fn is_bootstrap(&self) -> bool {
         self.bootstrap
     }
+    /// Returns the policy applied to the data when a channel is full
+    pub fn output_policy(&self) -> OutputPolicy {
+        self.policy
+    }
     /// Sets the output channel capacity to a very large size
     pub fn unbounded(&mut self) {
         self.capacity = vec![usize::MAX; self.capacity.len()];
     }
+    /// Sets the output channel capacity
+    pub fn bounded(&mut self, capacity: usize) {
+        self.capacity = vec![capacity.max(1); self.capacity.len()];
+    }
+    /// Sets the policy applied to the data when a channel is full
+    ///
+    /// The [KeepLatest](OutputPolicy::KeepLatest) policy sets the channel capacity to 1
+    pub fn policy(&mut self, policy: OutputPolicy) {
+        if policy == OutputPolicy::KeepLatest {
+            self.bounded(1);
+        }
+        self.policy = policy;
+    }
     /// Sets the bootstrapping flag
     pub fn bootstrap(&mut self) {
         self.bootstrap = true;
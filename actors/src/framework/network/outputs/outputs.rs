@@ -37,4 +37,22 @@ where
         let Self { actor, builder } = self;
         ActorOutput::build_output(actor, builder)
     }
+    #[cfg(feature = "filing")]
+    fn build_checkpointable<U>(self) -> std::result::Result<(), OutputRx<U, C, NI, NO>>
+    where
+        C: Write<U>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let Self { actor, builder } = self;
+        let output_rx = ActorOutput::build_output(actor, builder);
+        if let Some(output) = actor.outputs.iter_mut().flatten().find_map(|output| {
+            output
+                .as_mut_any()
+                .downcast_mut::<crate::actor::io::Output<C, U, NO>>()
+        }) {
+            output.checkpointable();
+        }
+        output_rx
+    }
 }
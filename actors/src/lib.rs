@@ -74,10 +74,15 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 pub use gmt_dos_actors_dsl::actorscript;
+/// Clients interface, used by the systems generated by [actorscript]
+pub use interface;
 
 pub mod actor;
 pub mod aggregation;
 pub mod client;
+pub mod clock;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
 pub mod framework;
 pub mod graph;
 pub mod model;
@@ -107,6 +112,8 @@ pub enum ActorError {
     NoClient,
     #[error("output {0} dropped")]
     Disconnected(String),
+    #[error("{0} cancelled")]
+    Cancelled(String),
     #[error("{0} has some inputs but inputs rate is zero")]
     SomeInputsZeroRate(String),
     #[error("{0} has no inputs but a positive inputs rate (May be this Actor should instead be an Initiator)")]
@@ -117,6 +124,19 @@ pub enum ActorError {
     NoOutputsPositiveRate(String),
     #[error(r#"Orphan output "{0}" in "{1}" actor"#)]
     OrphanOutput(String, String),
+    #[error("{0} is a rate transition between clock domains and cannot be stepped")]
+    Transition(String),
+    #[error("{0} is an event-driven actor and cannot be stepped")]
+    EventDriven(String),
+    #[error("{0} has only sample-and-hold inputs, at least one input must wait for new data")]
+    HoldOnly(String),
+    #[error("{0} has no output {1}")]
+    NoOutput(String, String),
+    #[error("{0} has no input {1}")]
+    NoInput(String, String),
+    #[cfg(feature = "filing")]
+    #[error("failed to record {0}")]
+    Record(String, #[source] model::record::RecordError),
 }
 pub(crate) type Result<R> = std::result::Result<R, ActorError>;
 
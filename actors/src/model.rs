@@ -10,6 +10,15 @@ The model has 4 states:
  3. [Running]: model state while all the actors are performing their respective tasks, the model can move to the [Running] state only from the [Ready] state
  4. [Completed]: model state after the succesful completion of the tasks of all the actors, the model can move to the [Completed] state only from the [Running] state
 
+Alternatively to the [Running] state, a [Ready] model can move to the [Stepping] state
+where the actors are run, one simulation sample at a time, by a deterministic single-threaded executor
+(see [Model::stepping]).
+A [Running] model can be stopped before its completion with a [cancel::Cancellation] handle
+and it can be monitored for deadlocks with a [watchdog].
+With the `profiling` feature, the timings of the actors of a [Completed] model are given by [Model::profile].
+With the `filing` feature, a [Stepping] or a [Running] model can be saved to and resumed from a [checkpoint] file.
+The random number generators of the clients are seeded with [Model::seed].
+
 # Example
 
 A 3 actors model with [Signals], [Sampler] and [Logging] clients is build with:
@@ -168,7 +177,8 @@ let data: &[f64]  = &logging.lock().await;
 */
 
 use crate::framework::model::{CheckError, Task, TaskError};
-use std::{fmt::Display, marker::PhantomData, time::Instant};
+use interface::Seed;
+use std::{collections::HashMap, fmt::Display, marker::PhantomData, time::Instant};
 
 mod flowchart;
 use tokio::task::JoinHandle;
@@ -185,6 +195,24 @@ pub enum ModelError {
     Task(#[from] Box<TaskError>),
     #[error("error in Check implementation")]
     Check(#[from] Box<CheckError>),
+    #[error("no actor can move forward at sample {0}, stalled actors: {1}")]
+    Stalled(usize, String),
+    #[error("sampling rates inconsistency")]
+    Rates(#[from] RateError),
+    #[error("physical units inconsistency")]
+    Units(#[from] Box<UnitsError>),
+    #[error("data sizes inconsistency")]
+    Sizes(#[from] Box<SizeError>),
+    #[error("the model has been cancelled")]
+    Cancelled,
+    #[error("the model has timed out after {0:?}")]
+    TimedOut(std::time::Duration),
+    #[cfg(feature = "filing")]
+    #[error("model checkpoint failed")]
+    Checkpoint(#[from] checkpoint::CheckpointError),
+    #[cfg(feature = "dynamic")]
+    #[error("dynamic model assembly failed")]
+    Dynamic(#[from] crate::dynamic::DynamicError),
 }
 
 type Result<T> = std::result::Result<T, ModelError>;
@@ -197,6 +225,8 @@ pub enum Ready {}
 pub enum Running {}
 /// [Model] final state
 pub enum Completed {}
+/// [Model] state for the deterministic single-threaded executor
+pub enum Stepping {}
 
 type Actors = Vec<Box<dyn Task>>;
 
@@ -209,6 +239,13 @@ pub struct Model<State> {
     pub(crate) start: Instant,
     pub(crate) verbose: bool,
     pub(crate) elapsed_time: f64,
+    pub(crate) stepper: Option<stepping::Stepper>,
+    pub(crate) cancellation: Option<cancel::Cancellation>,
+    pub(crate) monitor: Option<watchdog::Monitor>,
+    #[cfg(feature = "filing")]
+    pub(crate) checkpointer: Option<checkpoint::Checkpointer>,
+    #[cfg(feature = "profiling")]
+    pub(crate) profiler: Option<profiling::Profiler>,
 }
 
 impl<S> Display for Model<S> {
@@ -263,15 +300,84 @@ impl<S> Model<S> {
     }
 }
 
+impl<S: UnknownOrReady> Model<S> {
+    /// Seeds the random number generators of the clients
+    ///
+    /// The seed is given to all the clients with [Update::seed](interface::Update::seed).
+    /// Each client is given a random stream derived from the model `seed` and from the name of its actor,
+    /// so adding or removing an actor does not change the random streams of the other actors.
+    /// Unnamed actors are named after the type of their client,
+    /// actors with the same name are told apart by their rank among them in the model
+    ///
+    /// ```
+    /// use gmt_dos_actors::prelude::*;
+    /// use interface::{Seed, Update};
+    ///
+    /// #[derive(Default)]
+    /// struct Dice(Option<Seed>);
+    /// impl Update for Dice {
+    ///     fn seed(&mut self, seed: Seed) {
+    ///         self.0 = Some(seed);
+    ///     }
+    /// }
+    ///
+    /// let red = Dice::default().into_arcx();
+    /// let blue = Dice::default().into_arcx();
+    /// let red_actor = Actor::<_, 0, 0>::new(red.clone()).name("red");
+    /// let blue_actor = Actor::<_, 0, 0>::new(blue.clone()).name("blue");
+    /// let _model = model!(red_actor, blue_actor).seed(42);
+    /// assert_eq!(red.blocking_lock().0, Some(Seed::new(42).derive("red")));
+    /// assert_ne!(red.blocking_lock().0, blue.blocking_lock().0);
+    ///
+    /// // unnamed actors of the same client type get different random streams
+    /// let one = Dice::default().into_arcx();
+    /// let two = Dice::default().into_arcx();
+    /// let one_actor = Actor::<_, 0, 0>::new(one.clone());
+    /// let two_actor = Actor::<_, 0, 0>::new(two.clone());
+    /// let _model = model!(one_actor, two_actor).seed(42);
+    /// assert_ne!(one.blocking_lock().0, two.blocking_lock().0);
+    /// ```
+    pub fn seed(mut self, seed: u64) -> Self {
+        let mut ranks: HashMap<String, usize> = HashMap::new();
+        for actor in self.actors.iter_mut().flatten() {
+            let rank = ranks
+                .entry(actor.as_plain().client)
+                .and_modify(|rank| *rank += 1)
+                .or_default();
+            actor.seed(match rank {
+                0 => Seed::new(seed),
+                rank => Seed::new(seed).derive(&rank.to_string()),
+            });
+        }
+        self
+    }
+}
+
 #[doc(hidden)]
 pub trait UnknownOrReady {}
 impl UnknownOrReady for Unknown {}
 impl UnknownOrReady for Ready {}
 
+pub mod cancel;
+#[cfg(feature = "filing")]
+pub mod checkpoint;
+mod edges;
 mod plain;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+mod rates;
+pub use rates::RateError;
 pub mod ready;
+mod sizes;
+pub use sizes::SizeError;
+#[cfg(feature = "filing")]
+pub mod record;
 pub mod running;
+pub mod stepping;
+mod units;
 pub mod unknown;
+pub use units::UnitsError;
+pub mod watchdog;
 pub use plain::PlainModel;
 
 // mod task;
@@ -0,0 +1,240 @@
+/*!
+# Model cancellation
+
+A [Running](super::Running) model can be stopped before its natural completion with the [Cancellation] handle
+returned by [Model::cancellation](super::Model::cancellation), after a wall-clock budget with
+[Model::timeout](super::Model::timeout) or with Ctrl-C with [Model::cancel_on_ctrl_c](super::Model::cancel_on_ctrl_c).
+
+The cancellation is cooperative: the actors without inputs stop sending data
+and the other actors terminate either once their inputs are disconnected
+or when they are blocked on a channel, after having processed all the data waiting in their input channels.
+A model without actors without inputs, or with actors waiting on each other, can then be cancelled as well.
+A cancelled model completes with either a [Cancelled](super::ModelError::Cancelled)
+or a [TimedOut](super::ModelError::TimedOut) error.
+*/
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Notify;
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+const TIMED_OUT: u8 = 2;
+const COMPLETED: u8 = 3;
+
+tokio::task_local! {
+    static CANCELLATION: Cancellation;
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: AtomicU8,
+    notify: Notify,
+}
+
+/// Handle to stop a running model
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation(Arc<Inner>);
+
+impl Cancellation {
+    /// Creates a new handle that is cancelled when the handle of the model the current task belongs to is
+    pub(crate) fn new() -> Self {
+        let this = Self::default();
+        if let Ok(parent) = CANCELLATION.try_with(Clone::clone) {
+            let child = this.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = parent.stopped() => child.set(parent.0.state.load(Ordering::Acquire)),
+                    _ = child.stopped() => (),
+                }
+            });
+        }
+        this
+    }
+    fn set(&self, state: u8) {
+        if self
+            .0
+            .state
+            .compare_exchange(RUNNING, state, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.0.notify.notify_waiters();
+        }
+    }
+    /// Stops the model
+    pub fn cancel(&self) {
+        self.set(CANCELLED);
+    }
+    pub(crate) fn time_out(&self) {
+        self.set(TIMED_OUT);
+    }
+    pub(crate) fn complete(&self) {
+        self.set(COMPLETED);
+    }
+    /// Returns `true` if the model has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.0.state.load(Ordering::Acquire), CANCELLED | TIMED_OUT)
+    }
+    pub(crate) fn is_timed_out(&self) -> bool {
+        self.0.state.load(Ordering::Acquire) == TIMED_OUT
+    }
+    /// Waits for the model to be either cancelled or completed
+    pub(crate) async fn stopped(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.0.state.load(Ordering::Acquire) != RUNNING {
+                return;
+            }
+            notified.await;
+        }
+    }
+    /// Waits for the model to be cancelled
+    async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+    /// Runs the future until completion unless the model the current task belongs to is cancelled first
+    ///
+    /// The future is given priority over the cancellation
+    pub(crate) async fn until_cancelled<F: Future>(future: F) -> Option<F::Output> {
+        let Ok(cancellation) = CANCELLATION.try_with(Clone::clone) else {
+            return Some(future.await);
+        };
+        tokio::select! {
+            biased;
+            output = future => Some(output),
+            _ = cancellation.cancelled() => None,
+        }
+    }
+    /// Runs the future with the handle attached to the current task
+    pub(crate) fn scope<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        CANCELLATION.scope(self.clone(), future)
+    }
+    /// Returns `true` if the model the current task belongs to has been cancelled
+    pub(crate) fn current() -> bool {
+        CANCELLATION
+            .try_with(|cancellation| cancellation.is_cancelled())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{model::ModelError, prelude::*};
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+    use std::time::Duration;
+
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = usize;
+    }
+
+    #[derive(Default)]
+    struct Counter(usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[derive(Default)]
+    struct Logger(Vec<usize>);
+    impl Update for Logger {}
+    impl Read<Count> for Logger {
+        fn read(&mut self, data: Data<Count>) {
+            self.0.push(*data);
+        }
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let mut counter: Initiator<_> = Counter::default().into();
+        let logger = Logger::default().into_arcx();
+        let mut log: Terminator<_> = Actor::new(logger.clone());
+        counter
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut log)
+            .unwrap();
+        let result = model!(counter, log)
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .timeout(Duration::from_millis(50))
+            .await;
+        assert!(matches!(result, Err(ModelError::TimedOut(_))));
+        let data = &logger.lock().await.0;
+        assert!(!data.is_empty());
+        assert!(data.iter().enumerate().all(|(i, &x)| x == i + 1));
+    }
+
+    enum Echo {}
+    impl UniqueIdentifier for Echo {
+        type DataType = usize;
+    }
+    #[derive(Default)]
+    struct Ping(usize);
+    impl Update for Ping {}
+    impl Read<Count> for Ping {
+        fn read(&mut self, data: Data<Count>) {
+            self.0 = *data + 1;
+        }
+    }
+    impl Write<Echo> for Ping {
+        fn write(&mut self) -> Option<Data<Echo>> {
+            Some(Data::new(self.0))
+        }
+    }
+    #[derive(Default)]
+    struct Pong(usize);
+    impl Update for Pong {}
+    impl Read<Echo> for Pong {
+        fn read(&mut self, data: Data<Echo>) {
+            self.0 = *data;
+        }
+    }
+    impl Write<Count> for Pong {
+        fn write(&mut self) -> Option<Data<Count>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn timeout_without_initiator() {
+        let mut ping: Actor<_> = Ping::default().into();
+        let mut pong: Actor<_> = Pong::default().into();
+        ping.add_output()
+            .bootstrap()
+            .build::<Echo>()
+            .into_input(&mut pong)
+            .unwrap();
+        pong.add_output()
+            .build::<Count>()
+            .into_input(&mut ping)
+            .unwrap();
+        let result = model!(ping, pong)
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .timeout(Duration::from_millis(50))
+            .await;
+        assert!(matches!(result, Err(ModelError::TimedOut(_))));
+    }
+}
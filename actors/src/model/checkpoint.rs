@@ -0,0 +1,677 @@
+/*!
+# Model checkpoints
+
+A [Model] in either the [Stepping] or the [Running] state can be saved to a file at a sample boundary
+with [Model::checkpoint] and later resumed with [Model::resume].
+A running model is paused at the next sample boundary all its actors can reach,
+the checkpoint is written and the model carries on.
+A resumed model is in the [Stepping] state and can be moved forward either with
+the deterministic executor or with the async runtime with [Model::run].
+
+Resuming a model requires to rebuild the same model, i.e. with the same actors and the same connections,
+the state of the clients and the data in flight in the channels are then restored from the checkpoint file.
+
+For a client to be saved, the client must implement the [serde] traits and its actor
+must be flagged with [Actor::checkpointable](crate::actor::Actor::checkpointable).
+The data that has been sent but not received yet at the time of the checkpoint is saved only for
+the outputs build with [build_checkpointable](crate::framework::network::AddOuput::build_checkpointable).
+The clients that cannot be saved are reported by name when the checkpoint is taken.
+
+Event-driven actors and rate transitions between clock domains do not have sample boundaries,
+a running model with such actors cannot be checkpointed.
+
+```ignore
+let mut running = model.run();
+tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+running.checkpoint("model.chkpt").await?;
+running.await?;
+// later, with the same model
+model.resume("model.chkpt")?.run().await?;
+```
+*/
+
+use super::{Model, Ready, Result, Running, Stepping};
+use crate::framework::model::{Stage, StepCursor, Task};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    future::Future,
+    io::{BufReader, BufWriter},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Notify;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("the clients of {0} cannot be serialized, use `Actor::checkpointable`")]
+    NotSerializable(String),
+    #[error("output {output} of {actor} has data in flight, use `build_checkpointable`")]
+    InFlight { actor: String, output: String },
+    #[error("{0} are not at a sample boundary")]
+    Boundary(String),
+    #[error("system {0} must be flattened into its actors")]
+    System(String),
+    #[error("all the actors have completed")]
+    Completed,
+    #[error("the checkpoint does not match the model: {0}")]
+    Mismatch(String),
+    #[error("failed to access the checkpoint file")]
+    IO(#[from] std::io::Error),
+    #[error("failed to encode the checkpoint")]
+    Encode(#[from] bincode::error::EncodeError),
+    #[error("failed to decode the checkpoint")]
+    Decode(#[from] bincode::error::DecodeError),
+}
+
+/// Data in flight from an actor output
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutputCheckpoint {
+    pub(crate) hash: u64,
+    /// the last data sent by the output
+    pub(crate) history: Vec<Vec<u8>>,
+    /// the number of data waiting in each channel
+    pub(crate) lens: Vec<usize>,
+}
+
+/// Actor client state and data in flight
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActorCheckpoint {
+    pub(crate) name: String,
+    pub(crate) client: Vec<u8>,
+    pub(crate) outputs: Vec<OutputCheckpoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    id: usize,
+    cursor: StepCursor,
+    actor: ActorCheckpoint,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    name: Option<String>,
+    sample: usize,
+    n_actors: usize,
+    entries: Vec<Entry>,
+}
+impl Checkpoint {
+    fn save(&self, path: impl AsRef<Path>) -> std::result::Result<(), CheckpointError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        bincode::serde::encode_into_std_write(self, &mut file, bincode::config::standard())?;
+        Ok(())
+    }
+}
+
+tokio::task_local! {
+    static CHECKPOINTER: Checkpointer;
+}
+
+/// Pending checkpoint of a running model
+#[derive(Debug)]
+struct Request {
+    /// the sample the actors are paused at
+    target: usize,
+    paused: usize,
+    /// set once all the actors are paused
+    snapshot: bool,
+    entries: Vec<Entry>,
+    errors: Vec<CheckpointError>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// number of actors of the flattened model
+    n_actors: usize,
+    /// number of actors that are running
+    expected: usize,
+    /// the last sample boundary reached by each actor
+    samples: HashMap<usize, usize>,
+    done: usize,
+    unpausable: Vec<String>,
+    request: Option<Request>,
+    generation: usize,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: Mutex<State>,
+    notify: Notify,
+}
+impl Inner {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    /// Waits for the predicate on the state to be `Some`
+    async fn until<T>(&self, mut predicate: impl FnMut(&mut State) -> Option<T>) -> T {
+        loop {
+            let notified = self.notify.notified();
+            if let Some(value) = predicate(&mut self.state()) {
+                return value;
+            }
+            notified.await;
+        }
+    }
+    /// Resumes the paused actors
+    fn release(&self) {
+        let mut state = self.state();
+        state.request = None;
+        state.generation += 1;
+        drop(state);
+        self.notify.notify_waiters();
+    }
+}
+
+/// Resumes the paused actors even if the checkpoint is dropped before completion
+struct Release<'a>(&'a Inner);
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// Pauses the actors of a [Running] model at a sample boundary
+///
+/// The handle holds the index of the actor in the flattened model,
+/// or the index of the first actor of a system for the models of systems
+#[derive(Debug, Clone)]
+pub(crate) struct Checkpointer(Arc<Inner>, usize);
+
+impl Checkpointer {
+    /// Creates a new handle for a model of `expected` running actors out of `n_actors`
+    ///
+    /// Within a model, the handle of the model the current task belongs to is returned instead
+    pub(crate) fn new(n_actors: usize, expected: usize) -> Self {
+        CHECKPOINTER.try_with(Clone::clone).unwrap_or_else(|_| {
+            let inner = Inner::default();
+            *inner.state() = State {
+                n_actors,
+                expected,
+                ..Default::default()
+            };
+            Self(Arc::new(inner), 0)
+        })
+    }
+    /// Runs the future of the actor at index `offset` from the handle index with the handle attached to the current task
+    pub(crate) fn scope<F: Future>(
+        &self,
+        offset: usize,
+        future: F,
+    ) -> impl Future<Output = F::Output> {
+        CHECKPOINTER.scope(Self(self.0.clone(), self.1 + offset), future)
+    }
+    /// Flags the actor of the current task as completed
+    pub(crate) fn done() {
+        if let Ok(inner) = CHECKPOINTER.try_with(|this| this.0.clone()) {
+            inner.state().done += 1;
+            inner.notify.notify_waiters();
+        }
+    }
+    /// Flags the actor of the current task as an actor without sample boundaries
+    pub(crate) fn unpausable(name: String) {
+        if let Ok(inner) = CHECKPOINTER.try_with(|this| this.0.clone()) {
+            inner.state().unpausable.push(name);
+            inner.notify.notify_waiters();
+        }
+    }
+    /// Pauses the actor of the current task if a checkpoint is requested at this `sample`
+    ///
+    /// Once all the actors are paused, the actor is saved into the checkpoint
+    /// and waits for the checkpoint to be written
+    pub(crate) async fn boundary(sample: usize, task: &dyn Task) {
+        let Ok(Self(inner, id)) = CHECKPOINTER.try_with(Clone::clone) else {
+            return;
+        };
+        let generation = {
+            let mut state = inner.state();
+            state.samples.insert(id, sample);
+            let generation = state.generation;
+            match state.request.as_mut() {
+                Some(request) if request.target == sample => request.paused += 1,
+                _ => return,
+            }
+            generation
+        };
+        inner.notify.notify_waiters();
+        let snapshot = inner
+            .until(|state| {
+                (state.generation != generation)
+                    .then_some(false)
+                    .or_else(|| {
+                        state
+                            .request
+                            .as_ref()
+                            .is_some_and(|request| request.snapshot)
+                            .then_some(true)
+                    })
+            })
+            .await;
+        if !snapshot {
+            return;
+        }
+        let result = task.checkpoint();
+        if let Some(request) = inner.state().request.as_mut() {
+            match result {
+                Ok(actor) => request.entries.push(Entry {
+                    id,
+                    cursor: StepCursor {
+                        sample,
+                        stage: Stage::Collect,
+                        sent: vec![],
+                    },
+                    actor,
+                }),
+                Err(e) => request.errors.push(e),
+            }
+        }
+        inner.notify.notify_waiters();
+        inner
+            .until(|state| (state.generation != generation).then_some(()))
+            .await;
+    }
+    /// Pauses the actors at the next sample boundary they can all reach and saves them
+    async fn checkpoint(&self) -> std::result::Result<Checkpoint, CheckpointError> {
+        let inner = &*self.0;
+        {
+            let mut state = inner.state();
+            if !state.unpausable.is_empty() {
+                return Err(CheckpointError::Boundary(state.unpausable.join(", ")));
+            }
+            if state.done == state.expected {
+                return Err(CheckpointError::Completed);
+            }
+            state.request = Some(Request {
+                target: state.samples.values().max().map_or(0, |sample| sample + 1),
+                paused: 0,
+                snapshot: false,
+                entries: vec![],
+                errors: vec![],
+            });
+        }
+        let _release = Release(inner);
+        let paused = inner
+            .until(|state| {
+                if !state.unpausable.is_empty() {
+                    return Some(Err(CheckpointError::Boundary(state.unpausable.join(", "))));
+                }
+                let (done, expected) = (state.done, state.expected);
+                let request = state.request.as_mut()?;
+                if request.paused + done < expected {
+                    return None;
+                }
+                if request.paused == 0 {
+                    return Some(Err(CheckpointError::Completed));
+                }
+                request.snapshot = true;
+                Some(Ok(request.paused))
+            })
+            .await?;
+        inner.notify.notify_waiters();
+        let (target, entries, errors) = inner
+            .until(|state| {
+                let request = state.request.as_mut()?;
+                (request.entries.len() + request.errors.len() == paused).then(|| {
+                    (
+                        request.target,
+                        std::mem::take(&mut request.entries),
+                        std::mem::take(&mut request.errors),
+                    )
+                })
+            })
+            .await;
+        let mut not_serializable = vec![];
+        for e in errors {
+            match e {
+                CheckpointError::NotSerializable(name) => not_serializable.push(name),
+                e => return Err(e),
+            }
+        }
+        if !not_serializable.is_empty() {
+            return Err(CheckpointError::NotSerializable(
+                not_serializable.join(", "),
+            ));
+        }
+        let n_actors = inner.state().n_actors;
+        Ok(Checkpoint {
+            name: None,
+            sample: target,
+            n_actors,
+            entries,
+        })
+    }
+}
+
+impl Model<Stepping> {
+    /// Saves the state of the model to a file
+    ///
+    /// The model must be at a sample boundary, meaning that all the actors have processed
+    /// the same number of samples
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> Result<&Self> {
+        let (Some(actors), Some(stepper)) = (self.actors.as_ref(), self.stepper.as_ref()) else {
+            return Ok(self);
+        };
+        let k = stepper.sample;
+        let behind: Vec<_> = actors
+            .iter()
+            .zip(&stepper.cursors)
+            .filter(|(_, cursor)| {
+                !matches!(
+                    (cursor.sample == k, &cursor.stage),
+                    (true, Stage::Collect) | (true, Stage::Start)
+                )
+            })
+            .map(|(actor, _)| actor.as_plain().client)
+            .collect();
+        if !behind.is_empty() {
+            return Err(CheckpointError::Boundary(behind.join(", ")).into());
+        }
+        let mut entries = vec![];
+        let mut not_serializable = vec![];
+        for ((actor, cursor), &id) in actors.iter().zip(&stepper.cursors).zip(&stepper.ids) {
+            match actor.checkpoint() {
+                Ok(actor) => entries.push(Entry {
+                    id,
+                    cursor: cursor.clone(),
+                    actor,
+                }),
+                Err(CheckpointError::NotSerializable(name)) => not_serializable.push(name),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if !not_serializable.is_empty() {
+            return Err(CheckpointError::NotSerializable(not_serializable.join(", ")).into());
+        }
+        Checkpoint {
+            name: self.name.clone(),
+            sample: k,
+            n_actors: stepper.n_actors,
+            entries,
+        }
+        .save(path)?;
+        self.verbose.then(|| {
+            eprintln!(
+                "[{}] CHECKPOINT @ sample {k}",
+                self.get_name().to_uppercase()
+            )
+        });
+        Ok(self)
+    }
+}
+
+impl Model<Running> {
+    /// Saves the state of the model to a file
+    ///
+    /// The actors are paused at the next sample boundary they can all reach,
+    /// the state of the model is saved and the actors are resumed.
+    /// The actors that have completed their tasks are not part of the checkpoint
+    pub async fn checkpoint(&mut self, path: impl AsRef<Path>) -> Result<&mut Self> {
+        let Some(checkpointer) = self.checkpointer.as_ref() else {
+            return Ok(self);
+        };
+        let mut checkpoint = checkpointer.checkpoint().await?;
+        checkpoint.name = self.name.clone();
+        checkpoint.save(path)?;
+        self.verbose.then(|| {
+            eprintln!(
+                "[{}] CHECKPOINT @ sample {}",
+                self.get_name().to_uppercase(),
+                checkpoint.sample
+            )
+        });
+        Ok(self)
+    }
+}
+
+impl Model<Ready> {
+    /// Resumes the model from a checkpoint file
+    ///
+    /// The model must be the same as the one the checkpoint has been created from.
+    /// The actors that had completed their tasks at the time of the checkpoint are dropped,
+    /// the other actors get their client state and the content of their output channels
+    /// restored from the checkpoint
+    pub fn resume(self, path: impl AsRef<Path>) -> Result<Model<Stepping>> {
+        let mut file = BufReader::new(File::open(path).map_err(CheckpointError::from)?);
+        let checkpoint: Checkpoint =
+            bincode::serde::decode_from_std_read(&mut file, bincode::config::standard())
+                .map_err(CheckpointError::from)?;
+        let mut model = self.stepping();
+        let (Some(actors), Some(stepper)) = (model.actors.as_mut(), model.stepper.as_mut()) else {
+            return Err(CheckpointError::Mismatch("the model has no actors".into()).into());
+        };
+        if stepper.n_actors != checkpoint.n_actors {
+            return Err(CheckpointError::Mismatch(format!(
+                "expected {} actors, found {}",
+                checkpoint.n_actors, stepper.n_actors
+            ))
+            .into());
+        }
+        let mut entries: HashMap<_, _> = checkpoint
+            .entries
+            .into_iter()
+            .map(|entry| (entry.id, entry))
+            .collect();
+        let mut restored = vec![];
+        let mut completed = vec![];
+        let mut cursors = vec![];
+        let mut ids = vec![];
+        // the actors are kept in topological order
+        for (mut task, id) in actors.drain(..).zip(stepper.ids.drain(..)) {
+            let Some(Entry { cursor, actor, .. }) = entries.remove(&id) else {
+                completed.push(task);
+                continue;
+            };
+            let name = task.as_plain().client;
+            if name != actor.name {
+                return Err(CheckpointError::Mismatch(format!(
+                    "expected actor {} at #{id}, found {name}",
+                    actor.name
+                ))
+                .into());
+            }
+            task.restore(actor)?;
+            restored.push(task);
+            cursors.push(cursor);
+            ids.push(id);
+        }
+        if let Some(id) = entries.keys().min() {
+            return Err(CheckpointError::Mismatch(format!("no actor #{id}")).into());
+        }
+        // the actors that had completed are dropped, closing their channels
+        drop(completed);
+        *actors = restored;
+        stepper.cursors = cursors;
+        stepper.ids = ids;
+        stepper.sample = checkpoint.sample;
+        model.name = checkpoint.name.or(model.name);
+        model.verbose.then(|| {
+            eprintln!(
+                "[{}] RESUMED @ sample {}",
+                model.get_name().to_uppercase(),
+                checkpoint.sample
+            )
+        });
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CheckpointError;
+    use crate::{model::ModelError, prelude::*};
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+    use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = f64;
+    }
+    enum Total {}
+    impl UniqueIdentifier for Total {
+        type DataType = f64;
+    }
+    enum Feedback {}
+    impl UniqueIdentifier for Feedback {
+        type DataType = f64;
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Counter(usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            // leaves time to checkpoint the running model
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            (self.0 <= 20).then(|| Data::new(self.0 as f64))
+        }
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct Sum(f64, f64, f64);
+    impl Update for Sum {
+        fn update(&mut self) {
+            self.0 = self.1 + 0.5 * self.2;
+        }
+    }
+    impl Read<Count> for Sum {
+        fn read(&mut self, data: Data<Count>) {
+            self.1 = *data;
+        }
+    }
+    impl Read<Feedback> for Sum {
+        fn read(&mut self, data: Data<Feedback>) {
+            self.2 = *data;
+        }
+    }
+    impl Write<Total> for Sum {
+        fn write(&mut self) -> Option<Data<Total>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct Delay(f64);
+    impl Update for Delay {}
+    impl Read<Total> for Delay {
+        fn read(&mut self, data: Data<Total>) {
+            self.0 = *data;
+        }
+    }
+    impl Write<Feedback> for Delay {
+        fn write(&mut self) -> Option<Data<Feedback>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[derive(Default, Serialize, Deserialize)]
+    struct Logger(Vec<f64>);
+    impl Update for Logger {}
+    impl Read<Total> for Logger {
+        fn read(&mut self, data: Data<Total>) {
+            self.0.push(*data);
+        }
+    }
+
+    fn model(logger: Arc<Mutex<Logger>>) -> Model<model::Ready> {
+        let mut counter: Initiator<_> = Actor::from(Counter(0)).checkpointable();
+        let mut sum: Actor<_> = Actor::from(Sum::default()).checkpointable();
+        let mut delay: Actor<_> = Actor::from(Delay::default()).checkpointable();
+        let mut log: Terminator<_> = Actor::new(logger).checkpointable();
+        counter
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut sum)
+            .unwrap();
+        sum.add_output()
+            .multiplex(2)
+            .build::<Total>()
+            .into_input(&mut delay)
+            .into_input(&mut log)
+            .unwrap();
+        delay
+            .add_output()
+            .bootstrap()
+            .build_checkpointable::<Feedback>()
+            .into_input(&mut sum)
+            .unwrap();
+        model!(counter, sum, delay, log).quiet().check().unwrap()
+    }
+
+    #[test]
+    fn resume() {
+        let logger = Logger::default().into_arcx();
+        model(logger.clone())
+            .stepping()
+            .run_until(|_| false)
+            .unwrap();
+        let expected = std::mem::take(&mut logger.blocking_lock().0);
+
+        let path = std::env::temp_dir().join("gmt_dos-actors_checkpoint.bin");
+        let mut stepping = model(logger.clone()).stepping();
+        stepping.step_n(7).unwrap().checkpoint(&path).unwrap();
+        stepping.complete();
+
+        let logger = Logger::default().into_arcx();
+        let mut stepping = model(logger.clone()).resume(&path).unwrap();
+        assert_eq!(stepping.sample(), 7);
+        stepping.run_until(|_| false).unwrap();
+        assert_eq!(logger.blocking_lock().0, expected);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resume_running() {
+        let logger = Logger::default().into_arcx();
+        model(logger.clone()).run().await.unwrap();
+        let expected = std::mem::take(&mut logger.lock().await.0);
+
+        let path = std::env::temp_dir().join("gmt_dos-actors_checkpoint_running.bin");
+        let mut running = model(logger.clone()).run();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        running.checkpoint(&path).await.unwrap();
+        running.await.unwrap();
+        assert_eq!(logger.lock().await.0, expected);
+
+        let logger = Logger::default().into_arcx();
+        model(logger.clone())
+            .resume(&path)
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(logger.lock().await.0, expected);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn not_serializable() {
+        let mut counter: Initiator<_> = Actor::from(Counter(0)).checkpointable();
+        let mut sum: Actor<_> = Sum::default().into();
+        let mut log: Terminator<_> = Actor::new(Logger::default().into_arcx());
+        counter
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut sum)
+            .unwrap();
+        sum.add_output()
+            .build::<Total>()
+            .into_input(&mut log)
+            .unwrap();
+        let mut running = model!(counter, sum, log).quiet().check().unwrap().run();
+        let path = std::env::temp_dir().join("gmt_dos-actors_checkpoint_error.bin");
+        let result = running.checkpoint(&path).await;
+        assert!(matches!(
+            result,
+            Err(ModelError::Checkpoint(CheckpointError::NotSerializable(names)))
+                if names.split(", ").count() == 2
+        ));
+        running.await.unwrap();
+    }
+}
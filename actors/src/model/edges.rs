@@ -0,0 +1,51 @@
+//! Edges of the model graph
+//!
+//! An edge links the output of a producer to the input of a consumer with the same hash.
+
+use std::collections::HashMap;
+
+use crate::actor::{plain::IO, PlainActor};
+
+/// An edge of the model graph
+pub(crate) struct Edge<'a> {
+    pub producer: &'a PlainActor,
+    pub output: &'a IO,
+    pub consumer: &'a PlainActor,
+    pub input: &'a IO,
+}
+
+/// Returns the edges of the model graph
+///
+/// The inputs that are not connected to the output of any of the `actors` are skipped
+pub(crate) fn edges(actors: &[PlainActor]) -> Vec<Edge<'_>> {
+    let producers: HashMap<u64, (&PlainActor, &IO)> = actors
+        .iter()
+        .flat_map(|actor| {
+            actor
+                .outputs
+                .iter()
+                .flatten()
+                .map(move |output| (output.hash(), (actor, output)))
+        })
+        .collect();
+    actors
+        .iter()
+        .flat_map(|consumer| {
+            consumer
+                .inputs
+                .iter()
+                .flatten()
+                .map(move |input| (consumer, input))
+        })
+        .filter_map(|(consumer, input)| {
+            producers
+                .get(&input.hash())
+                .map(|&(producer, output)| Edge {
+                    producer,
+                    output,
+                    consumer,
+                    input,
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,335 @@
+/*!
+# Model profiling
+
+With the `profiling` feature, the actors record the time spent waiting on their inputs,
+updating their client and waiting on their outputs to be sent.
+
+Once a [Model] has completed, the [ModelProfile] returned by [Model::profile] gives a report
+of the timings of each actor and of the number of data sent by each output.
+The report is printed on stderr with [Display](std::fmt::Display), it can be saved in a JSON file
+with [ModelProfile::to_json] and the model [flowchart](FlowChart) can be annotated with
+the throughput of each edge and the busy fraction of each actor.
+*/
+
+use super::{Completed, Model};
+use crate::{
+    actor::PlainActor,
+    framework::model::{FlowChart, GetName},
+    graph::Graph,
+    trim,
+};
+use serde::Serialize;
+use std::{
+    fmt::Display,
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+tokio::task_local! {
+    static PROFILER: Profiler;
+}
+
+/// Actor timings accumulator
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ActorTimer {
+    pub recv: Duration,
+    pub update: Duration,
+    pub n_update: usize,
+}
+
+/// Output timings accumulator
+#[derive(Debug, Default, Clone)]
+pub(crate) struct OutputTimer {
+    pub send: Duration,
+    pub count: usize,
+}
+
+/// Collection of the profiles of the actors of a model
+#[derive(Debug, Clone)]
+pub(crate) struct Profiler {
+    actors: Arc<Mutex<Vec<ActorProfile>>>,
+    /// `false` for the models of systems, the actors profiles are then added to the parent model profiler
+    root: bool,
+}
+impl Profiler {
+    /// Creates a new profiler or, within a model, returns the profiler of the model
+    pub fn new() -> Self {
+        PROFILER
+            .try_with(|profiler| Self {
+                actors: profiler.actors.clone(),
+                root: false,
+            })
+            .unwrap_or_else(|_| Self {
+                actors: Default::default(),
+                root: true,
+            })
+    }
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+    /// Runs the future with the profiler attached to the current task
+    pub fn scope<F: std::future::Future>(
+        &self,
+        future: F,
+    ) -> impl std::future::Future<Output = F::Output> {
+        PROFILER.scope(self.clone(), future)
+    }
+    /// Adds the actor profile to the profiler of the model the current task belongs to
+    pub fn record(profile: ActorProfile) {
+        let _ = PROFILER.try_with(|profiler| profiler.actors.lock().unwrap().push(profile));
+    }
+    pub fn report(&self, name: String, elapsed: Duration) -> ModelProfile {
+        ModelProfile {
+            name,
+            elapsed: elapsed.as_secs_f64(),
+            actors: self.actors.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Output profile
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputProfile {
+    /// output name
+    pub name: String,
+    #[serde(skip)]
+    pub(crate) hash: u64,
+    /// number of data sent
+    pub count: usize,
+    /// time spent waiting for the data to be sent `[s]`
+    pub send: f64,
+}
+impl OutputProfile {
+    pub(crate) fn new(name: String, hash: u64, timer: &OutputTimer) -> Self {
+        Self {
+            name,
+            hash,
+            count: timer.count,
+            send: timer.send.as_secs_f64(),
+        }
+    }
+}
+
+/// Actor profile
+#[derive(Debug, Clone, Serialize)]
+pub struct ActorProfile {
+    /// actor name
+    pub name: String,
+    /// duration of the actor task `[s]`
+    pub lifetime: f64,
+    /// time spent waiting on the inputs `[s]`
+    pub recv: f64,
+    /// time spent in the client update `[s]`
+    pub update: f64,
+    /// number of client updates
+    pub n_update: usize,
+    /// outputs profiles
+    pub outputs: Vec<OutputProfile>,
+    #[serde(skip)]
+    pub(crate) plain: PlainActor,
+}
+impl ActorProfile {
+    pub(crate) fn new(
+        plain: PlainActor,
+        lifetime: Duration,
+        timer: &ActorTimer,
+        outputs: Vec<OutputProfile>,
+    ) -> Self {
+        Self {
+            name: plain.client.clone(),
+            lifetime: lifetime.as_secs_f64(),
+            recv: timer.recv.as_secs_f64(),
+            update: timer.update.as_secs_f64(),
+            n_update: timer.n_update,
+            outputs,
+            plain,
+        }
+    }
+    /// Returns the total time spent waiting on the outputs `[s]`
+    pub fn send(&self) -> f64 {
+        self.outputs.iter().map(|output| output.send).sum()
+    }
+    /// Returns the fraction of the actor task duration spent in the client update
+    pub fn busy(&self) -> f64 {
+        if self.lifetime > 0. {
+            self.update / self.lifetime
+        } else {
+            0.
+        }
+    }
+}
+
+/// Model profile
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelProfile {
+    /// model name
+    pub name: String,
+    /// model duration `[s]`
+    pub elapsed: f64,
+    /// actors profiles
+    pub actors: Vec<ActorProfile>,
+}
+impl ModelProfile {
+    /// Returns the profile of the output with the given hash
+    fn output(&self, hash: u64) -> Option<&OutputProfile> {
+        self.actors
+            .iter()
+            .flat_map(|actor| actor.outputs.iter())
+            .find(|output| output.hash == hash)
+    }
+    /// Writes the profile to a JSON file
+    pub fn to_json(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self).map_err(std::io::Error::from)
+    }
+}
+
+impl Display for ModelProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} PROFILE ({})",
+            self.name.to_uppercase(),
+            humantime::format_duration(Duration::from_millis((self.elapsed * 1e3) as u64))
+        )?;
+        writeln!(
+            f,
+            " {:<40} {:>8} {:>10} {:>10} {:>10} {:>6}",
+            "ACTOR", "UPDATES", "RECV [s]", "UPDATE [s]", "SEND [s]", "BUSY"
+        )?;
+        for actor in &self.actors {
+            writeln!(
+                f,
+                " {:<40} {:>8} {:>10.3} {:>10.3} {:>10.3} {:>5.1}%",
+                trim(&actor.name),
+                actor.n_update,
+                actor.recv,
+                actor.update,
+                actor.send(),
+                actor.busy() * 1e2
+            )?;
+            for output in &actor.outputs {
+                writeln!(
+                    f,
+                    "  > {:<37} {:>8} {:>10} {:>10} {:>10.3} {:>6}",
+                    trim(&output.name),
+                    output.count,
+                    "",
+                    "",
+                    output.send,
+                    ""
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GetName for ModelProfile {
+    fn get_name(&self) -> String {
+        format!("{}_profile", self.name)
+    }
+}
+
+impl FlowChart for ModelProfile {
+    /// Returns the actors network graph annotated with the busy fraction of each actor
+    /// and the number of data per second sent through each edge
+    fn graph(&self) -> Option<Graph> {
+        let actors: Vec<_> = self
+            .actors
+            .iter()
+            .map(|actor| {
+                let mut plain = actor.plain.clone();
+                plain.client = format!("{}\\n{:.1}% busy", plain.client, actor.busy() * 1e2);
+                for input in plain.inputs.iter_mut().flatten() {
+                    let throughput = self
+                        .output(input.hash())
+                        .map(|output| output.count as f64 / self.elapsed.max(f64::EPSILON));
+                    if let Some(throughput) = throughput {
+                        input.annotate(format!("{throughput:.0}/s"));
+                    }
+                }
+                plain
+            })
+            .collect();
+        (!actors.is_empty()).then(|| Graph::new(self.get_name(), actors))
+    }
+}
+
+impl Model<Completed> {
+    /// Returns the timings of the actors
+    ///
+    /// Returns [None] if the model has been run with the deterministic executor
+    pub fn profile(&self) -> Option<ModelProfile> {
+        self.profiler
+            .as_ref()
+            .map(|profiler| profiler.report(self.get_name(), self.elapsed_time()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = usize;
+    }
+
+    #[derive(Default)]
+    struct Counter(usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            (self.0 <= 10).then(|| Data::new(self.0))
+        }
+    }
+
+    #[derive(Default)]
+    struct Logger(Vec<usize>);
+    impl Update for Logger {}
+    impl Read<Count> for Logger {
+        fn read(&mut self, data: Data<Count>) {
+            self.0.push(*data);
+        }
+    }
+
+    #[tokio::test]
+    async fn profile() {
+        let mut counter: Initiator<_> = Counter::default().into();
+        let mut log: Terminator<_> = Logger::default().into();
+        counter
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut log)
+            .unwrap();
+        let model = model!(counter, log)
+            .name("profiled")
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        let profile = model.profile().unwrap();
+        assert_eq!(profile.actors.len(), 2);
+        let counter = profile
+            .actors
+            .iter()
+            .find(|actor| actor.outputs.len() == 1)
+            .unwrap();
+        assert_eq!(counter.n_update, 11);
+        assert_eq!(counter.outputs[0].count, 10);
+        let graph = profile.graph().unwrap().to_string();
+        assert!(graph.contains("% busy"));
+        assert!(graph.contains("/s"));
+    }
+}
@@ -0,0 +1,358 @@
+//! Sampling rates consistency analysis
+//!
+//! The sampling rates are propagated along the edges of the model graph:
+//! the rate of each output must match the rate of the inputs it is connected to,
+//! the inputs and outputs rates of an actor must be integer multiples of each other
+//! (except for rate transitions between [clock domains](crate::clock))
+//! and each feedback loop must have at least one bootstrapped output.
+//!
+//! The clock domains are propagated along the edges of the model graph as well:
+//! an output can only be connected to inputs within the same clock domain.
+//!
+//! [Event-driven](crate::actor::Actor::event_driven) actors and [sample-and-hold](crate::actor::Actor::hold) inputs
+//! have no sampling rate and are not bound to a clock domain, but the outputs of an event-driven actor
+//! can only be connected to sample-and-hold inputs or to other event-driven actors.
+
+use std::collections::HashMap;
+
+use crate::{
+    actor::{plain::IO, PlainActor},
+    clock::ClockDomain,
+    trim,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RateError {
+    #[error("output {uid} of {producer} (rate {output_rate}) is connected to {consumer} (inputs rate {input_rate})")]
+    Mismatch {
+        uid: String,
+        producer: String,
+        output_rate: usize,
+        consumer: String,
+        input_rate: usize,
+    },
+    #[error("the inputs rate ({ni}) and the outputs rate ({no}) of {actor} are not integer multiples of each other")]
+    NotMultiple { actor: String, ni: usize, no: usize },
+    #[error("feedback loop without a bootstrapped output: {0}")]
+    NoBootstrap(String),
+    #[error("output {uid} of {producer} (clock domain {from}) is connected to {consumer} (clock domain {to}) without a rate transition")]
+    ClockDomain {
+        uid: String,
+        producer: String,
+        from: Box<ClockDomain>,
+        consumer: String,
+        to: Box<ClockDomain>,
+    },
+    #[error("output {uid} of the event-driven actor {producer} is connected to {consumer} without a sample-and-hold input")]
+    Event {
+        uid: String,
+        producer: String,
+        consumer: String,
+    },
+}
+
+type Result<T> = std::result::Result<T, RateError>;
+
+fn rate(ios: &Option<Vec<IO>>) -> Option<usize> {
+    ios.as_ref().and_then(|ios| ios.first()).map(|io| io.rate())
+}
+
+/// Checks the consistency of the sampling rates of the actors
+///
+/// The actors flagged as systems are ignored when looking for feedback loops
+/// as the bootstrapped outputs within a system are not visible from outside
+pub(crate) fn analyse(actors: &[(PlainActor, bool)]) -> Result<()> {
+    // actors inputs and outputs rates
+    for (actor, _) in actors {
+        if actor.event
+            || actor
+                .clocks
+                .as_ref()
+                .is_some_and(|clocks| clocks.is_transition())
+        {
+            continue;
+        }
+        if let (Some(ni), Some(no)) = (rate(&actor.inputs), rate(&actor.outputs)) {
+            if ni > 0 && no > 0 && ni.max(no) % ni.min(no) != 0 {
+                return Err(RateError::NotMultiple {
+                    actor: actor.client.clone(),
+                    ni,
+                    no,
+                });
+            }
+        }
+    }
+    // outputs hashes to producer index and output
+    let producers: HashMap<u64, (usize, &IO)> = actors
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (actor, _))| {
+            actor
+                .outputs
+                .iter()
+                .flatten()
+                .map(move |output| (output.hash(), (i, output)))
+        })
+        .collect();
+    // edges rates, clock domains and feedback loops graph
+    let mut domains = Domains::new(actors);
+    let mut downstream: Vec<Vec<(usize, &IO)>> = vec![vec![]; actors.len()];
+    for (j, (actor, _)) in actors.iter().enumerate() {
+        for input in actor.inputs.iter().flatten() {
+            let Some(&(i, output)) = producers.get(&input.hash()) else {
+                continue;
+            };
+            if input.is_held() || actor.event {
+                continue;
+            }
+            if actors[i].0.event {
+                return Err(RateError::Event {
+                    uid: trim(output.name()),
+                    producer: actors[i].0.client.clone(),
+                    consumer: actor.client.clone(),
+                });
+            }
+            if let Err((from, to)) = domains.union(2 * i + 1, 2 * j) {
+                return Err(RateError::ClockDomain {
+                    uid: trim(output.name()),
+                    producer: actors[i].0.client.clone(),
+                    from: Box::new(from),
+                    consumer: actor.client.clone(),
+                    to: Box::new(to),
+                });
+            }
+            if output.rate() != input.rate() {
+                return Err(RateError::Mismatch {
+                    uid: trim(output.name()),
+                    producer: actors[i].0.client.clone(),
+                    output_rate: output.rate(),
+                    consumer: actor.client.clone(),
+                    input_rate: input.rate(),
+                });
+            }
+            if !matches!(output, IO::Bootstrap(_)) && !actors[i].1 && !actors[j].1 {
+                downstream[i].push((j, output));
+            }
+        }
+    }
+    if let Some(cycle) = find_cycle(&downstream) {
+        let mut path: Vec<_> = cycle
+            .iter()
+            .map(|&(i, output)| format!("{} -({})->", actors[i].0.client, trim(output.name())))
+            .collect();
+        path.push(actors[cycle[0].0].0.client.clone());
+        return Err(RateError::NoBootstrap(path.join(" ")));
+    }
+    Ok(())
+}
+
+/// Clock domains of the inputs (node `2i`) and of the outputs (node `2i+1`) of the actors
+///
+/// The nodes connected to each other are merged into the same set (union-find)
+/// and each set belongs to at most one clock domain
+struct Domains {
+    parent: Vec<usize>,
+    domain: Vec<Option<ClockDomain>>,
+}
+
+impl Domains {
+    fn new(actors: &[(PlainActor, bool)]) -> Self {
+        let n = 2 * actors.len();
+        let mut this = Self {
+            parent: (0..n).collect(),
+            domain: vec![None; n],
+        };
+        for (i, (actor, is_system)) in actors.iter().enumerate() {
+            match &actor.clocks {
+                Some(clocks) => {
+                    this.domain[2 * i] = Some(clocks.inputs.clone());
+                    this.domain[2 * i + 1] = Some(clocks.outputs.clone());
+                    if !clocks.is_transition() {
+                        this.parent[2 * i + 1] = 2 * i;
+                    }
+                }
+                // the inputs and the outputs of a system are not necessarily related
+                None if !is_system => this.parent[2 * i + 1] = 2 * i,
+                None => (),
+            }
+        }
+        this
+    }
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+    /// Merges the sets of 2 nodes, returning both clock domains if they differ
+    fn union(&mut self, i: usize, j: usize) -> std::result::Result<(), (ClockDomain, ClockDomain)> {
+        let (i, j) = (self.find(i), self.find(j));
+        if i == j {
+            return Ok(());
+        }
+        match (self.domain[i].take(), self.domain[j].take()) {
+            (Some(a), Some(b)) if a != b => return Err((a, b)),
+            (a, b) => self.domain[j] = a.or(b),
+        }
+        self.parent[i] = j;
+        Ok(())
+    }
+}
+
+/// Depth-first search of a cycle in the graph
+///
+/// Returns the nodes of the cycle with the edge leaving each node
+pub(crate) fn find_cycle<T: Copy>(downstream: &[Vec<(usize, T)>]) -> Option<Vec<(usize, T)>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        New,
+        Active,
+        Done,
+    }
+    fn visit<T: Copy>(
+        i: usize,
+        downstream: &[Vec<(usize, T)>],
+        marks: &mut [Mark],
+        path: &mut Vec<(usize, T)>,
+    ) -> Option<Vec<(usize, T)>> {
+        marks[i] = Mark::Active;
+        for &(j, edge) in &downstream[i] {
+            path.push((i, edge));
+            match marks[j] {
+                Mark::Active => {
+                    let start = path.iter().position(|&(k, _)| k == j).unwrap();
+                    return Some(path.split_off(start));
+                }
+                Mark::New => {
+                    if let Some(cycle) = visit(j, downstream, marks, path) {
+                        return Some(cycle);
+                    }
+                }
+                Mark::Done => (),
+            }
+            path.pop();
+        }
+        marks[i] = Mark::Done;
+        None
+    }
+    let mut marks = vec![Mark::New; downstream.len()];
+    (0..downstream.len()).find_map(|i| {
+        (marks[i] == Mark::New)
+            .then(|| visit(i, downstream, &mut marks, &mut vec![]))
+            .flatten()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{actor::plain::IOData, clock::Clocks};
+
+    fn io(name: &str, hash: u64, rate: usize) -> IO {
+        IO::Regular(IOData::new(name.into(), hash, 1, rate))
+    }
+
+    #[test]
+    fn mismatch() {
+        let a = PlainActor::new("A").outputs(vec![io("U", 1, 10)]).build();
+        let b = PlainActor::new("B").inputs(vec![io("U", 1, 1)]).build();
+        assert!(matches!(
+            analyse(&[(a, false), (b, false)]),
+            Err(RateError::Mismatch {
+                output_rate: 10,
+                input_rate: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn not_multiple() {
+        let a = PlainActor::new("A")
+            .inputs(vec![io("U", 1, 2)])
+            .outputs(vec![io("V", 2, 3)])
+            .build();
+        assert!(matches!(
+            analyse(&[(a, false)]),
+            Err(RateError::NotMultiple { ni: 2, no: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn feedback() {
+        let a = PlainActor::new("A")
+            .inputs(vec![io("V", 2, 1)])
+            .outputs(vec![io("U", 1, 1)])
+            .build();
+        let b = PlainActor::new("B")
+            .inputs(vec![io("U", 1, 1)])
+            .outputs(vec![io("V", 2, 1)])
+            .build();
+        let err = analyse(&[(a.clone(), false), (b, false)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "feedback loop without a bootstrapped output: A -(U)-> B -(V)-> A"
+        );
+        let b = PlainActor::new("B")
+            .inputs(vec![io("U", 1, 1)])
+            .outputs(vec![IO::Bootstrap(IOData::new("V".into(), 2, 1, 1))])
+            .build();
+        assert!(analyse(&[(a, false), (b, false)]).is_ok());
+    }
+
+    #[test]
+    fn clock_domains() {
+        let fem = ClockDomain::new("FEM", 8e3);
+        let asm = ClockDomain::new("ASM", 1.2e3);
+        let clocks = |inputs: &ClockDomain, outputs: &ClockDomain| Clocks {
+            inputs: inputs.clone(),
+            outputs: outputs.clone(),
+        };
+        let a = PlainActor::new("A")
+            .outputs(vec![io("U", 1, 1)])
+            .clocks(clocks(&fem, &fem))
+            .build();
+        let b = PlainActor::new("B")
+            .inputs(vec![io("U", 1, 1)])
+            .outputs(vec![io("V", 2, 1)])
+            .build();
+        let c = PlainActor::new("C")
+            .inputs(vec![io("V", 2, 1)])
+            .clocks(clocks(&asm, &asm))
+            .build();
+        let err = analyse(&[(a.clone(), false), (b, false), (c.clone(), false)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "output V of B (clock domain FEM@8000Hz) is connected to C (clock domain ASM@1200Hz) without a rate transition"
+        );
+        let b = PlainActor::new("B")
+            .inputs(vec![io("U", 1, 1)])
+            .outputs(vec![io("V", 2, 1)])
+            .clocks(clocks(&fem, &asm))
+            .build();
+        assert!(analyse(&[(a, false), (b, false), (c, false)]).is_ok());
+    }
+
+    #[test]
+    fn event() {
+        let a = PlainActor::new("A").outputs(vec![io("U", 1, 10)]).build();
+        let b = PlainActor::new("B")
+            .inputs(vec![io("U", 1, 1)])
+            .outputs(vec![io("V", 2, 1)])
+            .event(true)
+            .build();
+        let c = PlainActor::new("C").inputs(vec![io("V", 2, 5)]).build();
+        let err = analyse(&[(a.clone(), false), (b.clone(), false), (c, false)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "output V of the event-driven actor B is connected to C without a sample-and-hold input"
+        );
+        let c = PlainActor::new("C")
+            .inputs(vec![IO::Regular(
+                IOData::new("V".into(), 2, 1, 5).hold(true),
+            )])
+            .build();
+        assert!(analyse(&[(a, false), (b, false), (c, false)]).is_ok());
+    }
+}
@@ -1,42 +1,88 @@
-use super::{Model, Ready, Running};
+#[cfg(feature = "filing")]
+use super::checkpoint::Checkpointer;
+#[cfg(feature = "profiling")]
+use super::profiling::Profiler;
+use super::{cancel::Cancellation, watchdog::Monitor, Model, Ready, Running};
+use crate::framework::model::Task;
 use chrono::{DateTime, Local, SecondsFormat};
 use std::{marker::PhantomData, time::Instant};
 
 impl Model<Ready> {
     /// Spawns each actor task
+    ///
+    /// The running model is stopped with the [handle](Model::cancellation) it carries
     pub fn run(self) -> Model<Running> {
-        let now: DateTime<Local> = Local::now();
-        self.verbose.then(|| {
-            eprintln!(
-                "[{}<{}>] LAUNCHED",
-                self.name
-                    .as_ref()
-                    .unwrap_or(&String::from("Model"))
-                    .to_uppercase(),
-                now.to_rfc3339_opts(SecondsFormat::Secs, true),
-            )
-        });
-        // let mut task_handles = vec![];
-        /*         let mut actors = self.actors.take().unwrap();
-        while let Some(mut actor) = actors.pop() {
-            task_handles.push(tokio::spawn(async move {
-                actor.task().await;
-            }));
-        } */
-        let task_handles: Vec<_> = self
+        // index of the first actor of each task in the flattened model
+        let mut n_actors = 0;
+        let tasks: Vec<_> = self
             .actors
             .into_iter()
             .flatten()
-            .map(|actor| tokio::spawn(async move { actor.task().await }))
+            .map(|actor| {
+                let id = n_actors;
+                n_actors += actor.n_actors();
+                (id, actor)
+            })
             .collect();
-        Model::<Running> {
-            name: self.name,
-            actors: None,
-            task_handles: Some(task_handles),
-            state: PhantomData,
-            start: Instant::now(),
-            verbose: self.verbose,
-            elapsed_time: Default::default(),
-        }
+        launch(self.name, self.verbose, tasks, n_actors)
+    }
+}
+
+/// Spawns the tasks of a model of `n_actors` actors once flattened
+///
+/// Each task comes with the index of its first actor in the flattened model
+#[cfg_attr(not(feature = "filing"), allow(unused_variables))]
+pub(super) fn launch(
+    name: Option<String>,
+    verbose: bool,
+    tasks: Vec<(usize, Box<dyn Task>)>,
+    n_actors: usize,
+) -> Model<Running> {
+    let now: DateTime<Local> = Local::now();
+    verbose.then(|| {
+        eprintln!(
+            "[{}<{}>] LAUNCHED",
+            name.as_ref()
+                .unwrap_or(&String::from("Model"))
+                .to_uppercase(),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+    });
+    let cancellation = Cancellation::new();
+    let monitor = Monitor::new();
+    #[cfg(feature = "filing")]
+    let checkpointer = Checkpointer::new(
+        n_actors,
+        tasks.iter().map(|(_, actor)| actor.n_actors()).sum(),
+    );
+    #[cfg(feature = "profiling")]
+    let profiler = Profiler::new();
+    let task_handles: Vec<_> = tasks
+        .into_iter()
+        .map(|(id, actor)| {
+            let task = cancellation.scope(async move { actor.task().await });
+            let task = monitor.scope(task);
+            #[cfg(feature = "filing")]
+            let task = checkpointer.scope(id, task);
+            #[cfg(feature = "profiling")]
+            let task = profiler.scope(task);
+            tokio::spawn(task)
+        })
+        .collect();
+    Model::<Running> {
+        name,
+        actors: None,
+        task_handles: Some(task_handles),
+        state: PhantomData,
+        start: Instant::now(),
+        verbose,
+        elapsed_time: Default::default(),
+        stepper: None,
+        cancellation: Some(cancellation),
+        monitor: Some(monitor),
+        #[cfg(feature = "filing")]
+        checkpointer: Some(checkpointer),
+        #[cfg(feature = "profiling")]
+        profiler: Some(profiler),
     }
 }
@@ -0,0 +1,561 @@
+/*!
+# Channels record and replay
+
+The data sent by some outputs of a model can be recorded with a [Recorder], tapping the outputs
+with [Actor::record](crate::actor::Actor::record) once the actors have been connected.
+Each recorded data is streamed to the recorder file with the index of the sample it has been sent at,
+the file is complete once the recorder and the actors are dropped or after [Recorder::flush].
+The recorded channels are identified by the name of the actor and by the output they are recorded from.
+
+The recorded data is loaded into a [Recording] and fed back into a model with the [Replay] client
+of an [Initiator](crate::prelude::Initiator),
+the replay actor outputs rate must be the same than the recorded outputs rate, see [Recording::rate].
+The replay sends each recorded data at the sample it has been recorded at.
+An actor can then be run in isolation against the recorded inputs and its outputs
+compared to the recorded outputs with [Recording::data].
+
+```
+use gmt_dos_actors::{model::record::{Recorder, Recording, Replay}, prelude::*};
+use gmt_dos_clients::{integrator::Integrator, logging::Logging, signals::{Signal, Signals}};
+use interface::UID;
+
+#[derive(UID)]
+enum U {}
+#[derive(UID)]
+enum Y {}
+
+# tokio_test::block_on(async {
+let path = std::env::temp_dir().join("gmt_dos-actors_record-doc.bin");
+let recorder = Recorder::new(&path)?;
+
+let mut source: Initiator<_> = Signals::new(1, 10).channels(Signal::Ramp { a: 1., b: 0. }).into();
+let mut controller: Actor<_> = Integrator::<U>::new(1).gain(0.5).into();
+let mut sink: Terminator<_> = Logging::<f64>::new(1).into();
+source.add_output().build::<U>().into_input(&mut controller)?;
+controller.add_output().build::<Y>().into_input(&mut sink)?;
+source.record::<U>(&recorder)?;
+controller.record::<Y>(&recorder)?;
+model!(source, controller, sink).quiet().check()?.run().await?;
+recorder.flush()?;
+
+// replaying the inputs of the controller
+let recording = Recording::from_path(&path)?;
+let mut replay: Initiator<_> = Replay::new(recording.clone()).into();
+let mut controller: Actor<_> = Integrator::<U>::new(1).gain(0.5).into();
+let logging = Logging::<f64>::new(1).into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+replay.add_output().build::<U>().into_input(&mut controller)?;
+controller.add_output().build::<Y>().into_input(&mut sink)?;
+model!(replay, controller, sink).quiet().check()?.run().await?;
+
+let outputs: Vec<_> = recording.data::<Y>()?.into_iter().map(|(_, y)| y[0]).collect();
+assert_eq!(**logging.lock().await, outputs);
+# std::fs::remove_file(path)?;
+# Ok::<(), anyhow::Error>(())
+# });
+```
+*/
+
+use crate::actor::codec::Codec;
+use interface::{Data, UniqueIdentifier, Update, Write};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    any::type_name,
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecordError {
+    #[error("no recorded channel for {0}")]
+    NoChannel(String),
+    #[error("{uid} is recorded from {producers}, select one of them")]
+    Ambiguous { uid: String, producers: String },
+    #[error(
+        "output {uid} of {producer} is already recorded, the actors must have different names"
+    )]
+    Duplicate { uid: String, producer: String },
+    #[error("failed to access the recording file")]
+    IO(#[from] std::io::Error),
+    #[error("failed to encode the recording")]
+    Encode(#[from] bincode::error::EncodeError),
+    #[error("failed to decode the recording")]
+    Decode(#[from] bincode::error::DecodeError),
+}
+
+type Result<T> = std::result::Result<T, RecordError>;
+
+/// Items of a recording file
+#[derive(Debug, Serialize, Deserialize)]
+enum Record {
+    /// a new channel
+    Channel {
+        uid: String,
+        producer: String,
+        rate: usize,
+    },
+    /// a data recorded from the channel with index `channel`
+    Sample {
+        channel: usize,
+        index: usize,
+        bytes: Vec<u8>,
+    },
+}
+
+/// Data recorded from an actor output
+#[derive(Debug, Clone)]
+pub struct Channel {
+    /// output unique identifier
+    pub(crate) uid: String,
+    /// actor the output belongs to
+    pub(crate) producer: String,
+    /// output rate
+    pub(crate) rate: usize,
+    /// sample index and encoded data
+    pub(crate) samples: Vec<(usize, Vec<u8>)>,
+}
+
+#[derive(Debug)]
+struct Writer {
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// the output unique identifier and the producer of each channel
+    channels: Vec<(String, String)>,
+}
+impl Writer {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        bincode::serde::encode_into_std_write(record, &mut self.file, bincode::config::standard())?;
+        Ok(())
+    }
+}
+
+/// Recorder of actors outputs
+///
+/// The recorder is shared between all the outputs that are tapped with [Actor::record](crate::actor::Actor::record)
+/// and the data is written to the recorder file as it is sent
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    writer: Arc<Mutex<Writer>>,
+}
+
+impl Recorder {
+    /// Creates a new recorder writing to the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            writer: Arc::new(Mutex::new(Writer {
+                path: path.as_ref().to_path_buf(),
+                file: BufWriter::new(File::create(path)?),
+                channels: vec![],
+            })),
+        })
+    }
+    fn writer(&self) -> std::sync::MutexGuard<'_, Writer> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    /// Adds a new channel to the recorder
+    pub(crate) fn tap<U>(
+        &self,
+        producer: String,
+        rate: usize,
+    ) -> Result<Tap<<U as UniqueIdentifier>::DataType>>
+    where
+        U: UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: Serialize + DeserializeOwned,
+    {
+        let uid = type_name::<U>().to_string();
+        let mut writer = self.writer();
+        if writer
+            .channels
+            .iter()
+            .any(|channel| *channel == (uid.clone(), producer.clone()))
+        {
+            return Err(RecordError::Duplicate { uid, producer });
+        }
+        writer.write(&Record::Channel {
+            uid: uid.clone(),
+            producer: producer.clone(),
+            rate,
+        })?;
+        writer.channels.push((uid, producer));
+        Ok(Tap {
+            recorder: self.clone(),
+            channel: writer.channels.len() - 1,
+            codec: Codec::new(),
+            rate,
+            count: 0,
+        })
+    }
+    /// Writes the buffered data to the recorder file
+    pub fn flush(&self) -> Result<()> {
+        self.writer().file.flush()?;
+        Ok(())
+    }
+    /// Returns the data recorded so far
+    pub fn recording(&self) -> Result<Recording> {
+        self.flush()?;
+        let path = self.writer().path.clone();
+        Recording::from_path(path)
+    }
+}
+
+/// Tap on an actor output
+pub(crate) struct Tap<T> {
+    recorder: Recorder,
+    channel: usize,
+    codec: Codec<T>,
+    rate: usize,
+    count: usize,
+}
+impl<T> Clone for Tap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            recorder: self.recorder.clone(),
+            channel: self.channel,
+            codec: self.codec,
+            rate: self.rate,
+            count: self.count,
+        }
+    }
+}
+impl<T> Tap<T> {
+    /// Records the data that is about to be sent at the given `sample`
+    ///
+    /// Without sample, the sample is derived from the number of data already sent
+    pub fn record(&mut self, data: &T, sample: Option<usize>) -> Result<()> {
+        let bytes = self.codec.encode(data)?;
+        self.recorder.writer().write(&Record::Sample {
+            channel: self.channel,
+            index: sample.unwrap_or(self.count * self.rate),
+            bytes,
+        })?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Recorded actors outputs
+#[derive(Debug, Clone)]
+pub struct Recording {
+    channels: Arc<Vec<Channel>>,
+}
+
+impl Recording {
+    /// Loads a recording from a file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut channels: Vec<Channel> = vec![];
+        while !file.fill_buf()?.is_empty() {
+            match bincode::serde::decode_from_std_read(&mut file, bincode::config::standard())? {
+                Record::Channel {
+                    uid,
+                    producer,
+                    rate,
+                } => channels.push(Channel {
+                    uid,
+                    producer,
+                    rate,
+                    samples: vec![],
+                }),
+                Record::Sample {
+                    channel,
+                    index,
+                    bytes,
+                } => channels
+                    .get_mut(channel)
+                    .ok_or_else(|| RecordError::NoChannel(format!("#{channel}")))?
+                    .samples
+                    .push((index, bytes)),
+            }
+        }
+        Ok(Self {
+            channels: Arc::new(channels),
+        })
+    }
+    /// Returns the channel of the output `U`, either from the given `producer`
+    /// or from the only actor `U` is recorded from
+    fn channel<U: UniqueIdentifier>(&self, producer: Option<&str>) -> Result<&Channel> {
+        let uid = type_name::<U>();
+        let channels: Vec<_> = self
+            .channels
+            .iter()
+            .filter(|channel| {
+                channel.uid == uid && producer.is_none_or(|producer| channel.producer == producer)
+            })
+            .collect();
+        match channels.as_slice() {
+            [] => Err(RecordError::NoChannel(producer.map_or_else(
+                || uid.to_string(),
+                |producer| format!("{uid} from {producer}"),
+            ))),
+            [channel] => Ok(channel),
+            _ => Err(RecordError::Ambiguous {
+                uid: uid.to_string(),
+                producers: channels
+                    .iter()
+                    .map(|channel| channel.producer.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }),
+        }
+    }
+    /// Returns the names of the actors the output `U` has been recorded from
+    pub fn producers<U: UniqueIdentifier>(&self) -> Vec<&str> {
+        self.channels
+            .iter()
+            .filter(|channel| channel.uid == type_name::<U>())
+            .map(|channel| channel.producer.as_str())
+            .collect()
+    }
+    /// Returns the name of the actor the output `U` has been recorded from
+    pub fn producer<U: UniqueIdentifier>(&self) -> Result<&str> {
+        self.channel::<U>(None)
+            .map(|channel| channel.producer.as_str())
+    }
+    /// Returns the rate of the recorded output `U`
+    pub fn rate<U: UniqueIdentifier>(&self) -> Result<usize> {
+        self.channel::<U>(None).map(|channel| channel.rate)
+    }
+    /// Returns the sample indices and the data recorded from the output `U`
+    ///
+    /// An error is returned if `U` has been recorded from several actors, see [Recording::data_from]
+    pub fn data<U>(&self) -> Result<Vec<(usize, <U as UniqueIdentifier>::DataType)>>
+    where
+        U: UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: Serialize + DeserializeOwned,
+    {
+        decode(self.channel::<U>(None)?)
+    }
+    /// Returns the sample indices and the data recorded from the output `U` of the actor `producer`
+    pub fn data_from<U>(
+        &self,
+        producer: &str,
+    ) -> Result<Vec<(usize, <U as UniqueIdentifier>::DataType)>>
+    where
+        U: UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: Serialize + DeserializeOwned,
+    {
+        decode(self.channel::<U>(Some(producer))?)
+    }
+}
+
+fn decode<T: Serialize + DeserializeOwned>(channel: &Channel) -> Result<Vec<(usize, T)>> {
+    let codec = Codec::new();
+    channel
+        .samples
+        .iter()
+        .map(|(k, bytes)| Ok((*k, codec.decode(bytes)?)))
+        .collect()
+}
+
+/// Replay of recorded actors outputs
+///
+/// Each output `U` of the replay actor sends the data recorded from the output `U`, in the same order.
+/// A data is sent at the first replay sample that is not before the sample it has been recorded at,
+/// in between the data last sent is sent again.
+/// The replay ends when the recorded data is exhausted
+#[derive(Debug)]
+pub struct Replay {
+    recording: Recording,
+    producers: HashMap<String, String>,
+    cursors: HashMap<String, (usize, Option<usize>)>,
+    n_update: usize,
+}
+
+impl Replay {
+    /// Creates a new replay client from a [Recording]
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            producers: HashMap::new(),
+            cursors: HashMap::new(),
+            n_update: 0,
+        }
+    }
+    /// Replays the output `U` recorded from the actor `producer`
+    ///
+    /// Required only if `U` has been recorded from several actors
+    pub fn producer<U: UniqueIdentifier>(mut self, producer: impl Into<String>) -> Self {
+        self.producers
+            .insert(type_name::<U>().to_string(), producer.into());
+        self
+    }
+}
+
+impl Update for Replay {
+    fn update(&mut self) {
+        self.n_update += 1;
+    }
+}
+
+impl<U> Write<U> for Replay
+where
+    U: UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: Serialize + DeserializeOwned,
+{
+    fn write(&mut self) -> Option<Data<U>> {
+        let producer = self.producers.get(type_name::<U>()).map(String::as_str);
+        let channel = self
+            .recording
+            .channel::<U>(producer)
+            .map_err(|e| log::error!("{e}"))
+            .ok()?;
+        let sample = self.n_update.saturating_sub(1) * channel.rate;
+        let (next, last) = self.cursors.entry(channel.uid.clone()).or_default();
+        let k = match (channel.samples.get(*next), *last) {
+            (None, _) => return None,
+            // holding the data last sent until the sample of the next data
+            (Some((index, _)), Some(last)) if *index > sample => last,
+            _ => {
+                *last = Some(*next);
+                *next += 1;
+                *next - 1
+            }
+        };
+        Codec::new()
+            .decode(&channel.samples[k].1)
+            .map(Data::new)
+            .map_err(|e| log::error!("failed to decode {}: {e}", type_name::<U>()))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    enum U {}
+    impl UniqueIdentifier for U {
+        type DataType = Vec<f64>;
+    }
+    enum Y {}
+    impl UniqueIdentifier for Y {
+        type DataType = Vec<f64>;
+    }
+
+    struct Source(usize);
+    impl Update for Source {}
+    impl Write<U> for Source {
+        fn write(&mut self) -> Option<Data<U>> {
+            self.0 += 1;
+            (self.0 <= 10).then(|| Data::new(vec![self.0 as f64]))
+        }
+    }
+
+    #[derive(Default)]
+    struct Accumulator(f64);
+    impl Update for Accumulator {}
+    impl interface::Read<U> for Accumulator {
+        fn read(&mut self, data: Data<U>) {
+            self.0 += data[0];
+        }
+    }
+    impl Write<Y> for Accumulator {
+        fn write(&mut self) -> Option<Data<Y>> {
+            Some(Data::new(vec![self.0]))
+        }
+    }
+
+    #[derive(Default)]
+    struct Logger(Vec<f64>);
+    impl Update for Logger {}
+    impl interface::Read<Y> for Logger {
+        fn read(&mut self, data: Data<Y>) {
+            self.0.push(data[0]);
+        }
+    }
+
+    #[tokio::test]
+    async fn replay() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join("gmt_dos-actors_record-replay.bin");
+        let recorder = Recorder::new(&path)?;
+        let mut source: Initiator<_, 2> = Source(0).into();
+        let mut acc: Actor<_, 2, 4> = Accumulator::default().into();
+        let mut log: Terminator<_, 4> = Logger::default().into();
+        source.add_output().build::<U>().into_input(&mut acc)?;
+        acc.add_output().build::<Y>().into_input(&mut log)?;
+        source.record::<U>(&recorder)?;
+        acc.record::<Y>(&recorder)?;
+        model!(source, acc, log).quiet().check()?.run().await?;
+
+        let recording = recorder.recording()?;
+        assert_eq!(recording.rate::<U>()?, 2);
+        let inputs = recording.data::<U>()?;
+        assert_eq!(inputs.len(), 10);
+        assert_eq!(inputs[3], (6, vec![4.]));
+
+        let mut replay: Initiator<_, 2> = Replay::new(recording.clone()).into();
+        let mut acc: Actor<_, 2, 4> = Accumulator::default().into();
+        let logger = Logger::default().into_arcx();
+        let mut log = Terminator::<_, 4>::new(logger.clone());
+        replay.add_output().build::<U>().into_input(&mut acc)?;
+        acc.add_output().build::<Y>().into_input(&mut log)?;
+        model!(replay, acc, log).quiet().check()?.run().await?;
+
+        let outputs: Vec<_> = recording
+            .data::<Y>()?
+            .into_iter()
+            .map(|(_, y)| y[0])
+            .collect();
+        assert_eq!(logger.lock().await.0, outputs);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn producers() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join("gmt_dos-actors_record-producers.bin");
+        let recorder = Recorder::new(&path)?;
+        let mut source: Initiator<_> = Source(0).into();
+        let mut a: Actor<_> = (Accumulator::default(), "a").into();
+        let mut b: Actor<_> = (Accumulator::default(), "b").into();
+        let mut log_a: Terminator<_> = Logger::default().into();
+        let mut log_b: Terminator<_> = Logger::default().into();
+        source
+            .add_output()
+            .multiplex(2)
+            .build::<U>()
+            .into_input(&mut a)
+            .into_input(&mut b)?;
+        a.add_output()
+            .bootstrap()
+            .build::<Y>()
+            .into_input(&mut log_a)?;
+        b.add_output().build::<Y>().into_input(&mut log_b)?;
+        a.record::<Y>(&recorder)?;
+        b.record::<Y>(&recorder)?;
+        assert!(b.record::<Y>(&recorder).is_err());
+        model!(source, a, b, log_a, log_b)
+            .quiet()
+            .check()?
+            .run()
+            .await?;
+
+        let recording = recorder.recording()?;
+        assert_eq!(recording.producers::<Y>(), vec!["a", "b"]);
+        assert!(matches!(
+            recording.data::<Y>(),
+            Err(RecordError::Ambiguous { .. })
+        ));
+        // the bootstrapped data is sent before the data of the first sample
+        let a: Vec<_> = recording.data_from::<Y>("a")?;
+        assert_eq!(&a[..3], [(0, vec![0.]), (0, vec![1.]), (1, vec![3.])]);
+        let b: Vec<_> = recording.data_from::<Y>("b")?;
+        assert_eq!(&b[..2], [(0, vec![1.]), (1, vec![3.])]);
+
+        let logger = Logger::default().into_arcx();
+        let mut replay: Initiator<_> = Replay::new(recording).producer::<Y>("a").into();
+        let mut log = Terminator::<_>::new(logger.clone());
+        replay.add_output().build::<Y>().into_input(&mut log)?;
+        model!(replay, log).quiet().check()?.run().await?;
+        assert_eq!(
+            logger.lock().await.0,
+            a.into_iter().map(|(_, y)| y[0]).collect::<Vec<_>>()
+        );
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+}
@@ -1,19 +1,116 @@
-use super::{Completed, Model, ModelError, Result, Running};
+use super::{cancel::Cancellation, Completed, Model, ModelError, Result, Running};
 use crate::{
-    framework::model::TaskError::FromActor,
-    ActorError::{Disconnected, DropRecv, DropSend},
+    framework::model::TaskError::{FromActor, FromModel},
+    ActorError::{Cancelled, Disconnected, DropRecv, DropSend},
 };
 use chrono::{DateTime, Local, SecondsFormat};
 use std::{
     future::{Future, IntoFuture},
     marker::PhantomData,
     pin::Pin,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 impl Model<Running> {
+    /// Returns the handle to cancel the model
+    pub fn cancellation(&self) -> Cancellation {
+        self.cancellation.clone().unwrap_or_default()
+    }
+    /// Cancels the model if it is still running after the given duration
+    pub fn timeout(self, duration: Duration) -> Self {
+        let cancellation = self.cancellation();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(duration) => cancellation.time_out(),
+                _ = cancellation.stopped() => (),
+            }
+        });
+        self
+    }
+    /// Cancels the model when Ctrl-C is pressed
+    pub fn cancel_on_ctrl_c(self) -> Self {
+        let cancellation = self.cancellation();
+        tokio::spawn(async move {
+            tokio::select! {
+                Ok(_) = tokio::signal::ctrl_c() => cancellation.cancel(),
+                _ = cancellation.stopped() => (),
+            }
+        });
+        self
+    }
     /// Waits for the task of each actor to finish
+    ///
+    /// If the model has been cancelled, a [Cancelled](ModelError::Cancelled)
+    /// or a [TimedOut](ModelError::TimedOut) error is returned once all the actors have completed
     pub async fn wait(mut self) -> Result<Model<Completed>> {
+        let cancellation = self.cancellation();
+        let result = self.join().await;
+        cancellation.complete();
+        result?;
+        let elapsed_time = Instant::now().duration_since(self.start);
+        if cancellation.is_cancelled() {
+            self.verbose.then(|| {
+                eprintln!(
+                    "[{}] {} after {}",
+                    self.get_name().to_uppercase(),
+                    if cancellation.is_timed_out() {
+                        "TIMED OUT"
+                    } else {
+                        "CANCELLED"
+                    },
+                    humantime::format_duration(elapsed_time)
+                )
+            });
+            return Err(if cancellation.is_timed_out() {
+                ModelError::TimedOut(elapsed_time)
+            } else {
+                ModelError::Cancelled
+            });
+        }
+        let now: DateTime<Local> = Local::now();
+        self.verbose.then(|| {
+            eprintln!(
+                "[{}<{}>] COMPLETED in {}",
+                self.name
+                    .as_ref()
+                    .unwrap_or(&String::from("Model"))
+                    .to_uppercase(),
+                now.to_rfc3339_opts(SecondsFormat::Secs, true),
+                humantime::format_duration(elapsed_time)
+            );
+            if let Some(monitor) = self.monitor.as_ref() {
+                monitor
+                    .dropped()
+                    .into_iter()
+                    .filter(|drops| drops.dropped > 0)
+                    .for_each(|drops| eprintln!(" {drops}"));
+            }
+        });
+        let model = Model::<Completed> {
+            name: self.name,
+            actors: None,
+            task_handles: None,
+            state: PhantomData,
+            start: Instant::now(),
+            verbose: self.verbose,
+            elapsed_time: elapsed_time.as_secs_f64(),
+            stepper: None,
+            cancellation: None,
+            monitor: self.monitor,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: self.profiler,
+        };
+        #[cfg(feature = "profiling")]
+        if model.verbose && model.profiler.as_ref().is_some_and(|p| p.is_root()) {
+            if let Some(profile) = model.profile() {
+                eprintln!("{profile}");
+            }
+        }
+        Ok(model)
+    }
+    async fn join(&mut self) -> Result<()> {
         let task_handles = self.task_handles.take().unwrap();
         for task_handle in task_handles.into_iter() {
             // task_handle.await?.map_err(|e| Box::new(e))?;
@@ -37,32 +134,17 @@ impl Model<Running> {
                     log::debug!("{} has been dropped", msg);
                     Ok(())
                 }
+                Err(FromActor(Cancelled(msg))) => {
+                    log::debug!("{} has been cancelled", msg);
+                    Ok(())
+                }
+                // cancellation of a system, reported by the model
+                Err(FromModel(ModelError::Cancelled | ModelError::TimedOut(_))) => Ok(()),
                 Err(e) => Err(e),
             }
             .map_err(Box::new)?;
         }
-        let elapsed_time = Instant::now().duration_since(self.start);
-        let now: DateTime<Local> = Local::now();
-        self.verbose.then(|| {
-            eprintln!(
-                "[{}<{}>] COMPLETED in {}",
-                self.name
-                    .as_ref()
-                    .unwrap_or(&String::from("Model"))
-                    .to_uppercase(),
-                now.to_rfc3339_opts(SecondsFormat::Secs, true),
-                humantime::format_duration(elapsed_time)
-            )
-        });
-        Ok(Model::<Completed> {
-            name: self.name,
-            actors: None,
-            task_handles: None,
-            state: PhantomData,
-            start: Instant::now(),
-            verbose: self.verbose,
-            elapsed_time: elapsed_time.as_secs_f64(),
-        })
+        Ok(())
     }
 }
 
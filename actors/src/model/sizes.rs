@@ -0,0 +1,105 @@
+//! Data sizes consistency analysis
+//!
+//! The number of elements of the data of each output must match the number of elements
+//! expected by the inputs it is connected to.
+//! The sizes are given either by the [length](interface::UniqueIdentifier::LEN) of the data,
+//! set with `#[uid(len = ...)]`, or by the [Size](interface::Size) of the clients for the inputs and outputs
+//! declared with [Actor::sized_input](crate::actor::Actor::sized_input) and [Actor::sized_output](crate::actor::Actor::sized_output).
+//! Outputs and inputs without a known size are not checked.
+
+use super::edges::{edges, Edge};
+use crate::{actor::PlainActor, trim};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SizeError {
+    #[error("output {output} of {producer} (size {output_size}) is connected to input {input} of {consumer} (size {input_size})")]
+    Mismatch {
+        output: String,
+        producer: String,
+        output_size: usize,
+        input: String,
+        consumer: String,
+        input_size: usize,
+    },
+}
+
+/// Checks the consistency of the sizes of the data along the edges of the model graph
+pub(crate) fn analyse(actors: &[PlainActor]) -> Result<(), Box<SizeError>> {
+    for Edge {
+        producer,
+        output,
+        consumer,
+        input,
+    } in edges(actors)
+    {
+        if let (Some(output_size), Some(input_size)) = (output.size(), input.size()) {
+            if output_size != input_size {
+                return Err(Box::new(SizeError::Mismatch {
+                    output: trim(output.name()),
+                    producer: producer.client.clone(),
+                    output_size,
+                    input: trim(input.name()),
+                    consumer: consumer.client.clone(),
+                    input_size,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{model::ModelError, prelude::*};
+    use interface::{Data, Read, Size, UniqueIdentifier, Update, Write};
+
+    enum Forces {}
+    impl UniqueIdentifier for Forces {
+        type DataType = Vec<f64>;
+    }
+
+    struct Source(usize);
+    impl Update for Source {}
+    impl Write<Forces> for Source {
+        fn write(&mut self) -> Option<Data<Forces>> {
+            Some(Data::new(vec![0f64; self.0]))
+        }
+    }
+    impl Size<Forces> for Source {
+        fn len(&self) -> usize {
+            self.0
+        }
+    }
+
+    struct Sink(usize);
+    impl Update for Sink {}
+    impl Read<Forces> for Sink {
+        fn read(&mut self, _data: Data<Forces>) {}
+    }
+    impl Size<Forces> for Sink {
+        fn len(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn mismatch() -> anyhow::Result<()> {
+        let mut source = Initiator::<_>::from(Source(675)).name("M1");
+        let mut sink = Terminator::<_>::from(Sink(42)).name("M1 FEM");
+        source
+            .add_output()
+            .build::<Forces>()
+            .into_input(&mut sink)?;
+        source.sized_output::<Forces>()?;
+        sink.sized_input::<Forces>()?;
+        match model!(source, sink).quiet().check() {
+            Err(ModelError::Sizes(error)) => assert_eq!(
+                error.to_string(),
+                "output Forces of M1 (size 675) is connected to input Forces of M1 FEM (size 42)"
+            ),
+            Err(error) => panic!("unexpected error: {error}"),
+            Ok(_) => panic!("the sizes mismatch is not detected"),
+        }
+        Ok(())
+    }
+}
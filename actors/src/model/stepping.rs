@@ -0,0 +1,333 @@
+use super::{ready::launch, Completed, Model, ModelError, Ready, Result, Running, Stepping};
+use crate::{
+    actor::plain::IO,
+    framework::model::{Step, StepCursor, Task, TaskError::FromActor},
+    ActorError::{Disconnected, DropRecv, DropSend},
+};
+use std::{collections::HashMap, marker::PhantomData, time::Instant};
+
+/// Deterministic executor state
+#[derive(Debug, Default)]
+pub(crate) struct Stepper {
+    pub(crate) cursors: Vec<StepCursor>,
+    pub(crate) sample: usize,
+    /// index of the actors in the flattened model
+    pub(crate) ids: Vec<usize>,
+    #[cfg(feature = "filing")]
+    pub(crate) n_actors: usize,
+}
+
+/// Orders the actors such as each actor comes after the actors it receives data from
+///
+/// Bootstrapped outputs are ignored to break the feedback loops,
+/// actors within a loop without bootstrapped outputs are left in their original order
+fn topological_order(actors: &[Box<dyn Task>]) -> Vec<usize> {
+    let plain: Vec<_> = actors.iter().map(|actor| actor.as_plain()).collect();
+    let mut producers: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (i, actor) in plain.iter().enumerate() {
+        for output in actor.outputs.iter().flatten() {
+            if let IO::Regular(data) | IO::Unbounded(data) = output {
+                producers.entry(data.hash()).or_default().push(i);
+            }
+        }
+    }
+    let mut upstream: Vec<Vec<usize>> = plain
+        .iter()
+        .map(|actor| {
+            let mut upstream: Vec<usize> = actor
+                .inputs
+                .iter()
+                .flatten()
+                .filter_map(|input| producers.get(&input.hash()))
+                .flatten()
+                .cloned()
+                .collect();
+            upstream.sort();
+            upstream.dedup();
+            upstream
+        })
+        .collect();
+    let mut order = Vec::with_capacity(actors.len());
+    let mut visited = vec![false; actors.len()];
+    while order.len() < actors.len() {
+        let next = (0..actors.len())
+            .find(|&i| !visited[i] && upstream[i].iter().all(|&j| visited[j] || j == i))
+            .or_else(|| (0..actors.len()).find(|&i| !visited[i]))
+            .unwrap();
+        visited[next] = true;
+        upstream[next].clear();
+        order.push(next);
+    }
+    order
+}
+
+impl Model<Ready> {
+    /// Prepares the actors for the deterministic single-threaded executor
+    ///
+    /// Systems are flattened into their actors and the actors are sorted in topological order.
+    /// The model is then moved forward in time with [step](Model::step), [step_n](Model::step_n)
+    /// or [run_until](Model::run_until) without the need for an async runtime
+    pub fn stepping(self) -> Model<Stepping> {
+        let tasks: Vec<_> = self
+            .actors
+            .into_iter()
+            .flatten()
+            .flat_map(|actor| actor.into_tasks())
+            .collect();
+        let order = topological_order(&tasks);
+        let mut tasks: Vec<_> = tasks.into_iter().map(Some).collect();
+        let actors: Vec<_> = order.iter().filter_map(|&i| tasks[i].take()).collect();
+        Model::<Stepping> {
+            name: self.name,
+            stepper: Some(Stepper {
+                cursors: vec![StepCursor::default(); actors.len()],
+                sample: 0,
+                ids: order,
+                #[cfg(feature = "filing")]
+                n_actors: actors.len(),
+            }),
+            actors: Some(actors),
+            task_handles: None,
+            state: PhantomData,
+            start: Instant::now(),
+            verbose: self.verbose,
+            elapsed_time: Default::default(),
+            cancellation: None,
+            monitor: None,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
+        }
+    }
+}
+
+impl Model<Stepping> {
+    /// Returns the index of the next simulation sample
+    pub fn sample(&self) -> usize {
+        self.stepper.as_ref().map_or(0, |stepper| stepper.sample)
+    }
+    /// Returns `true` if all the actors have completed their tasks
+    pub fn is_completed(&self) -> bool {
+        self.actors.as_ref().is_none_or(|actors| actors.is_empty())
+    }
+    /// Moves the model forward by one simulation sample
+    ///
+    /// All the actors are given the opportunity to process the current sample
+    /// in topological order, and this is repeated until none of the actors can move forward.
+    /// An actor is removed from the model when its task completes.
+    /// An error is returned if none of the actors could move forward.
+    pub fn step(&mut self) -> Result<&mut Self> {
+        let (Some(actors), Some(stepper)) = (self.actors.as_mut(), self.stepper.as_mut()) else {
+            return Ok(self);
+        };
+        let k = stepper.sample;
+        let mut stalled = true;
+        loop {
+            let mut progress = false;
+            let mut i = 0;
+            while i < actors.len() {
+                match actors[i].step(k, &mut stepper.cursors[i]) {
+                    Ok(Step::Progress) => progress = true,
+                    Ok(Step::Blocked) => (),
+                    Ok(Step::Done)
+                    | Err(FromActor(Disconnected(_)))
+                    | Err(FromActor(DropRecv { .. }))
+                    | Err(FromActor(DropSend { .. })) => {
+                        log::debug!("{} completed at sample {k}", actors[i].as_plain().client);
+                        actors.remove(i);
+                        stepper.cursors.remove(i);
+                        stepper.ids.remove(i);
+                        progress = true;
+                        continue;
+                    }
+                    Err(e) => return Err(Box::new(e).into()),
+                }
+                i += 1;
+            }
+            if !progress {
+                break;
+            }
+            stalled = false;
+        }
+        if stalled && !actors.is_empty() {
+            let blocked: Vec<_> = actors.iter().map(|actor| actor.as_plain().client).collect();
+            return Err(ModelError::Stalled(k, blocked.join(", ")));
+        }
+        stepper.sample += 1;
+        Ok(self)
+    }
+    /// Moves the model forward by `n` simulation samples
+    ///
+    /// Stops early if the model completes
+    pub fn step_n(&mut self, n: usize) -> Result<&mut Self> {
+        for _ in 0..n {
+            if self.is_completed() {
+                break;
+            }
+            self.step()?;
+        }
+        Ok(self)
+    }
+    /// Moves the model forward until either the predicate is `true` or the model completes
+    ///
+    /// The predicate is evaluated before each step
+    pub fn run_until<F>(&mut self, mut predicate: F) -> Result<&mut Self>
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        while !self.is_completed() && !predicate(self) {
+            self.step()?;
+        }
+        Ok(self)
+    }
+    /// Spawns the task of each actor that has not completed yet
+    ///
+    /// The actors carry on from the sample the model is at
+    pub fn run(mut self) -> Model<Running> {
+        let (Some(actors), Some(stepper)) = (self.actors.take(), self.stepper.take()) else {
+            return launch(self.name, self.verbose, vec![], 0);
+        };
+        let tasks: Vec<_> = stepper
+            .ids
+            .into_iter()
+            .zip(actors.into_iter().zip(stepper.cursors))
+            .map(|(id, (mut actor, cursor))| {
+                actor.start_from(cursor);
+                (id, actor)
+            })
+            .collect();
+        #[cfg(feature = "filing")]
+        let n_actors = stepper.n_actors;
+        #[cfg(not(feature = "filing"))]
+        let n_actors = tasks.len();
+        launch(self.name, self.verbose, tasks, n_actors)
+    }
+    /// Ends the simulation, dropping the actors that have not completed yet
+    pub fn complete(mut self) -> Model<Completed> {
+        let elapsed_time = Instant::now().duration_since(self.start);
+        self.verbose.then(|| {
+            eprintln!(
+                "[{}] COMPLETED {} samples in {}",
+                self.name
+                    .as_ref()
+                    .unwrap_or(&String::from("Model"))
+                    .to_uppercase(),
+                self.sample(),
+                humantime::format_duration(elapsed_time)
+            )
+        });
+        drop(self.actors.take());
+        Model::<Completed> {
+            name: self.name,
+            actors: None,
+            task_handles: None,
+            state: PhantomData,
+            start: Instant::now(),
+            verbose: self.verbose,
+            elapsed_time: elapsed_time.as_secs_f64(),
+            stepper: None,
+            cancellation: None,
+            monitor: None,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    enum Count {}
+    impl UniqueIdentifier for Count {
+        type DataType = f64;
+    }
+    enum Total {}
+    impl UniqueIdentifier for Total {
+        type DataType = f64;
+    }
+
+    struct Counter(usize);
+    impl Update for Counter {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<Count> for Counter {
+        fn write(&mut self) -> Option<Data<Count>> {
+            (self.0 <= 100).then(|| Data::new(self.0 as f64))
+        }
+    }
+
+    #[derive(Default)]
+    struct Sum(f64, f64);
+    impl Update for Sum {
+        fn update(&mut self) {
+            self.0 += self.1;
+        }
+    }
+    impl Read<Count> for Sum {
+        fn read(&mut self, data: Data<Count>) {
+            self.1 = *data;
+        }
+    }
+    impl Write<Total> for Sum {
+        fn write(&mut self) -> Option<Data<Total>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[derive(Default)]
+    struct Logger(Vec<f64>);
+    impl Update for Logger {}
+    impl Read<Total> for Logger {
+        fn read(&mut self, data: Data<Total>) {
+            self.0.push(*data);
+        }
+    }
+
+    fn model(logger: Arc<Mutex<Logger>>) -> Model<model::Ready> {
+        let mut counter: Initiator<_> = Counter(0).into();
+        let mut sum: Actor<_, 1, 5> = Sum::default().into();
+        let mut log: Terminator<_, 5> = Actor::new(logger);
+        counter
+            .add_output()
+            .build::<Count>()
+            .into_input(&mut sum)
+            .unwrap();
+        sum.add_output()
+            .build::<Total>()
+            .into_input(&mut log)
+            .unwrap();
+        model!(log, sum, counter).quiet().check().unwrap()
+    }
+
+    #[test]
+    fn step() {
+        let logger = Logger::default().into_arcx();
+        let mut stepping = model(logger.clone()).stepping();
+        stepping.step_n(11).unwrap();
+        assert_eq!(stepping.sample(), 11);
+        assert_eq!(logger.blocking_lock().0, vec![1., 21., 66.]);
+        stepping.run_until(|_| false).unwrap();
+        assert!(stepping.is_completed());
+        stepping.complete();
+    }
+
+    #[tokio::test]
+    async fn bit_identical() {
+        let logger = Logger::default().into_arcx();
+        model(logger.clone()).run().await.unwrap();
+        let expected = std::mem::take(&mut logger.lock().await.0);
+
+        let mut stepping = model(logger.clone()).stepping();
+        stepping.run_until(|_| false).unwrap();
+        assert_eq!(logger.lock().await.0, expected);
+    }
+}
@@ -0,0 +1,69 @@
+//! Physical units consistency analysis
+//!
+//! The [units](interface::UniqueIdentifier::UNITS) of each output must match the units of the inputs
+//! it is connected to, outputs and inputs without units are not checked.
+//! Data with different units must go through an explicit conversion actor,
+//! like the [Convert](https://docs.rs/gmt_dos-clients/latest/gmt_dos_clients/convert/struct.Convert.html) client.
+
+use super::edges::{edges, Edge};
+use crate::{actor::PlainActor, trim};
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnitsError {
+    #[error("output {output} of {producer} ({output_units}) is connected to input {input} of {consumer} ({input_units})")]
+    Mismatch {
+        output: String,
+        producer: String,
+        output_units: String,
+        input: String,
+        consumer: String,
+        input_units: String,
+    },
+}
+
+/// Checks the consistency of the units of the data along the edges of the model graph
+pub(crate) fn analyse(actors: &[PlainActor]) -> Result<(), Box<UnitsError>> {
+    for Edge {
+        producer,
+        output,
+        consumer,
+        input,
+    } in edges(actors)
+    {
+        if let (Some(output_units), Some(input_units)) = (output.units(), input.units()) {
+            if output_units != input_units {
+                return Err(Box::new(UnitsError::Mismatch {
+                    output: trim(output.name()),
+                    producer: producer.client.clone(),
+                    output_units: output_units.to_string(),
+                    input: trim(input.name()),
+                    consumer: consumer.client.clone(),
+                    input_units: input_units.to_string(),
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::plain::{IOData, IO};
+
+    fn io(name: &str, hash: u64, units: &str) -> IO {
+        IO::Regular(IOData::new(name.into(), hash, 1, 1).units(Some(units)))
+    }
+
+    #[test]
+    fn mismatch() {
+        let a = PlainActor::new("A").outputs(vec![io("F", 1, "N")]).build();
+        let b = PlainActor::new("B").inputs(vec![io("X", 1, "m")]).build();
+        assert_eq!(
+            analyse(&[a.clone(), b]).unwrap_err().to_string(),
+            "output F of A (N) is connected to input X of B (m)"
+        );
+        let b = PlainActor::new("B").inputs(vec![io("F", 1, "N")]).build();
+        assert!(analyse(&[a, b]).is_ok());
+    }
+}
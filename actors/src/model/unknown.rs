@@ -1,6 +1,6 @@
 use crate::framework::model::Task;
 
-use super::{Actors, Model, ModelError, Ready, Result, Unknown};
+use super::{rates, sizes, units, Actors, Model, ModelError, Ready, Result, Unknown};
 use std::{marker::PhantomData, time::Instant};
 
 impl Default for Model<Unknown> {
@@ -13,6 +13,13 @@ impl Default for Model<Unknown> {
             start: Instant::now(),
             verbose: true,
             elapsed_time: Default::default(),
+            stepper: None,
+            cancellation: None,
+            monitor: None,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
         }
     }
 }
@@ -37,6 +44,13 @@ impl Model<Unknown> {
             start: Instant::now(),
             verbose: true,
             elapsed_time: Default::default(),
+            stepper: None,
+            cancellation: None,
+            monitor: None,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
         }
     }
     /// Sets the model name
@@ -79,6 +93,14 @@ impl Model<Unknown> {
                 "{} I/O hashes difference: expected 0, found {}, did you forget to add some actors to the model?",
                 self.name.unwrap_or_default(),
                 hashes_diff);
+                let plain: Vec<_> = actors
+                    .iter()
+                    .map(|actor| (actor.as_plain(), actor.is_system()))
+                    .collect();
+                rates::analyse(&plain)?;
+                let plain: Vec<_> = plain.into_iter().map(|(actor, _)| actor).collect();
+                units::analyse(&plain)?;
+                sizes::analyse(&plain)?;
                 Ok(Model::<Ready> {
                     name: self.name,
                     actors: self.actors,
@@ -87,6 +109,13 @@ impl Model<Unknown> {
                     start: Instant::now(),
                     verbose: self.verbose,
                     elapsed_time: Default::default(),
+                    stepper: None,
+                    cancellation: None,
+                    monitor: None,
+                    #[cfg(feature = "filing")]
+                    checkpointer: None,
+                    #[cfg(feature = "profiling")]
+                    profiler: None,
                 })
             }
             None => Err(ModelError::NoActors),
@@ -101,6 +130,13 @@ impl Model<Unknown> {
             start: Instant::now(),
             verbose: self.verbose,
             elapsed_time: Default::default(),
+            stepper: None,
+            cancellation: None,
+            monitor: None,
+            #[cfg(feature = "filing")]
+            checkpointer: None,
+            #[cfg(feature = "profiling")]
+            profiler: None,
         }
     }
 }
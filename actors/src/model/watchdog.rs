@@ -0,0 +1,659 @@
+/*!
+# Model watchdog
+
+The actors of a [Running] model report, at all time, the number of times their client has been updated
+and whether they are waiting on some of their inputs or on some of their outputs to be sent.
+
+A snapshot of the state of the actors is given by [Model::status].
+A watchdog that prints the [ModelStatus] on stderr whenever no actor has made progress
+for a given time interval is attached to a running model with [Model::watchdog].
+The status can be written as a [Graphviz](https://www.graphviz.org/) graph where the edges
+the actors are waiting on are highlighted, the cycle of actors waiting on each other is drawn in red.
+
+The outputs with a drop [policy](OutputPolicy) count the data they have dropped,
+the counts are given by [Model::dropped] and are listed in the model completion report.
+
+```no_run
+use gmt_dos_actors::model::{watchdog::Watchdog, Model, ModelError, Ready};
+use std::time::Duration;
+
+async fn run(model: Model<Ready>) -> Result<(), ModelError> {
+    model
+        .run()
+        .watchdog(Watchdog::new(Duration::from_secs(10)).dot("deadlock.dot"))
+        .await?;
+    Ok(())
+}
+```
+*/
+
+use super::{rates::find_cycle, Completed, Model, Running};
+use crate::{actor::PlainActor, framework::network::OutputPolicy, trim};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    future::Future,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+const STARTING: u8 = 0;
+const COLLECTING: u8 = 1;
+const UPDATING: u8 = 2;
+const DISTRIBUTING: u8 = 3;
+const DONE: u8 = 4;
+
+tokio::task_local! {
+    static MONITOR: Monitor;
+}
+
+/// Returns the bit mask of the `n` first inputs or outputs
+fn mask(n: usize) -> u64 {
+    if n >= 64 {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Actor progress report
+///
+/// Only the first 64 inputs and outputs of an actor are tracked
+#[derive(Debug)]
+pub(crate) struct Probe {
+    plain: PlainActor,
+    step: AtomicUsize,
+    progress: AtomicUsize,
+    stage: AtomicU8,
+    pending: AtomicU64,
+    /// the outputs with a drop policy and their count of dropped data
+    dropped: Vec<(String, OutputPolicy, Arc<AtomicUsize>)>,
+}
+impl Probe {
+    fn enter(&self, stage: u8, n: usize) {
+        self.pending.store(mask(n), Ordering::Release);
+        self.stage.store(stage, Ordering::Release);
+    }
+    fn clear(&self, k: usize) {
+        if k < 64 {
+            self.pending.fetch_and(!(1 << k), Ordering::AcqRel);
+        }
+        self.progress.fetch_add(1, Ordering::Release);
+    }
+    /// The actor is waiting on its `n` inputs
+    pub fn collecting(&self, n: usize) {
+        self.enter(COLLECTING, n);
+    }
+    /// The input #`k` has been received
+    pub fn received(&self, k: usize) {
+        self.clear(k);
+    }
+    /// The actor is updating its client
+    pub fn updating(&self) {
+        self.enter(UPDATING, 0);
+    }
+    /// The actor has updated its client
+    pub fn updated(&self) {
+        self.step.fetch_add(1, Ordering::Release);
+    }
+    /// The actor is waiting on its `n` outputs to be sent
+    pub fn distributing(&self, n: usize) {
+        self.enter(DISTRIBUTING, n);
+    }
+    /// The output #`k` has been sent
+    pub fn sent(&self, k: usize) {
+        self.clear(k);
+    }
+    /// The actor task has completed
+    pub fn done(&self) {
+        self.enter(DONE, 0);
+        self.progress.fetch_add(1, Ordering::Release);
+    }
+    fn status(&self) -> ActorStatus {
+        let pending = self.pending.load(Ordering::Acquire);
+        let names = |ios: &Option<Vec<crate::actor::plain::IO>>| -> Vec<String> {
+            ios.iter()
+                .flatten()
+                .enumerate()
+                .filter(|(k, _)| *k < 64 && pending & (1 << k) != 0)
+                .map(|(_, io)| trim(io.name()))
+                .collect()
+        };
+        let state = match self.stage.load(Ordering::Acquire) {
+            COLLECTING => ActorState::Collecting(names(&self.plain.inputs)),
+            UPDATING => ActorState::Updating,
+            DISTRIBUTING => ActorState::Distributing(names(&self.plain.outputs)),
+            DONE => ActorState::Done,
+            _ => ActorState::Starting,
+        };
+        ActorStatus {
+            name: self.plain.client.clone(),
+            step: self.step.load(Ordering::Acquire),
+            state,
+            plain: self.plain.clone(),
+            pending,
+        }
+    }
+}
+
+/// Collection of the progress reports of the actors of a model
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Monitor(Arc<Mutex<Vec<Arc<Probe>>>>);
+impl Monitor {
+    /// Creates a new monitor or, within a model, returns the monitor of the model
+    pub fn new() -> Self {
+        MONITOR.try_with(Clone::clone).unwrap_or_default()
+    }
+    /// Runs the future with the monitor attached to the current task
+    pub fn scope<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        MONITOR.scope(self.clone(), future)
+    }
+    /// Adds a progress report for the actor to the monitor of the model the current task belongs to
+    ///
+    /// `dropped` are the actor outputs with a drop policy and their count of dropped data
+    pub fn register(
+        plain: PlainActor,
+        dropped: Vec<(String, OutputPolicy, Arc<AtomicUsize>)>,
+    ) -> Option<Arc<Probe>> {
+        MONITOR
+            .try_with(|monitor| {
+                let probe = Arc::new(Probe {
+                    plain,
+                    step: Default::default(),
+                    progress: Default::default(),
+                    stage: AtomicU8::new(STARTING),
+                    pending: Default::default(),
+                    dropped,
+                });
+                monitor.0.lock().unwrap().push(probe.clone());
+                probe
+            })
+            .ok()
+    }
+    /// Returns a number that changes each time an actor moves forward
+    fn progress(&self) -> usize {
+        let probes = self.0.lock().unwrap();
+        probes.iter().fold(probes.len(), |a, probe| {
+            a + probe.step.load(Ordering::Acquire) + probe.progress.load(Ordering::Acquire)
+        })
+    }
+    /// Returns the number of data dropped by the outputs with a drop policy
+    pub fn dropped(&self) -> Vec<OutputDrops> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|probe| {
+                probe
+                    .dropped
+                    .iter()
+                    .map(|(output, policy, count)| OutputDrops {
+                        actor: probe.plain.client.clone(),
+                        output: output.clone(),
+                        policy: *policy,
+                        dropped: count.load(Ordering::Relaxed),
+                    })
+            })
+            .collect()
+    }
+    pub fn status(&self, name: String) -> ModelStatus {
+        ModelStatus {
+            name,
+            actors: self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|probe| probe.status())
+                .collect(),
+        }
+    }
+}
+
+/// Number of data dropped by an output with a drop [policy](OutputPolicy)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputDrops {
+    /// actor name
+    pub actor: String,
+    /// output name
+    pub output: String,
+    /// output drop policy
+    pub policy: OutputPolicy,
+    /// number of data dropped
+    pub dropped: usize,
+}
+impl Display for OutputDrops {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} ({:?}): {} dropped",
+            self.output, self.actor, self.policy, self.dropped
+        )
+    }
+}
+
+/// Actor state
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActorState {
+    /// the actor is bootstrapping its outputs
+    Starting,
+    /// the actor is waiting on the listed inputs
+    Collecting(Vec<String>),
+    /// the actor is updating its client
+    Updating,
+    /// the actor is waiting on the listed outputs to be sent
+    Distributing(Vec<String>),
+    /// the actor task has completed
+    Done,
+}
+impl Display for ActorState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActorState::Starting => write!(f, "starting"),
+            ActorState::Collecting(inputs) => {
+                write!(f, "collect: waiting on {}", inputs.join(", "))
+            }
+            ActorState::Updating => write!(f, "update"),
+            ActorState::Distributing(outputs) => {
+                write!(f, "distribute: channel full on {}", outputs.join(", "))
+            }
+            ActorState::Done => write!(f, "done"),
+        }
+    }
+}
+
+/// Actor status
+#[derive(Debug, Clone)]
+pub struct ActorStatus {
+    /// actor name
+    pub name: String,
+    /// number of client updates
+    pub step: usize,
+    /// actor state
+    pub state: ActorState,
+    plain: PlainActor,
+    pending: u64,
+}
+impl ActorStatus {
+    fn is_pending(&self, k: usize) -> bool {
+        k < 64 && self.pending & (1 << k) != 0
+    }
+}
+
+/// Edge of the graph of the actors waiting on each other: (hash, producer, consumer)
+type Wait = (u64, usize, usize);
+
+/// Model status
+#[derive(Debug, Clone)]
+pub struct ModelStatus {
+    /// model name
+    pub name: String,
+    /// actors status
+    pub actors: Vec<ActorStatus>,
+}
+impl ModelStatus {
+    /// Returns the connections between the actors: (UID, hash, producer, consumer)
+    fn edges(&self) -> Vec<(String, u64, usize, usize)> {
+        let producers: HashMap<u64, usize> = self
+            .actors
+            .iter()
+            .enumerate()
+            .flat_map(|(i, actor)| {
+                actor
+                    .plain
+                    .outputs
+                    .iter()
+                    .flatten()
+                    .map(move |output| (output.hash(), i))
+            })
+            .collect();
+        self.actors
+            .iter()
+            .enumerate()
+            .flat_map(|(j, actor)| {
+                let producers = &producers;
+                actor
+                    .plain
+                    .inputs
+                    .iter()
+                    .flatten()
+                    .filter_map(move |input| {
+                        producers
+                            .get(&input.hash())
+                            .map(|&i| (trim(input.name()), input.hash(), i, j))
+                    })
+            })
+            .collect()
+    }
+    /// Returns the graph of the actors waiting on each other
+    ///
+    /// An actor waits on the producer of an input it is collecting
+    /// and on the consumers of an output it is distributing
+    fn waits(&self) -> Vec<Vec<(usize, Wait)>> {
+        let mut waits = vec![vec![]; self.actors.len()];
+        for (_, hash, i, j) in self.edges() {
+            let (producer, consumer) = (&self.actors[i], &self.actors[j]);
+            let collecting = matches!(consumer.state, ActorState::Collecting(_))
+                && consumer
+                    .plain
+                    .inputs
+                    .iter()
+                    .flatten()
+                    .position(|input| input.hash() == hash)
+                    .is_some_and(|k| consumer.is_pending(k));
+            if collecting {
+                waits[j].push((i, (hash, i, j)));
+            }
+            let distributing = matches!(producer.state, ActorState::Distributing(_))
+                && producer
+                    .plain
+                    .outputs
+                    .iter()
+                    .flatten()
+                    .position(|output| output.hash() == hash)
+                    .is_some_and(|k| producer.is_pending(k));
+            if distributing {
+                waits[i].push((j, (hash, i, j)));
+            }
+        }
+        waits
+    }
+    /// Returns the cycle of actors waiting on each other, if any
+    ///
+    /// The cycle is given as `A -(U)-> B -(V)-> A` meaning that
+    /// `A` waits on `B` through `U` and `B` waits on `A` through `V`
+    pub fn cycle(&self) -> Option<String> {
+        let edges = self.edges();
+        find_cycle(&self.waits()).map(|cycle| {
+            let mut path: Vec<_> = cycle
+                .iter()
+                .map(|&(i, wait)| {
+                    let uid = edges
+                        .iter()
+                        .find(|(_, hash, i, j)| (*hash, *i, *j) == wait)
+                        .map(|(uid, ..)| uid.as_str())
+                        .unwrap_or_default();
+                    format!("{} -({})->", trim(&self.actors[i].name), uid)
+                })
+                .collect();
+            path.push(trim(&self.actors[cycle[0].0].name));
+            path.join(" ")
+        })
+    }
+    /// Returns the status in the [Graphviz](https://www.graphviz.org/) dot language
+    ///
+    /// The connections the actors are waiting on are drawn in orange
+    /// and the actors and connections of the cycle of actors waiting on each other are drawn in red
+    pub fn to_dot(&self) -> String {
+        let waits: Vec<Wait> = self.waits().into_iter().flatten().map(|(_, w)| w).collect();
+        let cycle: Vec<Wait> = find_cycle(&self.waits())
+            .into_iter()
+            .flatten()
+            .map(|(_, w)| w)
+            .collect();
+        let mut dot = format!("digraph \"{}_status\" {{\n  node [shape=box];\n", self.name);
+        for (i, actor) in self.actors.iter().enumerate() {
+            let color = if cycle.iter().any(|&(_, p, c)| p == i || c == i) {
+                "red"
+            } else if matches!(actor.state, ActorState::Done) {
+                "gray"
+            } else {
+                "black"
+            };
+            dot.push_str(&format!(
+                "  {i} [label=\"{}\\nstep {}\\n{}\", color={color}];\n",
+                trim(&actor.name),
+                actor.step,
+                actor.state
+            ));
+        }
+        for (uid, hash, i, j) in self.edges() {
+            let style = if cycle.contains(&(hash, i, j)) {
+                ", color=red, penwidth=2"
+            } else if waits.contains(&(hash, i, j)) {
+                ", color=orange"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("  {i} -> {j} [label=\"{uid}\"{style}];\n"));
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+impl Display for ModelStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} STATUS", self.name.to_uppercase())?;
+        writeln!(f, " {:<40} {:>8} STATE", "ACTOR", "STEP")?;
+        for actor in &self.actors {
+            writeln!(
+                f,
+                " {:<40} {:>8} {}",
+                trim(&actor.name),
+                actor.step,
+                actor.state
+            )?;
+        }
+        if let Some(cycle) = self.cycle() {
+            writeln!(f, " deadlock: {cycle}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Watchdog settings
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    interval: Duration,
+    dot: Option<PathBuf>,
+}
+impl Watchdog {
+    /// Creates a watchdog that reports when no actor has made progress for the given time interval
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            dot: None,
+        }
+    }
+    /// Writes the [ModelStatus] graph to the given file when the model is stalled
+    pub fn dot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dot = Some(path.into());
+        self
+    }
+}
+impl From<Duration> for Watchdog {
+    fn from(interval: Duration) -> Self {
+        Self::new(interval)
+    }
+}
+
+impl Model<Running> {
+    /// Returns the current state of the actors
+    pub fn status(&self) -> ModelStatus {
+        self.monitor
+            .as_ref()
+            .map(|monitor| monitor.status(self.get_name()))
+            .unwrap_or_else(|| ModelStatus {
+                name: self.get_name(),
+                actors: vec![],
+            })
+    }
+    /// Returns the number of data dropped so far by the outputs with a drop [policy](OutputPolicy)
+    pub fn dropped(&self) -> Vec<OutputDrops> {
+        self.monitor
+            .as_ref()
+            .map(|monitor| monitor.dropped())
+            .unwrap_or_default()
+    }
+    /// Prints the [ModelStatus] on stderr if no actor has made progress for the [Watchdog] interval
+    ///
+    /// The status is reported once per stall, until the actors are moving forward again
+    pub fn watchdog(self, watchdog: impl Into<Watchdog>) -> Self {
+        let Some(monitor) = self.monitor.clone() else {
+            return self;
+        };
+        let Watchdog { interval, dot } = watchdog.into();
+        let cancellation = self.cancellation();
+        let name = self.get_name();
+        tokio::spawn(async move {
+            let mut progress = monitor.progress();
+            let mut stalled = false;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => (),
+                    _ = cancellation.stopped() => break,
+                }
+                let current = monitor.progress();
+                if current != progress {
+                    progress = current;
+                    stalled = false;
+                    continue;
+                }
+                if stalled {
+                    continue;
+                }
+                stalled = true;
+                let status = monitor.status(name.clone());
+                eprintln!(
+                    "[{}] NO PROGRESS FOR {}\n{status}",
+                    name.to_uppercase(),
+                    humantime::format_duration(interval)
+                );
+                if let Some(path) = dot.as_ref() {
+                    if let Err(e) = std::fs::write(path, status.to_dot()) {
+                        log::warn!("failed to write {}: {e}", path.display());
+                    }
+                }
+            }
+        });
+        self
+    }
+}
+
+impl Model<Completed> {
+    /// Returns the number of data dropped by the outputs with a drop [policy](OutputPolicy)
+    pub fn dropped(&self) -> Vec<OutputDrops> {
+        self.monitor
+            .as_ref()
+            .map(|monitor| monitor.dropped())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{framework::model::Task, prelude::*};
+    use interface::{Data, Read, UniqueIdentifier, Update, Write};
+
+    enum U {}
+    impl UniqueIdentifier for U {
+        type DataType = f64;
+    }
+    enum V {}
+    impl UniqueIdentifier for V {
+        type DataType = f64;
+    }
+
+    #[derive(Default)]
+    struct Gain(f64);
+    impl Update for Gain {}
+    impl Read<U> for Gain {
+        fn read(&mut self, data: Data<U>) {
+            self.0 = *data;
+        }
+    }
+    impl Read<V> for Gain {
+        fn read(&mut self, data: Data<V>) {
+            self.0 = *data;
+        }
+    }
+    impl Write<U> for Gain {
+        fn write(&mut self) -> Option<Data<U>> {
+            Some(Data::new(self.0))
+        }
+    }
+    impl Write<V> for Gain {
+        fn write(&mut self) -> Option<Data<V>> {
+            Some(Data::new(self.0))
+        }
+    }
+
+    #[tokio::test]
+    async fn deadlock() {
+        let mut a: Actor<_> = (Gain::default(), "A").into();
+        let mut b: Actor<_> = (Gain::default(), "B").into();
+        a.add_output().build::<U>().into_input(&mut b).unwrap();
+        b.add_output().build::<V>().into_input(&mut a).unwrap();
+        let monitor = Monitor::new();
+        let handles: Vec<_> = [Box::new(a), Box::new(b)]
+            .into_iter()
+            .map(|actor| tokio::spawn(monitor.scope(actor.task())))
+            .collect();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let status = monitor.status("feedback".into());
+        assert!(status.actors.iter().all(
+            |actor| matches!(&actor.state, ActorState::Collecting(inputs) if inputs.len() == 1)
+        ));
+        let cycle = status.cycle().unwrap();
+        assert!(cycle == "A -(V)-> B -(U)-> A" || cycle == "B -(U)-> A -(V)-> B");
+        assert!(status.to_dot().contains("color=red, penwidth=2"));
+        handles.iter().for_each(|handle| handle.abort());
+    }
+
+    struct Source(usize);
+    impl Update for Source {
+        fn update(&mut self) {
+            self.0 += 1;
+        }
+    }
+    impl Write<U> for Source {
+        fn write(&mut self) -> Option<Data<U>> {
+            (self.0 <= 100).then(|| Data::new(self.0 as f64))
+        }
+    }
+    #[derive(Default)]
+    struct SlowSink(Vec<f64>);
+    impl Update for SlowSink {
+        fn update(&mut self) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+    impl Read<U> for SlowSink {
+        fn read(&mut self, data: Data<U>) {
+            self.0.push(*data);
+        }
+    }
+
+    #[tokio::test]
+    async fn keep_latest() {
+        let sink = SlowSink::default().into_arcx();
+        let mut source: Initiator<_> = (Source(0), "source").into();
+        let mut slow: Terminator<_> = Actor::new(sink.clone()).name("sink");
+        source
+            .add_output()
+            .keep_latest()
+            .build::<U>()
+            .into_input(&mut slow)
+            .unwrap();
+        let model = model!(source, slow)
+            .quiet()
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        let received = sink.lock().await.0.clone();
+        let dropped = model.dropped();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].policy, OutputPolicy::KeepLatest);
+        assert_eq!(received.len() + dropped[0].dropped, 100);
+        assert!(received.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(received.last(), Some(&100.));
+    }
+}
@@ -12,7 +12,11 @@
 //!
 //! `Gateway` is a system's actor that receives inputs from other clients to this system ([SystemInput]`<Gateway>`)
 //! or send outputs from the system to other clients (([SystemOutput]`<Gateway>`))
+//!
+//! The structure `S` and the implementation of the traits can also be generated
+//! from the flows of a model with the `system` attribute of [actorscript](crate::actorscript).
 
+use interface::Seed;
 use std::marker::PhantomData;
 use std::{
     fmt::Display,
@@ -57,6 +61,8 @@ where
 pub struct Sys<T: System, S = Built> {
     pub sys: T,
     state: PhantomData<S>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    seed: Option<Seed>,
 }
 
 impl<T: System, S> Clone for Sys<T, S> {
@@ -66,6 +72,7 @@ impl<T: System, S> Clone for Sys<T, S> {
         Self {
             sys,
             state: PhantomData,
+            seed: self.seed,
         }
     }
 }
@@ -88,6 +95,7 @@ impl<T: System> Sys<T, New> {
         Self {
             sys,
             state: PhantomData,
+            seed: None,
         }
     }
 
@@ -95,6 +103,7 @@ impl<T: System> Sys<T, New> {
         let mut this: Sys<T> = Sys {
             sys: self.sys,
             state: PhantomData,
+            seed: self.seed,
         };
         this.sys.build()?;
         Ok(this)
@@ -1,7 +1,9 @@
+use interface::Seed;
+
 use crate::{
     actor::{Actor, PlainActor},
     framework::{
-        model::{Check, CheckError, Task, TaskError},
+        model::{Check, CheckError, Step, StepCursor, Task, TaskError},
         network::{ActorOutput, AddActorInput},
     },
     prelude::{AddActorOutput, GetName, Model, Unknown},
@@ -19,15 +21,22 @@ where
     for<'a> &'a T: IntoIterator<Item = Box<&'a dyn Check>>,
     Box<T>: IntoIterator<Item = Box<dyn Task>>,
 {
+    /// A system runs its actors with [Task::task], the actors loop cannot be borrowed
     async fn async_run(&mut self) -> std::result::Result<(), TaskError> {
-        todo!()
+        Err(TaskError::System(self.name()))
     }
 
     async fn task(mut self: Box<Self>) -> std::result::Result<(), TaskError> {
         let name = self.name();
         let q = *self;
+        let seed = q.seed;
         let w = q.sys;
-        let b = Box::new(w);
+        let b = Box::new(w).into_iter().map(|mut task| {
+            if let Some(seed) = seed {
+                task.seed(seed);
+            }
+            task
+        });
         Model::<Unknown>::from_iter(b)
             .name(name)
             .skip_check()
@@ -39,6 +48,58 @@ where
     fn as_plain(&self) -> PlainActor {
         self.plain()
     }
+
+    /// A system is stepped through its actors, [Model::stepping](crate::model::Model::stepping)
+    /// flattens the system into its actors with [Task::into_tasks]
+    fn step(
+        &mut self,
+        _k: usize,
+        _cursor: &mut StepCursor,
+    ) -> std::result::Result<Step, TaskError> {
+        Err(TaskError::System(self.name()))
+    }
+
+    fn into_tasks(self: Box<Self>) -> Vec<Box<dyn Task>> {
+        let seed = self.seed;
+        Box::new(self.sys)
+            .into_iter()
+            .flat_map(|task| task.into_tasks())
+            .map(|mut task| {
+                if let Some(seed) = seed {
+                    task.seed(seed);
+                }
+                task
+            })
+            .collect()
+    }
+
+    fn seed(&mut self, seed: Seed) {
+        self.seed = Some(seed);
+    }
+
+    /// A system is saved through its actors, the model checkpoints are taken on the flattened actors
+    #[cfg(feature = "filing")]
+    fn checkpoint(
+        &self,
+    ) -> std::result::Result<
+        crate::model::checkpoint::ActorCheckpoint,
+        crate::model::checkpoint::CheckpointError,
+    > {
+        Err(crate::model::checkpoint::CheckpointError::System(
+            self.name(),
+        ))
+    }
+
+    /// A system is restored through its actors, see [Task::checkpoint]
+    #[cfg(feature = "filing")]
+    fn restore(
+        &mut self,
+        _checkpoint: crate::model::checkpoint::ActorCheckpoint,
+    ) -> std::result::Result<(), crate::model::checkpoint::CheckpointError> {
+        Err(crate::model::checkpoint::CheckpointError::System(
+            self.name(),
+        ))
+    }
 }
 
 impl<T> Check for Sys<T>
@@ -90,6 +151,12 @@ where
     fn is_system(&self) -> bool {
         true
     }
+
+    fn n_actors(&self) -> usize {
+        self.into_iter()
+            .map(|a: Box<&dyn Check>| a.n_actors())
+            .sum()
+    }
 }
 
 impl<T: System> GetName for T {
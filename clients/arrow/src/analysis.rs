@@ -0,0 +1,252 @@
+/*!
+# Post-processing of logged data
+
+A [Run] opens a [Parquet](https://docs.rs/parquet) file written by the [Arrow] logger
+and returns the data of a field as a [Series], the type name of a UID giving the name of the field.
+A [Series] can be sliced in time, decimated, and reduced to statistics and power spectral densities ([Psd]).
+
+```no_run
+use gmt_dos_clients_arrow::analysis::Run;
+use interface::UID;
+
+#[derive(UID)]
+pub enum MountEncoders {}
+
+let run = Run::open("data")?.sampling_frequency(1e3);
+let encoders = run.series::<MountEncoders>()?.time_window(5., 20.)?;
+println!("RMS: {:?}", encoders.rms());
+let psd = encoders.decimate(2).psd(1024)?;
+println!("variance: {:?}", psd.cumulative().values().iter().map(|c| c.last()).collect::<Vec<_>>());
+// a summary table of all the fields
+println!("{}", run.summary());
+# Ok::<(), gmt_dos_clients_arrow::analysis::AnalysisError>(())
+```
+*/
+
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use apache_arrow::{array::AsArray, datatypes::ArrowNativeType, record_batch::RecordBatch};
+use interface::UniqueIdentifier;
+
+use crate::{arrow::Column, who, Arrow, ArrowError, BufferDataType};
+
+mod psd;
+mod series;
+pub use psd::Psd;
+pub use series::Series;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnalysisError {
+    #[error("failed to load the data")]
+    Arrow(#[from] ArrowError),
+    #[error("the sampling frequency is not set")]
+    SamplingFrequency,
+    #[error("the number of samples of a segment must be a power of 2, found {0}")]
+    NotPowerOfTwo(usize),
+    #[error("the number of samples ({0}) is less than the number of samples of a segment ({1})")]
+    TooShort(usize, usize),
+}
+
+type Result<T> = std::result::Result<T, AnalysisError>;
+
+/// Data logged during a simulation
+///
+/// The data of a field is cast to `f64` when it is requested as a [Series],
+/// only the fields that are lists of numbers can be cast.
+pub struct Run {
+    record: RecordBatch,
+    sampling_frequency: Option<f64>,
+}
+
+impl Run {
+    /// Opens a [Parquet](https://docs.rs/parquet) data file
+    ///
+    /// The file is loaded with [Arrow::from_parquet]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::try_from(Arrow::from_parquet(path)?)
+    }
+    /// Sets the sampling frequency of the data in Hz
+    pub fn sampling_frequency(mut self, sampling_frequency: f64) -> Self {
+        self.sampling_frequency = Some(sampling_frequency);
+        self
+    }
+    /// Returns the metadata of the data
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        self.record.schema_ref().metadata()
+    }
+    /// Returns the names of the fields
+    pub fn names(&self) -> Vec<&str> {
+        self.record
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect()
+    }
+    /// Returns the data of the field `name` as a [Series]
+    pub fn column<S: AsRef<str>>(&self, name: S) -> Result<Series> {
+        let name = name.as_ref();
+        let (idx, field) = self
+            .record
+            .schema_ref()
+            .column_with_name(name)
+            .ok_or_else(|| ArrowError::FieldNotFound(name.to_string()))?;
+        let column = Column::new(field, self.record.column(idx))
+            .ok_or_else(|| ArrowError::ParseField(name.to_string()))?;
+        let n_data = column.shape.iter().skip(1).product::<usize>().max(1);
+        let series = Series::new(
+            column.name,
+            column.data.chunks(n_data).map(|row| row.to_vec()).collect(),
+        );
+        Ok(match self.sampling_frequency {
+            Some(fs) => series.sampling_frequency(fs),
+            None => series,
+        })
+    }
+    /// Returns the data of the UID `U` as a [Series]
+    pub fn series<U: UniqueIdentifier>(&self) -> Result<Series> {
+        self.column(who::<U>())
+    }
+    /// Returns the data of the UID `U` with its original type
+    ///
+    /// ```no_run
+    /// # use gmt_dos_clients_arrow::analysis::Run;
+    /// # use interface::UID;
+    /// #[derive(UID)]
+    /// #[uid(data = Vec<f32>)]
+    /// pub enum Frame {}
+    /// let run = Run::open("data")?;
+    /// let frames = run.get::<Frame, _>()?;
+    /// # Ok::<(), gmt_dos_clients_arrow::analysis::AnalysisError>(())
+    /// ```
+    pub fn get<U, T>(&self) -> Result<Vec<Vec<T>>>
+    where
+        U: UniqueIdentifier<DataType = Vec<T>>,
+        T: BufferDataType + ArrowNativeType,
+    {
+        let name = who::<U>();
+        let column = self
+            .record
+            .column_by_name(&name)
+            .ok_or_else(|| ArrowError::FieldNotFound(name.clone()))?;
+        let rows: Vec<_> = if let Some(list) = column.as_list_opt::<i32>() {
+            list.iter().collect()
+        } else if let Some(list) = column.as_fixed_size_list_opt() {
+            list.iter().collect()
+        } else {
+            return Err(ArrowError::ParseField(name).into());
+        };
+        rows.into_iter()
+            .map(|row| {
+                row.as_ref()
+                    .and_then(|row| row.as_primitive_opt::<T::ArrayType>())
+                    .map(|row| row.values().to_vec())
+                    .ok_or_else(|| ArrowError::ParseField(name.clone()).into())
+            })
+            .collect()
+    }
+    /// Returns a summary of the statistics of all the fields
+    ///
+    /// The fields that are not lists of numbers are skipped
+    pub fn summary(&self) -> Summary {
+        let n_sample = self.record.num_rows();
+        Summary {
+            duration: self.sampling_frequency.map(|fs| n_sample as f64 / fs),
+            n_sample,
+            rows: self
+                .record
+                .schema_ref()
+                .fields()
+                .iter()
+                .zip(self.record.columns())
+                .filter_map(|(field, column)| Column::new(field, column))
+                .map(|c| SummaryRow {
+                    shape: c.shape[1..].to_vec(),
+                    units: c.metadata.get("units").cloned(),
+                    mean: series::mean(&c.data),
+                    rms: series::rms(&c.data),
+                    std: series::std(&c.data),
+                    min: series::min(&c.data),
+                    max: series::max(&c.data),
+                    name: c.name,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Arrow> for Run {
+    type Error = AnalysisError;
+
+    fn try_from(mut arrow: Arrow) -> Result<Self> {
+        Ok(Self::from(arrow.record()?.clone()))
+    }
+}
+
+impl From<RecordBatch> for Run {
+    /// Creates a run from the data in the record
+    fn from(record: RecordBatch) -> Self {
+        Self {
+            record,
+            sampling_frequency: None,
+        }
+    }
+}
+
+struct SummaryRow {
+    name: String,
+    shape: Vec<usize>,
+    units: Option<String>,
+    mean: f64,
+    rms: f64,
+    std: f64,
+    min: f64,
+    max: f64,
+}
+
+/// Summary of the statistics of the fields of a [Run]
+///
+/// The statistics of a field are computed over all the elements of all the time steps
+pub struct Summary {
+    n_sample: usize,
+    duration: Option<f64>,
+    rows: Vec<SummaryRow>,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} samples", self.n_sample)?;
+        if let Some(duration) = self.duration {
+            write!(f, " ({duration}s)")?;
+        }
+        writeln!(f)?;
+        let width = self
+            .rows
+            .iter()
+            .map(|row| row.name.len())
+            .max()
+            .unwrap_or_default()
+            .max(5);
+        writeln!(
+            f,
+            "{:<width$} {:>12} {:>6} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+            "Field", "Shape", "Units", "Mean", "RMS", "Std", "Min", "Max", "PtV"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:<width$} {:>12} {:>6} {:>12.3e} {:>12.3e} {:>12.3e} {:>12.3e} {:>12.3e} {:>12.3e}",
+                row.name,
+                format!("{:?}", row.shape),
+                row.units.as_deref().unwrap_or("-"),
+                row.mean,
+                row.rms,
+                row.std,
+                row.min,
+                row.max,
+                row.max - row.min
+            )?;
+        }
+        Ok(())
+    }
+}
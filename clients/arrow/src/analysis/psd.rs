@@ -0,0 +1,93 @@
+use std::f64::consts::PI;
+
+use rustfft::{num_complex::Complex64, FftPlanner};
+
+/// Power spectral densities
+///
+/// One-sided power spectral densities in units²/Hz, one per element of the rows of a [Series](super::Series)
+#[derive(Debug, Clone)]
+pub struct Psd {
+    sampling_frequency: f64,
+    nfft: usize,
+    values: Vec<Vec<f64>>,
+}
+
+impl Psd {
+    pub(crate) fn new(sampling_frequency: f64, nfft: usize, values: Vec<Vec<f64>>) -> Self {
+        Self {
+            sampling_frequency,
+            nfft,
+            values,
+        }
+    }
+    /// Returns the frequencies in Hz
+    pub fn frequencies(&self) -> Vec<f64> {
+        let df = self.frequency_resolution();
+        (0..=self.nfft / 2).map(|i| i as f64 * df).collect()
+    }
+    /// Returns the frequency resolution in Hz
+    pub fn frequency_resolution(&self) -> f64 {
+        self.sampling_frequency / self.nfft as f64
+    }
+    /// Returns the power spectral densities
+    pub fn values(&self) -> &[Vec<f64>] {
+        &self.values
+    }
+    /// Returns the cumulative power spectral densities
+    ///
+    /// The cumulative power spectral density is the integral of the power spectral density from 0Hz,
+    /// its last value is the variance of the data
+    pub fn cumulative(&self) -> Self {
+        let df = self.frequency_resolution();
+        Self {
+            values: self
+                .values
+                .iter()
+                .map(|psd| {
+                    psd.iter()
+                        .scan(0f64, |cumsum, p| {
+                            *cumsum += p * df;
+                            Some(*cumsum)
+                        })
+                        .collect()
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Welch power spectral density estimate of `x` sampled at `fs`
+///
+/// `x` must have at least `nfft` samples
+pub(crate) fn welch(x: &[f64], nfft: usize, fs: f64) -> Vec<f64> {
+    let window: Vec<f64> = (0..nfft)
+        .map(|i| (PI * i as f64 / nfft as f64).sin().powi(2))
+        .collect();
+    let scale = fs * window.iter().map(|w| w * w).sum::<f64>();
+    let fft = FftPlanner::new().plan_fft_forward(nfft);
+    let mut data = vec![Complex64::default(); nfft];
+    let hop = nfft / 2;
+    let mut pxx = vec![0f64; 1 + nfft / 2];
+    let mut n_segment = 0;
+    for segment in x.windows(nfft).step_by(hop) {
+        let m = segment.iter().sum::<f64>() / nfft as f64;
+        data.iter_mut()
+            .zip(segment.iter().zip(&window))
+            .for_each(|(c, (x, w))| *c = Complex64::from((x - m) * w));
+        fft.process(&mut data);
+        pxx.iter_mut()
+            .zip(&data)
+            .for_each(|(p, x)| *p += x.norm_sqr());
+        n_segment += 1;
+    }
+    let n = pxx.len();
+    pxx.iter_mut().enumerate().for_each(|(i, p)| {
+        *p /= scale * n_segment as f64;
+        // one-sided spectrum: all but the DC and the Nyquist frequency components are doubled
+        if i > 0 && i < n - 1 {
+            *p *= 2.;
+        }
+    });
+    pxx
+}
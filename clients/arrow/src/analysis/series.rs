@@ -0,0 +1,177 @@
+use std::ops::{Bound, RangeBounds};
+
+use super::{psd::welch, AnalysisError, Psd, Result};
+
+/// Time series of the data of a record field
+///
+/// The data is stored as one row of `f64` per time step.
+#[derive(Debug, Clone, Default)]
+pub struct Series {
+    name: String,
+    data: Vec<Vec<f64>>,
+    sampling_frequency: Option<f64>,
+}
+
+impl Series {
+    /// Creates a new time series from one row of data per time step
+    pub fn new<S: Into<String>>(name: S, data: Vec<Vec<f64>>) -> Self {
+        Self {
+            name: name.into(),
+            data,
+            sampling_frequency: None,
+        }
+    }
+    /// Sets the sampling frequency of the time series in Hz
+    pub fn sampling_frequency(mut self, sampling_frequency: f64) -> Self {
+        self.sampling_frequency = Some(sampling_frequency);
+        self
+    }
+    /// Returns the name of the time series
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the data, one row per time step
+    pub fn data(&self) -> &[Vec<f64>] {
+        &self.data
+    }
+    /// Returns the number of time steps
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Returns true if there is no time step
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the number of elements of a row
+    pub fn n_data(&self) -> usize {
+        self.data.first().map_or(0, |row| row.len())
+    }
+    /// Returns the time vector in seconds, if the sampling frequency is set
+    pub fn time(&self) -> Option<Vec<f64>> {
+        self.sampling_frequency
+            .map(|fs| (0..self.len()).map(|i| i as f64 / fs).collect())
+    }
+    /// Returns the time series of the element `i` of the rows
+    pub fn channel(&self, i: usize) -> Vec<f64> {
+        self.data.iter().map(|row| row[i]).collect()
+    }
+    /// Keeps the time steps within the range of sample indices
+    pub fn window<R: RangeBounds<usize>>(mut self, range: R) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        }
+        .min(self.len());
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.len(),
+        }
+        .clamp(start, self.len());
+        self.data.truncate(end);
+        self.data.drain(..start);
+        self
+    }
+    /// Keeps the time steps within the time range `[start,end[` in seconds
+    pub fn time_window(self, start: f64, end: f64) -> Result<Self> {
+        let fs = self
+            .sampling_frequency
+            .ok_or(AnalysisError::SamplingFrequency)?;
+        let start = (start * fs).ceil().max(0.) as usize;
+        let end = (end * fs).ceil().max(0.) as usize;
+        Ok(self.window(start..end))
+    }
+    /// Keeps every `decimation` time step
+    ///
+    /// The sampling frequency, if any, is divided by `decimation`
+    pub fn decimate(mut self, decimation: usize) -> Self {
+        let decimation = decimation.max(1);
+        self.data = self.data.into_iter().step_by(decimation).collect();
+        self.sampling_frequency = self.sampling_frequency.map(|fs| fs / decimation as f64);
+        self
+    }
+    fn per_channel<F: Fn(&[f64]) -> f64>(&self, f: F) -> Vec<f64> {
+        (0..self.n_data()).map(|i| f(&self.channel(i))).collect()
+    }
+    /// Returns the mean of each element of the rows
+    pub fn mean(&self) -> Vec<f64> {
+        self.per_channel(mean)
+    }
+    /// Returns the root mean square of each element of the rows
+    pub fn rms(&self) -> Vec<f64> {
+        self.per_channel(rms)
+    }
+    /// Returns the standard deviation of each element of the rows
+    pub fn std(&self) -> Vec<f64> {
+        self.per_channel(std)
+    }
+    /// Returns the minimum of each element of the rows
+    pub fn min(&self) -> Vec<f64> {
+        self.per_channel(min)
+    }
+    /// Returns the maximum of each element of the rows
+    pub fn max(&self) -> Vec<f64> {
+        self.per_channel(max)
+    }
+    /// Returns the peak-to-valley of each element of the rows
+    pub fn peak_to_valley(&self) -> Vec<f64> {
+        self.per_channel(|x| max(x) - min(x))
+    }
+    /// Returns the `p`th percentile, with `p` in `[0,100]`, of each element of the rows
+    ///
+    /// The percentile is linearly interpolated between the two nearest samples
+    pub fn percentile(&self, p: f64) -> Vec<f64> {
+        self.per_channel(|x| percentile(x, p))
+    }
+    /// Returns the power spectral density of each element of the rows
+    ///
+    /// The power spectral density is estimated with the Welch method,
+    /// averaging the periodograms of segments of `nfft` samples, `nfft` must be a power of 2.
+    /// The segments overlap by half, their means are removed and they are weighted with a Hann window.
+    pub fn psd(&self, nfft: usize) -> Result<Psd> {
+        let fs = self
+            .sampling_frequency
+            .ok_or(AnalysisError::SamplingFrequency)?;
+        if !nfft.is_power_of_two() || nfft < 2 {
+            return Err(AnalysisError::NotPowerOfTwo(nfft));
+        }
+        if self.len() < nfft {
+            return Err(AnalysisError::TooShort(self.len(), nfft));
+        }
+        let values = (0..self.n_data())
+            .map(|i| welch(&self.channel(i), nfft, fs))
+            .collect();
+        Ok(Psd::new(fs, nfft, values))
+    }
+}
+
+pub(crate) fn mean(x: &[f64]) -> f64 {
+    x.iter().sum::<f64>() / x.len() as f64
+}
+pub(crate) fn rms(x: &[f64]) -> f64 {
+    (x.iter().map(|x| x * x).sum::<f64>() / x.len() as f64).sqrt()
+}
+pub(crate) fn std(x: &[f64]) -> f64 {
+    let m = mean(x);
+    (x.iter().map(|x| (x - m).powi(2)).sum::<f64>() / x.len() as f64).sqrt()
+}
+pub(crate) fn min(x: &[f64]) -> f64 {
+    x.iter().cloned().fold(f64::INFINITY, f64::min)
+}
+pub(crate) fn max(x: &[f64]) -> f64 {
+    x.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+}
+fn percentile(x: &[f64], p: f64) -> f64 {
+    if x.is_empty() {
+        return f64::NAN;
+    }
+    let mut x = x.to_vec();
+    x.sort_by(f64::total_cmp);
+    let rank = p.clamp(0., 100.) / 100. * (x.len() - 1) as f64;
+    let (i, f) = (rank.floor() as usize, rank.fract());
+    match x.get(i + 1) {
+        Some(next) => x[i] + f * (next - x[i]),
+        None => x[i],
+    }
+}
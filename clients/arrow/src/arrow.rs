@@ -1,28 +1,29 @@
-use std::{collections::HashMap, fmt::Display, mem::size_of};
+use std::{any::TypeId, collections::HashMap, fmt::Display};
 
-use apache_arrow::{
-    array::BufferBuilder,
-    datatypes::{ArrowNativeType, DataType},
-    record_batch::RecordBatch,
-};
+use apache_arrow::record_batch::RecordBatch;
 use interface::{print_info, Entry, UniqueIdentifier};
 
-use crate::{
-    ArrowBuffer, BufferDataType, BufferObject, DropOption, FileFormat, LogData, MAX_CAPACITY_BYTE,
-};
+use crate::{ArrowBuffer, ArrowData, Buffers, DropOption, FileFormat, LogData};
 
 mod arrow;
 mod builder;
+mod columns;
 // mod get;
+#[cfg(feature = "hdf5")]
+mod h5;
 mod iter;
+#[cfg(feature = "netcdf")]
+mod nc;
 pub use builder::ArrowBuilder;
+pub(crate) use columns::Column;
 
 /// Apache [Arrow](https://docs.rs/arrow) client
 pub struct Arrow {
     n_step: usize,
     capacities: Vec<usize>,
-    buffers: Vec<(Box<dyn BufferObject>, DataType)>,
-    metadata: Option<HashMap<String, String>>,
+    buffers: Buffers,
+    shapes: HashMap<TypeId, Vec<usize>>,
+    pub(crate) metadata: Option<HashMap<String, String>>,
     pub(crate) step: usize,
     pub(crate) n_entry: usize,
     record: Option<RecordBatch>,
@@ -32,6 +33,10 @@ pub struct Arrow {
     pub(crate) count: usize,
     file_format: FileFormat,
     pub(crate) batch_size: Option<usize>,
+    // number of time steps moved out of the buffers
+    recorded: usize,
+    // number of Parquet part files written in streaming mode
+    part: usize,
 }
 impl Default for Arrow {
     fn default() -> Self {
@@ -39,6 +44,7 @@ impl Default for Arrow {
             n_step: 0,
             capacities: Vec::new(),
             buffers: Vec::new(),
+            shapes: HashMap::new(),
             metadata: None,
             step: 0,
             n_entry: 0,
@@ -49,6 +55,8 @@ impl Default for Arrow {
             count: 0,
             file_format: Default::default(),
             batch_size: None,
+            recorded: 0,
+            part: 0,
         }
     }
 }
@@ -59,10 +67,10 @@ impl Arrow {
     pub fn builder(n_step: usize) -> ArrowBuilder {
         ArrowBuilder::new(n_step)
     }
-    pub(crate) fn data<T, U>(&mut self) -> Option<&mut LogData<ArrowBuffer<U>>>
+    pub(crate) fn data<U>(&mut self) -> Option<&mut LogData<ArrowBuffer<U>>>
     where
-        T: 'static + ArrowNativeType,
-        U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+        U: 'static + UniqueIdentifier,
+        <U as UniqueIdentifier>::DataType: ArrowData,
     {
         self.buffers
             .iter_mut()
@@ -76,20 +84,20 @@ impl Arrow {
     }
 }
 
-impl<T, U> Entry<U> for Arrow
+impl<U> Entry<U> for Arrow
 where
-    T: 'static + BufferDataType + ArrowNativeType + Send + Sync,
-    U: 'static + Send + Sync + UniqueIdentifier<DataType = Vec<T>>,
+    U: 'static + Send + Sync + UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: ArrowData,
 {
     fn entry(&mut self, size: usize) {
-        let mut capacity = size * (1 + self.n_step / self.decimation);
-        //log::info!("Buffer capacity: {}", capacity);
-        if capacity * size_of::<T>() > MAX_CAPACITY_BYTE {
-            capacity = MAX_CAPACITY_BYTE / size_of::<T>();
-            log::info!("Capacity limit of 1GB exceeded, reduced to : {}", capacity);
-        }
-        let buffer: LogData<ArrowBuffer<U>> = LogData::new(BufferBuilder::<T>::new(capacity));
-        self.buffers.push((Box::new(buffer), T::buffer_data_type()));
+        let n_step = self
+            .batch_size
+            .unwrap_or_else(|| 1 + self.n_step / self.decimation);
+        let buffer: LogData<ArrowBuffer<U>> = LogData::new(
+            <<U as UniqueIdentifier>::DataType as ArrowData>::builder(size, n_step),
+        );
+        let shape = self.shapes.get(&TypeId::of::<U>()).cloned();
+        self.buffers.push((Box::new(buffer), shape));
         self.capacities.push(size);
         self.n_entry += 1;
     }
@@ -152,6 +160,11 @@ impl Drop for Arrow {
                     .cloned()
                     .unwrap_or_else(|| "data".to_string());
                 match self.file_format {
+                    FileFormat::Parquet if self.batch_size.is_some() => {
+                        if let Err(e) = self.close() {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
                     FileFormat::Parquet => {
                         if let Err(e) = self.to_parquet(file_name) {
                             print_info("Arrow error", Some(&e));
@@ -163,6 +176,18 @@ impl Drop for Arrow {
                             print_info("Arrow error", Some(&e));
                         }
                     }
+                    #[cfg(feature = "hdf5")]
+                    FileFormat::Hdf5 => {
+                        if let Err(e) = self.to_hdf5(file_name) {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
+                    #[cfg(feature = "netcdf")]
+                    FileFormat::NetCdf => {
+                        if let Err(e) = self.to_netcdf(file_name) {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
                 }
             }
             DropOption::NoSave => {
@@ -1,14 +1,15 @@
 use std::{
+    collections::HashMap,
     env,
-    fs::{DirBuilder, File},
-    path::Path,
+    fs::{self, DirBuilder, File},
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
 use apache_arrow::{
     array::Array,
     compute::concat_batches,
-    datatypes::{DataType, Field, Schema},
+    datatypes::{Field, Schema},
     record_batch::{RecordBatch, RecordBatchReader},
 };
 use interface::print_info;
@@ -21,6 +22,9 @@ use crate::{Arrow, ArrowError, DropOption, FileFormat, Result};
 
 impl Arrow {
     /// Writes the record to file
+    ///
+    /// If a batch size is set, the record is written to a new Parquet part file (see [Arrow::flush])
+    /// or, for the other file formats, moved into the record batch that is written to file when the logger is dropped
     pub fn save(&mut self) -> &mut Self {
         match self.drop_option {
             DropOption::Save(ref filename) => {
@@ -29,17 +33,41 @@ impl Arrow {
                     .cloned()
                     .unwrap_or_else(|| "data".to_string());
                 match self.file_format {
+                    FileFormat::Parquet if self.batch_size.is_some() => {
+                        if let Err(e) = self.flush() {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
                     FileFormat::Parquet => {
                         if let Err(e) = self.to_parquet(file_name) {
                             print_info("Arrow error", Some(&e));
                         }
                     }
+                    // the other formats are written once, when the logger is dropped
+                    #[allow(unreachable_patterns)]
+                    _ if self.batch_size.is_some() => {
+                        if let Err(e) = self.batch() {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
                     #[cfg(feature = "matio-rs")]
                     FileFormat::Matlab(_) => {
                         if let Err(e) = self.to_mat(file_name) {
                             print_info("Arrow error", Some(&e));
                         }
                     }
+                    #[cfg(feature = "hdf5")]
+                    FileFormat::Hdf5 => {
+                        if let Err(e) = self.to_hdf5(file_name) {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
+                    #[cfg(feature = "netcdf")]
+                    FileFormat::NetCdf => {
+                        if let Err(e) = self.to_netcdf(file_name) {
+                            print_info("Arrow error", Some(&e));
+                        }
+                    }
                 }
             }
             DropOption::NoSave => {
@@ -67,25 +95,28 @@ impl Arrow {
     /// Returns the data record
     pub fn record(&mut self) -> Result<&RecordBatch> {
         if self.record.is_none() {
+            let n_step = self.count.checked_div(self.n_entry).unwrap_or_default() - self.recorded;
             let mut lists: Vec<Arc<dyn Array>> = vec![];
-            for ((buffer, buffer_data_type), n) in self.buffers.iter_mut().zip(&self.capacities) {
-                let list = buffer.into_list(
-                    self.batch_size.unwrap_or(self.count / self.n_entry),
-                    *n,
-                    buffer_data_type.clone(),
-                )?;
-                lists.push(Arc::new(list));
+            for ((buffer, shape), n) in self.buffers.iter_mut().zip(&self.capacities) {
+                let list = buffer.take_array(n_step, *n, shape.as_deref())?;
+                lists.push(list);
             }
+            self.recorded += n_step;
 
             let fields: Vec<_> = self
                 .buffers
                 .iter()
-                .map(|(buffer, data_type)| {
-                    Field::new(
-                        &buffer.who(),
-                        DataType::List(Arc::new(Field::new("values", data_type.clone(), false))),
-                        false,
-                    )
+                .zip(&lists)
+                .map(|((buffer, shape), list)| {
+                    let mut metadata = HashMap::new();
+                    if let Some(units) = buffer.units() {
+                        metadata.insert("units".to_string(), units.to_string());
+                    }
+                    if let Some(shape) = shape {
+                        metadata.insert("shape".to_string(), format!("{shape:?}"));
+                    }
+                    Field::new(buffer.who(), list.data_type().clone(), false)
+                        .with_metadata(metadata)
                 })
                 .collect();
             let schema = Arc::new(if let Some(metadata) = self.metadata.as_ref() {
@@ -98,6 +129,61 @@ impl Arrow {
         }
         self.record.as_ref().ok_or(ArrowError::NoRecord)
     }
+    /// Writes the data logged since the last call to a new [Parquet](https://docs.rs/parquet) part file
+    ///
+    /// The part files `part-<i>.parquet` are saved in a directory named after the Parquet data file
+    /// (`data.parquet/` by default), the directory is emptied of previous part files on the first call.
+    /// Each part file is complete on its own: it is written under a temporary name and renamed once closed,
+    /// so the data already written can be loaded with [Arrow::from_parquet] even if the simulation crashes.
+    pub fn flush(&mut self) -> Result<()> {
+        let DropOption::Save(ref filename) = self.drop_option else {
+            return Ok(());
+        };
+        let file_name = filename
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| "data".to_string());
+        if self.count.checked_div(self.n_entry).unwrap_or_default() == self.recorded {
+            return Ok(());
+        }
+        let root = data_path(file_name, "parquet")?;
+        if self.part == 0 {
+            if root.is_file() {
+                fs::remove_file(&root)?;
+            }
+            if root.is_dir() {
+                for path in parts(&root)? {
+                    fs::remove_file(path)?;
+                }
+            }
+            DirBuilder::new().recursive(true).create(&root)?;
+            log::info!("Streaming Arrow data to {root:?}");
+        }
+        self.record()?;
+        let record = self.record.take().ok_or(ArrowError::NoRecord)?;
+        let part = root.join(format!("part-{:05}.parquet", self.part));
+        let tmp = part.with_extension("parquet.tmp");
+        let props = WriterProperties::builder().build();
+        let mut writer = ArrowWriter::try_new(File::create(&tmp)?, record.schema(), Some(props))?;
+        writer.write(&record)?;
+        writer.close()?;
+        fs::rename(tmp, part)?;
+        self.part += 1;
+        Ok(())
+    }
+    /// Writes the remaining data to a last [Parquet](https://docs.rs/parquet) part file
+    ///
+    /// See [Arrow::flush]
+    pub fn close(&mut self) -> Result<()> {
+        self.flush()?;
+        log::info!(
+            "Arrow data saved ({} part files, {} rows)",
+            self.part,
+            self.recorded
+        );
+        self.drop_option = DropOption::NoSave;
+        Ok(())
+    }
     /// Saves the data to a [Parquet](https://docs.rs/parquet) data file
     ///
     /// The [Parquet](https://docs.rs/parquet) data file is saved in the current directory
@@ -106,13 +192,13 @@ impl Arrow {
     pub fn to_parquet<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
         // let batch = self.record()?;
         let batch = self.concat_batches()?;
-        let root_env = env::var("DATA_REPO").unwrap_or_else(|_| ".".to_string());
-        let root = Path::new(&root_env).join(&path).with_extension("parquet");
-        if let Some(path) = root.parent() {
-            if !path.is_dir() {
-                DirBuilder::new().recursive(true).create(&path)?;
+        let root = data_path(path, "parquet")?;
+        if root.is_dir() {
+            for path in parts(&root)? {
+                fs::remove_file(path)?;
             }
-        };
+            fs::remove_dir(&root)?;
+        }
         let file = File::create(&root)?;
         let props = WriterProperties::builder().build();
         let mut writer = ArrowWriter::try_new(file, Arc::clone(&batch.schema()), Some(props))?;
@@ -124,6 +210,9 @@ impl Arrow {
     }
     /// Loads data from a [Parquet](https://docs.rs/parquet) data file
     ///
+    /// If the data file is a directory, the data is loaded from the Parquet part files
+    /// written in streaming mode (see [Arrow::flush]).
+    ///
     /// The [Parquet](https://docs.rs/parquet) data file is loaded from the current
     /// directory unless the environment variable `DATA_REPO` is set to another directory.
     /// We will try to create the directory if does not exist.
@@ -139,19 +228,29 @@ impl Arrow {
                 DirBuilder::new().recursive(true).create(&path)?;
             }
         };
-        let file = File::open(&filename)?;
         log::info!("Loading {:?}", filename);
-        let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(file)?
-            .with_batch_size(2048)
-            .build()?;
-        let schema = parquet_reader.schema();
-        let records: std::result::Result<Vec<_>, apache_arrow::error::ArrowError> =
-            parquet_reader.collect();
-        let record = concat_batches(&schema, records?.as_slice())?;
+        let files = if filename.is_dir() {
+            parts(&filename)?
+        } else {
+            vec![filename]
+        };
+        let mut schema = None;
+        let mut records = vec![];
+        for file in files {
+            let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(File::open(file)?)?
+                .with_batch_size(2048)
+                .build()?;
+            schema.get_or_insert_with(|| parquet_reader.schema());
+            for record in parquet_reader {
+                records.push(record?);
+            }
+        }
+        let record = concat_batches(&schema.ok_or(ArrowError::NoRecord)?, records.as_slice())?;
         Ok(Arrow {
             n_step: 0,
             capacities: Vec::new(),
             buffers: Vec::new(),
+            shapes: HashMap::new(),
             metadata: None,
             step: 0,
             n_entry: 0,
@@ -162,6 +261,8 @@ impl Arrow {
             count: 0,
             file_format: FileFormat::Parquet,
             batch_size: None,
+            recorded: 0,
+            part: 0,
         })
     }
     #[cfg(feature = "matio-rs")]
@@ -201,3 +302,28 @@ impl Arrow {
         Ok(())
     }
 }
+
+/// Returns the path to the data file in the `DATA_REPO` directory, creating the directory if needed
+pub(crate) fn data_path<P: AsRef<Path>>(path: P, extension: &str) -> Result<PathBuf> {
+    let root_env = env::var("DATA_REPO").unwrap_or_else(|_| ".".to_string());
+    let root = Path::new(&root_env).join(&path).with_extension(extension);
+    if let Some(path) = root.parent() {
+        if !path.is_dir() {
+            DirBuilder::new().recursive(true).create(path)?;
+        }
+    };
+    Ok(root)
+}
+
+// Returns the Parquet part files of a directory, sorted by name
+fn parts(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut parts = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "parquet") {
+            parts.push(path);
+        }
+    }
+    parts.sort();
+    Ok(parts)
+}
@@ -1,21 +1,18 @@
-use std::{collections::HashMap, mem::size_of};
+use std::{any::TypeId, collections::HashMap};
 
-use apache_arrow::{
-    array::BufferBuilder,
-    datatypes::{ArrowNativeType, DataType},
-};
+use apache_arrow::datatypes::ArrowNativeType;
 use interface::UniqueIdentifier;
 
 use crate::{
-    Arrow, ArrowBuffer, BufferDataType, BufferObject, DropOption, FileFormat, LogData,
-    MAX_CAPACITY_BYTE,
+    Arrow, ArrowBuffer, ArrowData, BufferDataType, Buffers, DropOption, FileFormat, LogData,
 };
 
 /// Arrow format logger builder
 pub struct ArrowBuilder {
     n_step: usize,
     capacities: Vec<usize>,
-    buffers: Vec<(Box<dyn BufferObject>, DataType)>,
+    buffers: Buffers,
+    shapes: HashMap<TypeId, Vec<usize>>,
     metadata: Option<HashMap<String, String>>,
     n_entry: usize,
     drop_option: DropOption,
@@ -31,6 +28,7 @@ impl ArrowBuilder {
             n_step,
             capacities: Vec::new(),
             buffers: Vec::new(),
+            shapes: HashMap::new(),
             metadata: None,
             n_entry: 0,
             drop_option: DropOption::Save(None),
@@ -47,14 +45,15 @@ impl ArrowBuilder {
         U: 'static + Send + Sync + UniqueIdentifier<DataType = Vec<T>>,
     {
         let mut buffers = self.buffers;
-        let mut capacity = size * (1 + self.n_step / self.decimation);
-        //log::info!("Buffer capacity: {}", capacity);
-        if capacity * size_of::<T>() > MAX_CAPACITY_BYTE {
-            capacity = MAX_CAPACITY_BYTE / size_of::<T>();
-            log::info!("Capacity limit of 1GB exceeded, reduced to : {}", capacity);
-        }
-        let buffer: LogData<ArrowBuffer<U>> = LogData::new(BufferBuilder::<T>::new(capacity));
-        buffers.push((Box::new(buffer), T::buffer_data_type()));
+        let buffer: LogData<ArrowBuffer<U>> = LogData::new(<Vec<T> as ArrowData>::builder(
+            size,
+            self.batch_size
+                .unwrap_or_else(|| 1 + self.n_step / self.decimation),
+        ));
+        buffers.push((
+            Box::new(buffer),
+            self.shapes.get(&TypeId::of::<U>()).cloned(),
+        ));
         let mut capacities = self.capacities;
         capacities.push(size);
         Self {
@@ -66,11 +65,22 @@ impl ArrowBuilder {
     }
     /// Sets the size of the record batch in number of time steps
     ///
-    /// The record will be written to file every time step that is a multiple of the batch size
+    /// The logger streams the data to Parquet files: the buffers are sized for a single batch
+    /// and the record is written to a new Parquet part file every time step that is a multiple of the batch size.
+    /// The part files are saved in a directory named after the Parquet file, see [Arrow::flush].
     pub fn batch_size(mut self, batch_size: usize) -> Self {
         self.batch_size = Some(batch_size);
         self
     }
+    /// Sets the shape of the data of the UID `U`
+    ///
+    /// The data of a time step is saved as a fixed size list,
+    /// instead of a list, with the shape saved in the `shape` key of the metadata of the data field.
+    /// The number of elements of the shape must match the size of the data.
+    pub fn shape<U: 'static + UniqueIdentifier>(mut self, shape: impl Into<Vec<usize>>) -> Self {
+        self.shapes.insert(TypeId::of::<U>(), shape.into());
+        self
+    }
     /// Sets the name of the file to save the data to (default: "data.parquet")
     pub fn filename<S: Into<String>>(self, filename: S) -> Self {
         Self {
@@ -93,7 +103,18 @@ impl ArrowBuilder {
         }
     }
     pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
-        self.metadata = Some(metadata);
+        self.metadata
+            .get_or_insert_with(Default::default)
+            .extend(metadata);
+        self
+    }
+    /// Sets the name of the model the data is logged from
+    ///
+    /// The name is saved in the `model` key of the metadata of the data schema
+    pub fn model<S: Into<String>>(mut self, name: S) -> Self {
+        self.metadata
+            .get_or_insert_with(Default::default)
+            .insert("model".to_string(), name.into());
         self
     }
     /// Decimate the data by the given factor
@@ -109,6 +130,7 @@ impl ArrowBuilder {
             n_step: self.n_step,
             capacities: self.capacities,
             buffers: self.buffers,
+            shapes: self.shapes,
             metadata: self.metadata,
             step: 0,
             n_entry: self.n_entry,
@@ -119,6 +141,8 @@ impl ArrowBuilder {
             count: 0,
             file_format: self.file_format,
             batch_size: self.batch_size,
+            recorded: 0,
+            part: 0,
         }
     }
 }
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, sync::Arc};
+
+use apache_arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array},
+    compute::cast,
+    datatypes::{DataType, Field, Float64Type, Schema},
+    record_batch::RecordBatch,
+};
+
+use crate::{data::into_list, Arrow, ArrowError, Result};
+
+/// Numeric data of a record field
+///
+/// The data of all the time steps is cast to `f64` and stored contiguously,
+/// the first dimension of the shape is the number of time steps
+pub(crate) struct Column {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub data: Vec<f64>,
+    pub metadata: HashMap<String, String>,
+}
+
+// Parses the `shape` key of the metadata of a field
+fn parse_shape(shape: &str) -> Option<Vec<usize>> {
+    shape
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|n| n.trim().parse().ok())
+        .collect()
+}
+
+impl Column {
+    /// Casts the data of a record field to a [Column]
+    ///
+    /// Returns [None] if the field is not a list of numbers
+    pub(crate) fn new(field: &Field, column: &ArrayRef) -> Option<Self> {
+        let (n_sample, n_data, values) = if let Some(list) = column.as_list_opt::<i32>() {
+            let offsets = list.value_offsets();
+            let (first, last) = (offsets[0] as usize, offsets[list.len()] as usize);
+            let n_data = (last - first).checked_div(list.len()).unwrap_or_default();
+            if list.offsets().lengths().any(|n| n != n_data) {
+                log::warn!("{}: lists of different lengths are skipped", field.name());
+                return None;
+            }
+            (list.len(), n_data, list.values().slice(first, last - first))
+        } else if let Some(list) = column.as_fixed_size_list_opt() {
+            let n_data = list.value_length() as usize;
+            (
+                list.len(),
+                n_data,
+                list.values()
+                    .slice(list.offset() * n_data, list.len() * n_data),
+            )
+        } else {
+            log::warn!("{}: {} data is skipped", field.name(), field.data_type());
+            return None;
+        };
+        let Ok(values) = cast(&values, &DataType::Float64) else {
+            log::warn!("{}: {} data is skipped", field.name(), field.data_type());
+            return None;
+        };
+        let mut shape = vec![n_sample];
+        match field.metadata().get("shape").and_then(|s| parse_shape(s)) {
+            Some(data_shape) => shape.extend(data_shape),
+            None => shape.push(n_data),
+        }
+        Some(Self {
+            name: field.name().to_string(),
+            shape,
+            data: values.as_primitive::<Float64Type>().values().to_vec(),
+            metadata: field.metadata().clone(),
+        })
+    }
+}
+
+impl Arrow {
+    /// Returns the record fields that are lists of numbers as [Column]s and the record metadata
+    ///
+    /// Fields of other data types are skipped
+    #[cfg_attr(not(any(feature = "hdf5", feature = "netcdf")), allow(dead_code))]
+    pub(crate) fn columns(&mut self) -> Result<(Vec<Column>, HashMap<String, String>)> {
+        let record = self.concat_batches()?;
+        let schema = record.schema();
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(record.columns())
+            .filter_map(|(field, column)| Column::new(field, column))
+            .collect();
+        Ok((columns, schema.metadata().clone()))
+    }
+    /// Creates a logger with a record made of the [Column]s
+    ///
+    /// The rows of a column are fixed size lists if its metadata has a `shape` key
+    #[cfg_attr(not(any(feature = "hdf5", feature = "netcdf")), allow(dead_code))]
+    pub(crate) fn from_columns(
+        columns: Vec<Column>,
+        metadata: HashMap<String, String>,
+    ) -> Result<Self> {
+        let mut fields = vec![];
+        let mut arrays: Vec<ArrayRef> = vec![];
+        for column in columns {
+            let (n_sample, shape) = column
+                .shape
+                .split_first()
+                .ok_or_else(|| ArrowError::ParseField(column.name.clone()))?;
+            let n_data = shape.iter().product();
+            let array = into_list(
+                Float64Array::from(column.data).into_data(),
+                *n_sample,
+                n_data,
+                column.metadata.contains_key("shape").then_some(shape),
+            )?;
+            fields.push(
+                Field::new(column.name, array.data_type().clone(), false)
+                    .with_metadata(column.metadata),
+            );
+            arrays.push(array);
+        }
+        let schema = Schema::new_with_metadata(fields, metadata);
+        let mut arrow = Arrow::default();
+        arrow.record = Some(RecordBatch::try_new(Arc::new(schema), arrays)?);
+        Ok(arrow)
+    }
+}
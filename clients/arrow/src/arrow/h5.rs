@@ -0,0 +1,91 @@
+use std::{collections::HashMap, path::Path};
+
+use hdf5::{types::VarLenUnicode, File, Location};
+
+use super::{arrow::data_path, columns::Column};
+use crate::{Arrow, ArrowError, DropOption, Result};
+
+// Writes the pairs of keys and values as string attributes of an HDF5 location
+fn write_attributes(location: &Location, attributes: &HashMap<String, String>) -> Result<()> {
+    for (key, value) in attributes {
+        let value: VarLenUnicode = value
+            .parse()
+            .map_err(|_| ArrowError::Attribute(key.clone()))?;
+        location
+            .new_attr::<VarLenUnicode>()
+            .shape(())
+            .create(key.as_str())?
+            .write_scalar(&value)?;
+    }
+    Ok(())
+}
+
+// Reads the string attributes of an HDF5 location
+fn read_attributes(location: &Location) -> Result<HashMap<String, String>> {
+    location
+        .attr_names()?
+        .into_iter()
+        .map(|name| {
+            let value = location.attr(&name)?.read_scalar::<VarLenUnicode>()?;
+            Ok((name, value.to_string()))
+        })
+        .collect()
+}
+
+impl Arrow {
+    /// Saves the data to an [HDF5](https://docs.rs/hdf5) data file
+    ///
+    /// Each field of the record is written to a dataset of `f64` with the time steps as the leading dimension,
+    /// followed by the shape of the data if any.
+    /// The metadata of the record and of the fields are saved as string attributes of the file and of the datasets, respectively.
+    /// Only the fields that are lists of numbers are saved.
+    ///
+    /// The HDF5 data file is saved in the current directory
+    /// unless the environment variable `DATA_REPO` is set to another directory.
+    pub fn to_hdf5<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let (columns, metadata) = self.columns()?;
+        let root = data_path(path, "h5")?;
+        let file = File::create(&root)?;
+        write_attributes(&file, &metadata)?;
+        for Column {
+            name,
+            shape,
+            data,
+            metadata,
+        } in columns
+        {
+            let dataset = file
+                .new_dataset::<f64>()
+                .shape(shape)
+                .create(name.as_str())?;
+            dataset.write_raw(data.as_slice())?;
+            write_attributes(&dataset, &metadata)?;
+        }
+        log::info!("Arrow data saved to {root:?}");
+        self.drop_option = DropOption::NoSave;
+        Ok(())
+    }
+    /// Loads data from an [HDF5](https://docs.rs/hdf5) data file written with [Arrow::to_hdf5]
+    ///
+    /// The HDF5 data file is loaded from the current directory
+    /// unless the environment variable `DATA_REPO` is set to another directory.
+    pub fn from_hdf5<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = data_path(path, "h5")?;
+        let file = File::open(&filename)?;
+        log::info!("Loading {:?}", filename);
+        let metadata = read_attributes(&file)?;
+        let columns = file
+            .datasets()?
+            .into_iter()
+            .map(|dataset| {
+                Ok(Column {
+                    name: dataset.name().trim_start_matches('/').to_string(),
+                    shape: dataset.shape(),
+                    data: dataset.read_raw::<f64>()?,
+                    metadata: read_attributes(&dataset)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Arrow::from_columns(columns, metadata)
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::VecDeque;
 
 use apache_arrow::{
-    array::{ListArray, PrimitiveArray},
+    array::{FixedSizeListArray, ListArray, PrimitiveArray},
     datatypes::ArrowPrimitiveType,
 };
 
@@ -48,6 +48,8 @@ where
 
 impl Arrow {
     /// Return an iterator over the data in the specified field
+    ///
+    /// The data of the field is either a list or a fixed size list of primitive types
     pub fn iter<S, T>(&mut self, field_name: S) -> Result<ArrowIter<T>>
     where
         S: AsRef<str>,
@@ -58,24 +60,29 @@ impl Arrow {
     {
         match self.record() {
             Ok(record) => match record.schema().column_with_name(field_name.as_ref()) {
-                Some((idx, _)) => record
-                    .column(idx)
-                    .as_any()
-                    .downcast_ref::<ListArray>()
-                    .map(|data| {
-                        data.iter()
-                            .map(|data| {
-                                data.map(|data| {
-                                    data.as_any()
-                                        .downcast_ref::<PrimitiveArray<<T as BufferDataType>::ArrayType>>()
-                                        .and_then(|data| data.iter().collect::<Option<Vec<T>>>())
+                Some((idx, _)) => {
+                    let column = record.column(idx).as_any();
+                    column
+                        .downcast_ref::<ListArray>()
+                        .map(|data| data.iter().collect::<Vec<_>>())
+                        .or_else(|| {
+                            column
+                                .downcast_ref::<FixedSizeListArray>()
+                                .map(|data| data.iter().collect::<Vec<_>>())
+                        })
+                        .and_then(|data| {
+                            data.into_iter()
+                                .map(|data| {
+                                    data.and_then(|data| {
+                                        data.as_any()
+                                            .downcast_ref::<PrimitiveArray<<T as BufferDataType>::ArrayType>>()
+                                            .and_then(|data| data.iter().collect::<Option<Vec<T>>>())
+                                    })
                                 })
-                                .flatten()
-                            })
-                            .collect::<Option<VecDeque<Vec<T>>>>()
-                    })
-                    .flatten()
-                    .ok_or_else(|| ArrowError::ParseField(field_name.into())),
+                                .collect::<Option<VecDeque<Vec<T>>>>()
+                        })
+                        .ok_or_else(|| ArrowError::ParseField(field_name.into()))
+                }
                 None => Err(ArrowError::FieldNotFound(field_name.into())),
             },
             Err(e) => Err(e),
@@ -0,0 +1,85 @@
+use std::{collections::HashMap, path::Path};
+
+use netcdf::{Attribute, AttributeValue};
+
+use super::{arrow::data_path, columns::Column};
+use crate::{Arrow, ArrowError, DropOption, Result};
+
+// Reads the string attributes of a NetCDF file or variable
+fn read_attributes<'a>(
+    attributes: impl Iterator<Item = Attribute<'a>>,
+) -> Result<HashMap<String, String>> {
+    attributes
+        .map(|attribute| match attribute.value()? {
+            AttributeValue::Str(value) => Ok((attribute.name().to_string(), value)),
+            _ => Err(ArrowError::Attribute(attribute.name().to_string())),
+        })
+        .collect()
+}
+
+impl Arrow {
+    /// Saves the data to a [NetCDF](https://docs.rs/netcdf) data file
+    ///
+    /// Each field of the record is written to a variable of `f64` with the `time` dimension as the leading dimension,
+    /// followed by the dimensions `<field name>_<i>` of the shape of the data.
+    /// The metadata of the record and of the fields are saved as string attributes of the file and of the variables, respectively.
+    /// Only the fields that are lists of numbers are saved.
+    ///
+    /// The NetCDF data file is saved in the current directory
+    /// unless the environment variable `DATA_REPO` is set to another directory.
+    pub fn to_netcdf<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let (columns, metadata) = self.columns()?;
+        let root = data_path(path, "nc")?;
+        let mut file = netcdf::create(&root)?;
+        for (key, value) in &metadata {
+            file.add_attribute(key, value.as_str())?;
+        }
+        let n_sample = columns.first().map_or(0, |column| column.shape[0]);
+        file.add_dimension("time", n_sample)?;
+        for Column {
+            name,
+            shape,
+            data,
+            metadata,
+        } in columns
+        {
+            let mut dimensions = vec!["time".to_string()];
+            for (i, n) in shape.iter().skip(1).enumerate() {
+                let dimension = format!("{name}_{i}");
+                file.add_dimension(&dimension, *n)?;
+                dimensions.push(dimension);
+            }
+            let dimensions: Vec<_> = dimensions.iter().map(String::as_str).collect();
+            let mut variable = file.add_variable::<f64>(&name, &dimensions)?;
+            variable.put_values(&data, ..)?;
+            for (key, value) in &metadata {
+                variable.put_attribute(key, value.as_str())?;
+            }
+        }
+        log::info!("Arrow data saved to {root:?}");
+        self.drop_option = DropOption::NoSave;
+        Ok(())
+    }
+    /// Loads data from a [NetCDF](https://docs.rs/netcdf) data file written with [Arrow::to_netcdf]
+    ///
+    /// The NetCDF data file is loaded from the current directory
+    /// unless the environment variable `DATA_REPO` is set to another directory.
+    pub fn from_netcdf<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = data_path(path, "nc")?;
+        let file = netcdf::open(&filename)?;
+        log::info!("Loading {:?}", filename);
+        let metadata = read_attributes(file.attributes())?;
+        let columns = file
+            .variables()
+            .map(|variable| {
+                Ok(Column {
+                    name: variable.name(),
+                    shape: variable.dimensions().iter().map(|d| d.len()).collect(),
+                    data: variable.get_values::<f64, _>(..)?,
+                    metadata: read_attributes(variable.attributes())?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Arrow::from_columns(columns, metadata)
+    }
+}
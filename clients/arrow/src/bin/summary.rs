@@ -0,0 +1,35 @@
+use clap::Parser;
+use gmt_dos_clients_arrow::analysis::Run;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Summary of the statistics of the fields of a Parquet data file", long_about = None
+)]
+struct Cli {
+    /// Path to the Parquet data file
+    path: String,
+    /// Sampling frequency of the data [Hz]
+    #[arg(short, long)]
+    sampling_frequency: Option<f64>,
+    /// Lists the metadata of the data file
+    #[arg(short, long)]
+    metadata: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let mut run = Run::open(&cli.path)?;
+    if let Some(sampling_frequency) = cli.sampling_frequency {
+        run = run.sampling_frequency(sampling_frequency);
+    }
+    if cli.metadata {
+        for (key, value) in run.metadata() {
+            println!("{key}: {value}");
+        }
+    }
+    println!("{}", run.summary());
+
+    Ok(())
+}
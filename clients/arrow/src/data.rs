@@ -0,0 +1,209 @@
+use std::{mem::size_of, sync::Arc};
+
+use apache_arrow::{
+    array::{
+        Array, ArrayData, ArrayRef, BooleanArray, BooleanBufferBuilder, BufferBuilder,
+        FixedSizeListArray, ListArray, ListBuilder, PrimitiveBuilder, StructArray,
+    },
+    buffer::Buffer,
+    datatypes::{ArrowNativeType, DataType, Field, ToByteSlice},
+};
+
+use crate::{ArrowError, BufferDataType, Result, MAX_CAPACITY_BYTE};
+
+/// Conversion of the data of a UID into an Apache [Arrow](https://docs.rs/arrow) array
+///
+/// The data of each time step is appended to a buffer that is moved into an array
+/// with one row per time step when the record is built.
+/// [ArrowData] is implemented for:
+///  - `Vec<T>` and `Vec<bool>`: a list per time step or a fixed size list if the [shape](crate::ArrowBuilder::shape) of the data is given,
+///  - `Vec<Vec<T>>`: a list of lists per time step,
+///  - `(T1, T2)` and `(T1, T2, T3)`: a struct per time step with the fields `0`, `1` and `2`,
+///  - any type implementing [ArrowSerialize] (requires the `serde` feature): a struct per time step,
+///
+/// where `T`, `T1`, `T2` and `T3` are any of the Rust primitive numeric types.
+pub trait ArrowData: Send + Sync + 'static {
+    /// Buffer the data is appended to
+    type Builder: Send + Sync + 'static;
+    /// Creates the buffer for `n_step` time steps of `size` elements
+    fn builder(size: usize, n_step: usize) -> Self::Builder;
+    /// Appends the data of a time step to the buffer
+    fn append(&self, builder: &mut Self::Builder);
+    /// Moves the content of the buffer into an array of `n_step` rows of `size` elements
+    fn finish(
+        builder: &mut Self::Builder,
+        n_step: usize,
+        size: usize,
+        shape: Option<&[usize]>,
+    ) -> Result<ArrayRef>;
+}
+
+// Returns the capacity of a buffer for `n_step` time steps of `size` elements of type `T`
+fn capacity<T>(size: usize, n_step: usize) -> usize {
+    let mut capacity = size * n_step;
+    //log::info!("Buffer capacity: {}", capacity);
+    if capacity * size_of::<T>() > MAX_CAPACITY_BYTE {
+        capacity = MAX_CAPACITY_BYTE / size_of::<T>();
+        log::warn!(
+            "Capacity limit of 1GB exceeded, reduced to : {} (set a batch size to stream the data to file)",
+            capacity
+        );
+    }
+    capacity
+}
+
+// Wraps the values into a list array of `n_step` rows of `size` elements,
+// the rows are fixed size lists if a shape is given
+pub(crate) fn into_list(
+    values: ArrayData,
+    n_step: usize,
+    size: usize,
+    shape: Option<&[usize]>,
+) -> Result<ArrayRef> {
+    let field = Arc::new(Field::new("values", values.data_type().clone(), false));
+    match shape {
+        Some(shape) => {
+            if shape.iter().product::<usize>() != size {
+                return Err(ArrowError::Shape(shape.to_vec(), size));
+            }
+            let list = ArrayData::builder(DataType::FixedSizeList(field, size as i32))
+                .len(n_step)
+                .add_child_data(values)
+                .build()?;
+            Ok(Arc::new(FixedSizeListArray::from(list)))
+        }
+        None => {
+            let offsets = (0..).step_by(size).take(n_step + 1).collect::<Vec<i32>>();
+            let list = ArrayData::builder(DataType::List(field))
+                .len(n_step)
+                .add_buffer(Buffer::from(offsets.to_byte_slice()))
+                .add_child_data(values)
+                .build()?;
+            Ok(Arc::new(ListArray::from(list)))
+        }
+    }
+}
+
+impl<T> ArrowData for Vec<T>
+where
+    T: 'static + BufferDataType + ArrowNativeType + Send + Sync,
+{
+    type Builder = BufferBuilder<T>;
+
+    fn builder(size: usize, n_step: usize) -> Self::Builder {
+        BufferBuilder::<T>::new(capacity::<T>(size, n_step))
+    }
+    fn append(&self, builder: &mut Self::Builder) {
+        builder.append_slice(self);
+    }
+    fn finish(
+        builder: &mut Self::Builder,
+        n_step: usize,
+        size: usize,
+        shape: Option<&[usize]>,
+    ) -> Result<ArrayRef> {
+        let values = ArrayData::builder(T::buffer_data_type())
+            .len(builder.len())
+            .add_buffer(builder.finish())
+            .build()?;
+        into_list(values, n_step, size, shape)
+    }
+}
+
+/// Masks are saved as lists of booleans
+impl ArrowData for Vec<bool> {
+    type Builder = BooleanBufferBuilder;
+
+    fn builder(size: usize, n_step: usize) -> Self::Builder {
+        BooleanBufferBuilder::new(capacity::<u8>(size, n_step))
+    }
+    fn append(&self, builder: &mut Self::Builder) {
+        builder.append_slice(self);
+    }
+    fn finish(
+        builder: &mut Self::Builder,
+        n_step: usize,
+        size: usize,
+        shape: Option<&[usize]>,
+    ) -> Result<ArrayRef> {
+        let values = BooleanArray::new(builder.finish(), None).into_data();
+        into_list(values, n_step, size, shape)
+    }
+}
+
+/// The number of elements of the inner lists may vary from one list to another,
+/// the shape of the data, if any, is ignored
+impl<T> ArrowData for Vec<Vec<T>>
+where
+    T: 'static + BufferDataType + ArrowNativeType + Send + Sync,
+{
+    type Builder = ListBuilder<ListBuilder<PrimitiveBuilder<T::ArrayType>>>;
+
+    fn builder(size: usize, n_step: usize) -> Self::Builder {
+        let values = Field::new("values", T::buffer_data_type(), false);
+        let lists = Field::new("values", DataType::List(Arc::new(values.clone())), false);
+        ListBuilder::with_capacity(
+            ListBuilder::new(PrimitiveBuilder::with_capacity(capacity::<T>(size, n_step)))
+                .with_field(values),
+            n_step,
+        )
+        .with_field(lists)
+    }
+    fn append(&self, builder: &mut Self::Builder) {
+        for data in self {
+            builder.values().values().append_slice(data);
+            builder.values().append(true);
+        }
+        builder.append(true);
+    }
+    fn finish(
+        builder: &mut Self::Builder,
+        _n_step: usize,
+        _size: usize,
+        _shape: Option<&[usize]>,
+    ) -> Result<ArrayRef> {
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+macro_rules! impl_tuples {
+    ( $( ($($t:ident $i:tt),+) ),+ ) => {
+        $(
+/// Tuples are saved as structs with the tuple indices as field names
+impl<$($t),+> ArrowData for ($($t,)+)
+where
+    $($t: 'static + BufferDataType + ArrowNativeType + Send + Sync,)+
+{
+    type Builder = ($(PrimitiveBuilder<$t::ArrayType>,)+);
+
+    fn builder(_size: usize, n_step: usize) -> Self::Builder {
+        ($(PrimitiveBuilder::<$t::ArrayType>::with_capacity(n_step),)+)
+    }
+    fn append(&self, builder: &mut Self::Builder) {
+        $(builder.$i.append_value(self.$i);)+
+    }
+    fn finish(
+        builder: &mut Self::Builder,
+        _n_step: usize,
+        _size: usize,
+        _shape: Option<&[usize]>,
+    ) -> Result<ArrayRef> {
+        Ok(Arc::new(StructArray::from(vec![$((
+            Arc::new(Field::new(stringify!($i), $t::buffer_data_type(), false)),
+            Arc::new(builder.$i.finish()) as ArrayRef,
+        )),+])))
+    }
+}
+        )+
+    };
+}
+
+impl_tuples! {
+    (T1 0, T2 1),
+    (T1 0, T2 1, T3 2)
+}
+
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "serde")]
+pub use serialize::ArrowSerialize;
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use apache_arrow::{
+    array::{new_empty_array, ArrayRef, StructArray},
+    datatypes::{DataType, Fields, SchemaRef},
+    json::{reader::infer_json_schema_from_iterator, ReaderBuilder},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::ArrowData;
+use crate::{ArrowError, Result};
+
+/// Marker trait for the data types saved as Arrow structs
+///
+/// The data is serialized with [serde] and the schema of the struct is inferred from the first batch of data,
+/// nested structs and sequences are saved as nested structs and lists.
+/// Building the record fails if the data cannot be serialized or if it does not match the schema.
+/// ```
+/// use gmt_dos_clients_arrow::{Arrow, ArrowSerialize};
+/// use interface::{Data, Entry, Read, UID};
+///
+/// #[derive(serde::Serialize)]
+/// pub struct Pose {
+///     position: (f64, f64),
+///     angle: f64,
+/// }
+/// impl ArrowSerialize for Pose {}
+///
+/// #[derive(UID)]
+/// #[uid(data = Pose)]
+/// pub enum Telescope {}
+///
+/// let mut logging = Arrow::builder(10).no_save().build();
+/// <Arrow as Entry<Telescope>>::entry(&mut logging, 1);
+/// for i in 0..10 {
+///     let angle = i as f64;
+///     <Arrow as Read<Telescope>>::read(
+///         &mut logging,
+///         Data::new(Pose { position: (angle.cos(), angle.sin()), angle }),
+///     );
+/// }
+/// assert_eq!(logging.record().unwrap().num_rows(), 10);
+/// ```
+pub trait ArrowSerialize: Serialize + Send + Sync + 'static {}
+
+/// Buffer of the serialized data
+///
+/// The schema of the struct is inferred from the first batch of data and used for all the following batches
+#[derive(Default)]
+pub struct SerializeBuilder {
+    rows: Vec<Value>,
+    schema: Option<SchemaRef>,
+    error: Option<serde_json::Error>,
+}
+
+impl<T: ArrowSerialize> ArrowData for T {
+    type Builder = SerializeBuilder;
+
+    fn builder(_size: usize, n_step: usize) -> Self::Builder {
+        SerializeBuilder {
+            rows: Vec::with_capacity(n_step),
+            ..Default::default()
+        }
+    }
+    fn append(&self, builder: &mut Self::Builder) {
+        match serde_json::to_value(self) {
+            Ok(value) => builder.rows.push(value),
+            // the first error is returned when the record is built
+            Err(e) => {
+                builder.error.get_or_insert(e);
+            }
+        }
+    }
+    fn finish(
+        builder: &mut Self::Builder,
+        _n_step: usize,
+        _size: usize,
+        _shape: Option<&[usize]>,
+    ) -> Result<ArrayRef> {
+        let rows = std::mem::take(&mut builder.rows);
+        if let Some(e) = builder.error.take() {
+            return Err(e.into());
+        }
+        if rows.is_empty() {
+            let fields = builder
+                .schema
+                .as_ref()
+                .map_or_else(Fields::empty, |schema| schema.fields().clone());
+            return Ok(new_empty_array(&DataType::Struct(fields)));
+        }
+        let schema =
+            match &builder.schema {
+                Some(schema) => Arc::clone(schema),
+                None => Arc::clone(builder.schema.insert(Arc::new(
+                    infer_json_schema_from_iterator(rows.iter().map(Ok))?,
+                ))),
+            };
+        let mut decoder = ReaderBuilder::new(schema)
+            .with_batch_size(rows.len())
+            .build_decoder()?;
+        decoder.serialize(&rows)?;
+        let record = decoder.flush()?.ok_or(ArrowError::NoRecord)?;
+        Ok(Arc::new(StructArray::from(record)))
+    }
+}
@@ -4,6 +4,21 @@
 A simulation data logger that records the data in the [Apache Arrow] format and
 automatically saves the data into a [Parquet] file (`data.parquet`) at the end of a simulation.
 
+The physical units of the data, if any, are saved in the `units` key of the metadata of the data field.
+The seed of the random number generators of a model is saved in the `seed` key of the metadata of the data schema.
+
+Beside vectors of numbers, the logger records vectors of booleans, nested vectors, tuples of numbers
+and, with the `serde` feature, any type implementing [ArrowSerialize] (see [ArrowData]).
+The data of a vector can be saved with a given shape using [ArrowBuilder::shape].
+
+If a batch size is set with [ArrowBuilder::batch_size], the data is streamed to Parquet files during the simulation,
+one part file per batch in a directory named after the Parquet file, keeping the memory footprint of the logger to a single batch.
+Each part file is complete as soon as it is written, so the data of all the batches written before a crash can be loaded back.
+
+The data saved into a Parquet file can be replayed into another simulation with the [Replay] client
+and analyzed with the [analysis] module.
+A summary of the statistics of the fields of a Parquet file is printed with the `arrow-summary` binary (`clap` feature).
+
 [Apache Arrow]: https://docs.rs/arrow
 [Parquet]: https://docs.rs/parquet
 
@@ -24,6 +39,25 @@ let logging = Arrow::builder(1000)
                        .filename("my_data.parquet")
                        .build();
 ```
+streaming the data to Parquet part files, one part file every 100 time steps
+```
+# use gmt_dos_clients_arrow::Arrow;
+# use gmt_dos_actors::prelude::*;
+
+let logging = Arrow::builder(1_000_000)
+                       .batch_size(100)
+                       .build();
+```
+saving the data to an HDF5 file (`data.h5`) instead, with the `hdf5` feature
+```ignore
+# use gmt_dos_clients_arrow::{Arrow, FileFormat};
+# use gmt_dos_actors::prelude::*;
+
+let logging = Arrow::builder(1000)
+                       .file_format(FileFormat::Hdf5)
+                       .model("wind loads")
+                       .build();
+```
 opting out of saving the data to the Parquet file
 ```
 # use gmt_dos_clients_arrow::Arrow;
@@ -74,14 +108,11 @@ source.add_output().build::<Source>().log(&mut sink).await;
 ```
 */
 
-use apache_arrow::datatypes::ArrowNativeType;
 use apache_arrow::{
-    array::{ArrayData, BufferBuilder, ListArray},
-    buffer::Buffer,
-    datatypes::{DataType, Field, ToByteSlice},
+    array::ArrayRef,
+    datatypes::{ArrowPrimitiveType, DataType},
 };
-use interface::{Data, Read, UniqueIdentifier, Update};
-use std::sync::Arc;
+use interface::{Data, Read, Seed, UniqueIdentifier, Update};
 use std::{
     any::{type_name, Any},
     marker::PhantomData,
@@ -102,9 +133,24 @@ pub enum ArrowError {
     FieldNotFound(String),
     #[error("Parsing field {0} failed")]
     ParseField(String),
+    #[error("field {0} is a list of {1}, expected a list of {2}")]
+    DataType(String, DataType, DataType),
+    #[error("the shape {0:?} does not match the data size {1}")]
+    Shape(Vec<usize>, usize),
     #[cfg(feature = "matio-rs")]
     #[error("failed to save data to mat file")]
     MatFile(#[from] matio_rs::MatioError),
+    #[cfg(feature = "hdf5")]
+    #[error("failed to save data to HDF5 file")]
+    Hdf5(#[from] hdf5::Error),
+    #[cfg(feature = "netcdf")]
+    #[error("failed to save data to NetCDF file")]
+    NetCdf(#[from] netcdf::Error),
+    #[error("invalid attribute {0}")]
+    Attribute(String),
+    #[cfg(feature = "serde")]
+    #[error("failed to serialize the data")]
+    Serialize(#[from] serde_json::Error),
 }
 
 type Result<T> = std::result::Result<T, ArrowError>;
@@ -119,6 +165,12 @@ pub enum FileFormat {
     Parquet,
     #[cfg(feature = "matio-rs")]
     Matlab(MatFormat),
+    /// HDF5 file with a dataset per field (see [Arrow::to_hdf5])
+    #[cfg(feature = "hdf5")]
+    Hdf5,
+    /// NetCDF file with a variable per field (see [Arrow::to_netcdf])
+    #[cfg(feature = "netcdf")]
+    NetCdf,
 }
 impl Default for FileFormat {
     fn default() -> Self {
@@ -143,18 +195,34 @@ impl Default for MatFormat {
 /// Buffers generic interface
 trait BufferObject: Send + Sync {
     fn who(&self) -> String;
+    /// Returns the physical units of the data
+    fn units(&self) -> Option<&'static str>;
     #[allow(dead_code)]
     fn as_any(&self) -> &dyn Any;
     fn as_mut_any(&mut self) -> &mut dyn Any;
-    fn into_list(&mut self, n_step: usize, n: usize, data_type: DataType) -> Result<ListArray>;
+    /// Moves the buffer content into an array of `n_step` rows of `n` elements
+    fn take_array(&mut self, n_step: usize, n: usize, shape: Option<&[usize]>) -> Result<ArrayRef>;
+}
+/// Buffers with the optional shape of their data
+type Buffers = Vec<(Box<dyn BufferObject>, Option<Vec<usize>>)>;
+
+/// Returns the name of the field of the UID `U`, i.e. the type name of `U` without the module paths
+fn who<U>() -> String {
+    type_name::<U>()
+        .split("<")
+        .map(|x| x.split("::").last().unwrap().to_string())
+        .collect::<Vec<_>>()
+        .join("<")
 }
 
 /// Arrow buffer type match to a dos-actors Data type
 struct ArrowBuffer<U: UniqueIdentifier>(PhantomData<U>);
-impl<T: ArrowNativeType, U: UniqueIdentifier<DataType = Vec<T>>> UniqueIdentifier
-    for ArrowBuffer<U>
+impl<U> UniqueIdentifier for ArrowBuffer<U>
+where
+    U: UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: ArrowData,
 {
-    type DataType = BufferBuilder<T>;
+    type DataType = <<U as UniqueIdentifier>::DataType as ArrowData>::Builder;
 }
 struct LogData<U: UniqueIdentifier>(<U as UniqueIdentifier>::DataType, PhantomData<U>);
 impl<U: UniqueIdentifier> Deref for LogData<U> {
@@ -174,17 +242,16 @@ impl<T, U: UniqueIdentifier<DataType = T>> LogData<U> {
         Self(data, PhantomData)
     }
 }
-impl<T, U> BufferObject for LogData<ArrowBuffer<U>>
+impl<U> BufferObject for LogData<ArrowBuffer<U>>
 where
-    T: ArrowNativeType,
-    U: 'static + Send + Sync + UniqueIdentifier<DataType = Vec<T>>,
+    U: 'static + Send + Sync + UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: ArrowData,
 {
     fn who(&self) -> String {
-        type_name::<U>()
-            .split("<")
-            .map(|x| format!("{}", x.split("::").last().unwrap()))
-            .collect::<Vec<_>>()
-            .join("<")
+        who::<U>()
+    }
+    fn units(&self) -> Option<&'static str> {
+        <U as UniqueIdentifier>::UNITS
     }
     fn as_any(&self) -> &dyn Any {
         self
@@ -192,27 +259,14 @@ where
     fn as_mut_any(&mut self) -> &mut dyn Any {
         self
     }
-    fn into_list(&mut self, n_step: usize, n: usize, data_type: DataType) -> Result<ListArray> {
-        let buffer = &mut *self;
-        let data = ArrayData::builder(data_type.clone())
-            .len(buffer.len())
-            .add_buffer(buffer.finish())
-            .build()?;
-        let offsets = (0..).step_by(n).take(n_step + 1).collect::<Vec<i32>>();
-        let list = ArrayData::builder(DataType::List(Arc::new(Field::new(
-            "values", data_type, false,
-        ))))
-        .len(n_step)
-        .add_buffer(Buffer::from(offsets.to_byte_slice()))
-        .add_child_data(data)
-        .build()?;
-        Ok(ListArray::from(list))
+    fn take_array(&mut self, n_step: usize, n: usize, shape: Option<&[usize]>) -> Result<ArrayRef> {
+        <<U as UniqueIdentifier>::DataType as ArrowData>::finish(self, n_step, n, shape)
     }
 }
 
 #[doc(hidden)]
 pub trait BufferDataType {
-    type ArrayType;
+    type ArrayType: ArrowPrimitiveType<Native = Self>;
     fn buffer_data_type() -> DataType;
 }
 use paste::paste;
@@ -251,12 +305,28 @@ enum DropOption {
 
 mod arrow;
 pub use arrow::{Arrow, ArrowBuilder};
+mod data;
+pub use data::ArrowData;
+pub mod analysis;
+mod replay;
+#[cfg(feature = "serde")]
+pub use data::ArrowSerialize;
+pub use replay::Replay;
 
-impl Update for Arrow {}
-impl<T, U> Read<U> for Arrow
+/// The model seed is saved in the `seed` key of the metadata of the data schema
+impl Update for Arrow {
+    fn seed(&mut self, seed: Seed) {
+        self.metadata
+            .get_or_insert_with(Default::default)
+            .insert("seed".to_string(), seed.model().to_string());
+    }
+}
+/// The data is copied into the logger and released before a record batch is saved,
+/// so the writer can recycle the data buffer with a [BufferPool](interface::BufferPool)
+impl<U> Read<U> for Arrow
 where
-    T: ArrowNativeType,
-    U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+    U: 'static + UniqueIdentifier,
+    <U as UniqueIdentifier>::DataType: ArrowData,
 {
     fn read(&mut self, data: Data<U>) {
         let r = 1 + (self.step as f64 / self.n_entry as f64).floor() as usize;
@@ -264,8 +334,9 @@ where
         if r % self.decimation > 0 {
             return;
         }
-        if let Some(buffer) = self.data::<T, U>() {
-            buffer.append_slice(&data);
+        if let Some(buffer) = self.data::<U>() {
+            ArrowData::append(&*data, buffer);
+            drop(data);
             self.count += 1;
             match self.batch_size {
                 Some(batch_size) if self.count % (self.n_entry * batch_size) == 0 => {
@@ -281,8 +352,8 @@ where
 mod tests {
     use std::sync::Arc;
 
-    use apache_arrow::datatypes::Schema;
-    use interface::{Data, Entry, UID};
+    use apache_arrow::datatypes::{Field, Schema};
+    use interface::{Data, Entry, Size, Write, UID};
 
     use super::*;
 
@@ -313,6 +384,34 @@ mod tests {
         assert_eq!(arw.record().unwrap().schema(), schema);
     }
 
+    #[test]
+    fn units() {
+        let mut arw = Arrow::builder(10).build();
+        #[derive(UID)]
+        #[uid(units = "N")]
+        pub enum Force {}
+        <Arrow as Entry<Force>>::entry(&mut arw, 1);
+        let record = arw.record().unwrap();
+        assert_eq!(
+            record.schema().field(0).metadata().get("units"),
+            Some(&"N".to_string())
+        );
+    }
+
+    #[test]
+    fn seed() {
+        let mut arw = Arrow::builder(10).build();
+        #[derive(UID)]
+        pub enum Data {}
+        <Arrow as Entry<Data>>::entry(&mut arw, 1);
+        arw.seed(Seed::new(42).derive("Arrow"));
+        let record = arw.record().unwrap();
+        assert_eq!(
+            record.schema().metadata().get("seed"),
+            Some(&"42".to_string())
+        );
+    }
+
     #[test]
     fn batch() {
         //env_logger::init();
@@ -342,4 +441,309 @@ mod tests {
             arw.read(Data::<V>::new(vec![(10 * i) as f64; 3]));
         }
     }
+
+    #[test]
+    fn streaming() {
+        let path = std::env::temp_dir().join("gmt_dos-clients_arrow-streaming");
+        let n_step = 10;
+        let mut arw = Arrow::builder(n_step)
+            .batch_size(4)
+            .filename(path.to_str().unwrap())
+            .build();
+        #[derive(UID)]
+        pub enum U {}
+        <Arrow as Entry<U>>::entry(&mut arw, 2);
+        for i in 0..n_step {
+            arw.read(Data::<U>::new(vec![i as f64; 2]));
+        }
+        drop(arw);
+
+        let parts: Vec<_> = std::fs::read_dir(path.with_extension("parquet"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(parts.len(), 3);
+
+        let mut arw = Arrow::from_parquet(&path).unwrap();
+        let data: Vec<Vec<f64>> = arw.iter("U").unwrap().collect();
+        assert_eq!(data.last(), Some(&vec![9.; 2]));
+
+        // the logger is never dropped, as if the simulation had been killed
+        let mut arw = Arrow::builder(n_step)
+            .batch_size(4)
+            .filename(path.to_str().unwrap())
+            .build();
+        <Arrow as Entry<U>>::entry(&mut arw, 2);
+        for i in 0..n_step {
+            arw.read(Data::<U>::new(vec![i as f64; 2]));
+        }
+        std::mem::forget(arw);
+        let mut arw = Arrow::from_parquet(&path).unwrap();
+        let data: Vec<Vec<f64>> = arw.iter("U").unwrap().collect();
+        assert_eq!(data.len(), 8);
+    }
+
+    #[test]
+    fn shape() {
+        let mut arw = Arrow::builder(4).shape::<Frame>([2, 3]).no_save().build();
+        #[derive(UID)]
+        pub enum Frame {}
+        <Arrow as Entry<Frame>>::entry(&mut arw, 6);
+        for i in 0..4 {
+            arw.read(Data::<Frame>::new(vec![i as f64; 6]));
+        }
+        let record = arw.record().unwrap();
+        let field = record.schema().field(0).clone();
+        assert_eq!(
+            field.data_type(),
+            &DataType::FixedSizeList(Arc::new(Field::new("values", DataType::Float64, false)), 6)
+        );
+        assert_eq!(field.metadata().get("shape"), Some(&"[2, 3]".to_string()));
+        let data: Vec<Vec<f64>> = arw.iter("Frame").unwrap().collect();
+        assert_eq!(data[3], vec![3.; 6]);
+    }
+
+    #[test]
+    fn nested() {
+        let mut arw = Arrow::builder(4).no_save().build();
+        pub enum Mask {}
+        impl UniqueIdentifier for Mask {
+            type DataType = Vec<bool>;
+        }
+        <Arrow as Entry<Mask>>::entry(&mut arw, 3);
+        pub enum Segments {}
+        impl UniqueIdentifier for Segments {
+            type DataType = Vec<Vec<f64>>;
+        }
+        <Arrow as Entry<Segments>>::entry(&mut arw, 7);
+        pub enum Pair {}
+        impl UniqueIdentifier for Pair {
+            type DataType = (f64, f64);
+        }
+        <Arrow as Entry<Pair>>::entry(&mut arw, 1);
+        for i in 0..4 {
+            arw.read(Data::<Mask>::new(vec![true, false, i % 2 == 0]));
+            arw.read(Data::<Segments>::new(vec![vec![i as f64; 2]; 7]));
+            arw.read(Data::<Pair>::new((i as f64, -(i as f64))));
+        }
+        let record = arw.record().unwrap();
+        assert_eq!(record.num_rows(), 4);
+        let schema = record.schema();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::List(Arc::new(Field::new("values", DataType::Boolean, false)))
+        );
+        assert_eq!(
+            schema.field(1).data_type(),
+            &DataType::List(Arc::new(Field::new(
+                "values",
+                DataType::List(Arc::new(Field::new("values", DataType::Float64, false))),
+                false
+            )))
+        );
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Struct(
+                vec![
+                    Field::new("0", DataType::Float64, false),
+                    Field::new("1", DataType::Float64, false)
+                ]
+                .into()
+            )
+        );
+    }
+
+    #[cfg(feature = "hdf5")]
+    #[test]
+    fn hdf5() {
+        let path = std::env::temp_dir().join("gmt_dos-clients_arrow-hdf5");
+        let mut arw = Arrow::builder(4)
+            .batch_size(3)
+            .shape::<Frame>([2, 3])
+            .file_format(FileFormat::Hdf5)
+            .model("hdf5")
+            .filename(path.to_str().unwrap())
+            .build();
+        #[derive(UID)]
+        #[uid(units = "m")]
+        pub enum Frame {}
+        <Arrow as Entry<Frame>>::entry(&mut arw, 6);
+        for i in 0..4 {
+            arw.read(Data::<Frame>::new(vec![i as f64; 6]));
+        }
+        drop(arw);
+
+        let mut arw = Arrow::from_hdf5(&path).unwrap();
+        let record = arw.record().unwrap();
+        assert_eq!(
+            record.schema().metadata().get("model"),
+            Some(&"hdf5".to_string())
+        );
+        let field = record.schema().field(0).clone();
+        assert_eq!(field.metadata().get("units"), Some(&"m".to_string()));
+        assert_eq!(field.metadata().get("shape"), Some(&"[2, 3]".to_string()));
+        let data: Vec<Vec<f64>> = arw.iter("Frame").unwrap().collect();
+        assert_eq!(data.len(), 4);
+        assert_eq!(data[3], vec![3.; 6]);
+    }
+
+    #[test]
+    fn replay() {
+        let path = std::env::temp_dir().join("gmt_dos-clients_arrow-replay");
+        let mut arw = Arrow::builder(5).filename(path.to_str().unwrap()).build();
+        #[derive(UID)]
+        pub enum U {}
+        <Arrow as Entry<U>>::entry(&mut arw, 2);
+        for i in 0..5 {
+            arw.read(Data::<U>::new(vec![i as f64; 2]));
+        }
+        drop(arw);
+
+        #[derive(UID)]
+        pub enum V {}
+        let mut replay = Replay::from_parquet(&path)
+            .unwrap()
+            .field::<V, _>("U")
+            .unwrap()
+            .decimation(2)
+            .upsampling(2);
+        assert_eq!(replay.n_step(), Some(6));
+        assert_eq!(<Replay as Size<V>>::len(&replay), 2);
+        let mut data = vec![];
+        loop {
+            replay.update();
+            match <Replay as Write<V>>::write(&mut replay) {
+                Some(value) => data.push(value[0]),
+                None => break,
+            }
+        }
+        assert_eq!(data, vec![0., 0., 2., 2., 4., 4.]);
+
+        assert!(matches!(
+            Replay::from_parquet(&path).unwrap().field::<V, _>("V"),
+            Err(ArrowError::FieldNotFound(_))
+        ));
+        #[derive(UID)]
+        #[uid(data = Vec<f32>)]
+        pub enum W {}
+        assert!(matches!(
+            Replay::from_parquet(&path).unwrap().field::<W, _>("U"),
+            Err(ArrowError::DataType(..))
+        ));
+
+        let mut replay = Replay::from_parquet(&path)
+            .unwrap()
+            .output::<U, _>()
+            .unwrap()
+            .looping();
+        let data: Vec<f64> = (0..7)
+            .filter_map(|_| {
+                replay.update();
+                <Replay as Write<U>>::write(&mut replay).map(|value| value[0])
+            })
+            .collect();
+        assert_eq!(data, vec![0., 1., 2., 3., 4., 0., 1.]);
+
+        // the decimated replay loops back to the first row
+        let mut replay = Replay::from_parquet(&path)
+            .unwrap()
+            .output::<U, _>()
+            .unwrap()
+            .decimation(2)
+            .looping();
+        let data: Vec<f64> = (0..7)
+            .filter_map(|_| {
+                replay.update();
+                <Replay as Write<U>>::write(&mut replay).map(|value| value[0])
+            })
+            .collect();
+        assert_eq!(data, vec![0., 2., 4., 0., 2., 4., 0.]);
+    }
+
+    #[test]
+    fn analysis() {
+        let path = std::env::temp_dir().join("gmt_dos-clients_arrow-analysis");
+        let mut arw = Arrow::builder(101).filename(path.to_str().unwrap()).build();
+        #[derive(UID)]
+        pub enum U {}
+        <Arrow as Entry<U>>::entry(&mut arw, 2);
+        for i in 0..=100 {
+            arw.read(Data::<U>::new(vec![i as f64, -1.]));
+        }
+        drop(arw);
+
+        let run = analysis::Run::open(&path).unwrap().sampling_frequency(10.);
+        assert_eq!(run.names(), vec!["U"]);
+        let series = run.series::<U>().unwrap();
+        assert_eq!(series.mean(), vec![50., -1.]);
+        assert_eq!(series.peak_to_valley(), vec![100., 0.]);
+        assert_eq!(series.percentile(25.), vec![25., -1.]);
+        let window = series.clone().time_window(1., 2.).unwrap();
+        assert_eq!(
+            window.channel(0),
+            (10..20).map(|i| i as f64).collect::<Vec<_>>()
+        );
+        let decimated = series.window(..10).decimate(3);
+        assert_eq!(decimated.channel(0), vec![0., 3., 6., 9.]);
+        assert_eq!(run.get::<U, f64>().unwrap()[100], vec![100., -1.]);
+    }
+
+    #[test]
+    fn psd() {
+        let (fs, f0, a) = (1e3, 125., 2.);
+        let series = analysis::Series::new(
+            "U",
+            (0..8192)
+                .map(|i| vec![a * (2. * std::f64::consts::PI * f0 * i as f64 / fs).sin()])
+                .collect(),
+        )
+        .sampling_frequency(fs);
+        let psd = series.psd(256).unwrap();
+        let (i, _) = psd.values()[0]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(psd.frequencies()[i], f0);
+        let variance = *psd.cumulative().values()[0].last().unwrap();
+        assert!((variance - a * a / 2.).abs() < 1e-3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        use std::collections::HashMap;
+
+        #[derive(serde::Serialize)]
+        pub struct Pose {
+            angle: Option<f64>,
+            tags: HashMap<(u8, u8), f64>,
+        }
+        impl ArrowSerialize for Pose {}
+        #[derive(UID)]
+        #[uid(data = Pose)]
+        pub enum P {}
+
+        let mut arw = Arrow::builder(4).no_save().build();
+        <Arrow as Entry<P>>::entry(&mut arw, 1);
+        for angle in [Some(0.), None, Some(2.)] {
+            arw.read(Data::<P>::new(Pose {
+                angle,
+                tags: HashMap::new(),
+            }));
+            if angle.is_none() {
+                arw.batch().unwrap();
+            }
+        }
+        let batches = arw.batch().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].schema(), batches[1].schema());
+
+        // map keys must be strings
+        arw.read(Data::<P>::new(Pose {
+            angle: None,
+            tags: HashMap::from([((0, 1), 1.)]),
+        }));
+        assert!(matches!(arw.record(), Err(ArrowError::Serialize(_))));
+    }
 }
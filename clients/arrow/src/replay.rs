@@ -0,0 +1,198 @@
+use std::{any::TypeId, collections::HashMap, path::Path};
+
+use apache_arrow::{
+    array::{Array, AsArray},
+    datatypes::{ArrowNativeType, DataType},
+    record_batch::RecordBatch,
+};
+use interface::{Data, Size, TimerMarker, UniqueIdentifier, Update, Write};
+
+use crate::{who, Arrow, ArrowError, BufferDataType, Result};
+
+/// Replay of the data logged into a [Parquet](https://docs.rs/parquet) file
+///
+/// The data of a field of the file is written to an output, one row per time step.
+/// The outputs are declared with [Replay::output], replaying the field with the type name of the output UID
+/// as given by the [Arrow] logger, or with [Replay::field], replaying the field of the given name.
+/// Both check that the field exists and that its data type matches the data type of the UID.
+/// The data of the fields can be decimated or upsampled and the replay can loop back to the beginning of the data
+/// instead of ending after the last row.
+///
+/// ```no_run
+/// use gmt_dos_clients_arrow::Replay;
+/// use interface::UID;
+///
+/// #[derive(UID)]
+/// pub enum WindLoads {}
+///
+/// let replay = Replay::from_parquet("data")?
+///     .field::<WindLoads, _>("CFDM1WindLoads")?
+///     .upsampling(2)
+///     .looping();
+/// # Ok::<(), gmt_dos_clients_arrow::ArrowError>(())
+/// ```
+pub struct Replay {
+    record: RecordBatch,
+    fields: HashMap<TypeId, String>,
+    decimation: usize,
+    upsampling: usize,
+    looping: bool,
+    step: usize,
+    row: Option<usize>,
+}
+
+impl Replay {
+    /// Loads the data from a [Parquet](https://docs.rs/parquet) data file
+    ///
+    /// The file is loaded with [Arrow::from_parquet]
+    pub fn from_parquet<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut data = Arrow::from_parquet(path)?;
+        Ok(Self::from(data.record()?.clone()))
+    }
+    /// Replays the field `name` to the output UID `U`
+    ///
+    /// Returns an error if the field does not exist or if its data type does not match the data type of `U`
+    pub fn field<U, T>(mut self, name: impl Into<String>) -> Result<Self>
+    where
+        T: BufferDataType,
+        U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+    {
+        let name = name.into();
+        let column = self
+            .record
+            .column_by_name(&name)
+            .ok_or_else(|| ArrowError::FieldNotFound(name.clone()))?;
+        let data_type = match column.data_type() {
+            DataType::List(field) | DataType::FixedSizeList(field, _) => field.data_type(),
+            _ => return Err(ArrowError::ParseField(name)),
+        };
+        if *data_type != T::buffer_data_type() {
+            return Err(ArrowError::DataType(
+                name,
+                data_type.clone(),
+                T::buffer_data_type(),
+            ));
+        }
+        self.fields.insert(TypeId::of::<U>(), name);
+        Ok(self)
+    }
+    /// Replays the field with the type name of the output UID `U`
+    ///
+    /// See [Replay::field]
+    pub fn output<U, T>(self) -> Result<Self>
+    where
+        T: BufferDataType,
+        U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+    {
+        self.field::<U, T>(who::<U>())
+    }
+    /// Replays every `decimation` row of the data
+    pub fn decimation(mut self, decimation: usize) -> Self {
+        self.decimation = decimation.max(1);
+        self
+    }
+    /// Replays each row of the data `upsampling` times
+    pub fn upsampling(mut self, upsampling: usize) -> Self {
+        self.upsampling = upsampling.max(1);
+        self
+    }
+    /// Loops back to the first row of the data after the last one
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+    /// Returns the number of time steps of the replay, [None] if it is looping
+    pub fn n_step(&self) -> Option<usize> {
+        (!self.looping).then(|| self.upsampling * self.record.num_rows().div_ceil(self.decimation))
+    }
+    fn value<T, U>(&self, row: usize) -> Result<Vec<T>>
+    where
+        T: BufferDataType + ArrowNativeType,
+        U: 'static,
+    {
+        let column = self
+            .fields
+            .get(&TypeId::of::<U>())
+            .and_then(|name| self.record.column_by_name(name))
+            .ok_or_else(|| ArrowError::FieldNotFound(who::<U>()))?;
+        if row >= column.len() {
+            return Err(ArrowError::NoRecord);
+        }
+        let value = if let Some(list) = column.as_list_opt::<i32>() {
+            list.value(row)
+        } else if let Some(list) = column.as_fixed_size_list_opt() {
+            list.value(row)
+        } else {
+            return Err(ArrowError::ParseField(who::<U>()));
+        };
+        value
+            .as_primitive_opt::<T::ArrayType>()
+            .map(|value| value.values().to_vec())
+            .ok_or_else(|| ArrowError::ParseField(who::<U>()))
+    }
+}
+
+impl From<RecordBatch> for Replay {
+    /// Creates a replay of the data in the record
+    fn from(record: RecordBatch) -> Self {
+        Self {
+            record,
+            fields: HashMap::new(),
+            decimation: 1,
+            upsampling: 1,
+            looping: false,
+            step: 0,
+            row: None,
+        }
+    }
+}
+
+impl TimerMarker for Replay {}
+impl Update for Replay {
+    fn update(&mut self) {
+        let n_row = self.record.num_rows();
+        let k = self.step / self.upsampling;
+        self.row = if self.looping && n_row > 0 {
+            // the replay loops back to the first row after the last decimated row
+            Some((k % n_row.div_ceil(self.decimation)) * self.decimation)
+        } else {
+            let row = k * self.decimation;
+            (row < n_row).then_some(row)
+        };
+        self.step += 1;
+    }
+}
+
+/// The output UID `U` must be declared with [Replay::output] or [Replay::field]
+impl<T, U> Write<U> for Replay
+where
+    T: BufferDataType + ArrowNativeType,
+    U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+{
+    fn write(&mut self) -> Option<Data<U>> {
+        let row = self.row?;
+        assert!(
+            self.fields.contains_key(&TypeId::of::<U>()),
+            "{} is not a replay output, declare it with Replay::output or Replay::field",
+            who::<U>()
+        );
+        match self.value::<T, U>(row) {
+            Ok(value) => Some(Data::new(value)),
+            Err(e) => {
+                log::error!("Replay failed to write {}: {e}", who::<U>());
+                None
+            }
+        }
+    }
+}
+
+/// The size of the data is the number of elements of the first row of the field
+impl<T, U> Size<U> for Replay
+where
+    T: BufferDataType + ArrowNativeType,
+    U: 'static + UniqueIdentifier<DataType = Vec<T>>,
+{
+    fn len(&self) -> usize {
+        self.value::<T, U>(0).map_or(0, |value| value.len())
+    }
+}
@@ -0,0 +1,164 @@
+//! # Units conversion
+//!
+//! Converts the data of an input into the [units](interface::UniqueIdentifier::UNITS) of an output
+//!
+//! A [Convert] actor is the explicit conversion actor between 2 UIDs with different units
+//! that `Model::check` requires.
+//!
+//! ```
+//! use gmt_dos_clients::convert::Convert;
+//! use interface::{Data, Read, Update, Write, UID};
+//!
+//! #[derive(UID)]
+//! #[uid(units = "m")]
+//! enum Meters {}
+//! #[derive(UID)]
+//! #[uid(units = "nm")]
+//! enum Nanometers {}
+//!
+//! let mut convert = Convert::<f64, Meters, Nanometers>::new()?;
+//! <Convert<f64, Meters, Nanometers> as Read<Meters>>::read(&mut convert, Data::new(vec![1e-9, 2e-9]));
+//! convert.update();
+//! let data = <Convert<f64, Meters, Nanometers> as Write<Nanometers>>::write(&mut convert).unwrap();
+//! assert!(data.iter().zip([1., 2.]).all(|(x, y)| (x - y).abs() < 1e-12));
+//! # Ok::<(), gmt_dos_clients::convert::ConvertError>(())
+//! ```
+
+use std::{any::type_name, marker::PhantomData, ops::Mul, sync::Arc};
+
+use interface::{units::scale, Data, Read, UniqueIdentifier, Update, Write};
+use num_traits::AsPrimitive;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConvertError {
+    #[error("cannot convert {0} ({1}) into {2} ({3})")]
+    Units(String, String, String, String),
+    #[error("the units of {0} and {1} must be both defined")]
+    Undefined(String, String),
+}
+
+/// Units conversion from `U` to `V`
+#[derive(Debug)]
+pub struct Convert<T, U, V>
+where
+    U: UniqueIdentifier<DataType = Vec<T>>,
+    V: UniqueIdentifier<DataType = Vec<T>>,
+{
+    scale: T,
+    data: Arc<Vec<T>>,
+    uid: PhantomData<(U, V)>,
+}
+
+impl<T, U, V> Convert<T, U, V>
+where
+    T: Copy + 'static,
+    f64: AsPrimitive<T>,
+    U: UniqueIdentifier<DataType = Vec<T>>,
+    V: UniqueIdentifier<DataType = Vec<T>>,
+{
+    /// Creates a new units converter
+    ///
+    /// Returns an error if the units of `U` cannot be converted into the units of `V`
+    pub fn new() -> Result<Self, ConvertError> {
+        let s = match (U::UNITS, V::UNITS) {
+            (Some(from), Some(to)) => scale(from, to).ok_or_else(|| {
+                ConvertError::Units(
+                    type_name::<U>().into(),
+                    from.into(),
+                    type_name::<V>().into(),
+                    to.into(),
+                )
+            })?,
+            _ => {
+                return Err(ConvertError::Undefined(
+                    type_name::<U>().into(),
+                    type_name::<V>().into(),
+                ))
+            }
+        };
+        Ok(Self::with_scale(s))
+    }
+    /// Creates a new units converter with a given scale factor
+    pub fn with_scale(scale: f64) -> Self {
+        Self {
+            scale: scale.as_(),
+            data: Default::default(),
+            uid: PhantomData,
+        }
+    }
+}
+
+impl<T, U, V> Update for Convert<T, U, V>
+where
+    T: Send + Sync,
+    U: UniqueIdentifier<DataType = Vec<T>>,
+    V: UniqueIdentifier<DataType = Vec<T>>,
+{
+}
+
+impl<T, U, V> Read<U> for Convert<T, U, V>
+where
+    T: Send + Sync,
+    U: UniqueIdentifier<DataType = Vec<T>>,
+    V: UniqueIdentifier<DataType = Vec<T>>,
+{
+    fn read(&mut self, data: Data<U>) {
+        self.data = data.into_arc();
+    }
+}
+
+impl<T, U, V> Write<V> for Convert<T, U, V>
+where
+    T: Copy + Mul<Output = T> + Send + Sync,
+    U: UniqueIdentifier<DataType = Vec<T>>,
+    V: UniqueIdentifier<DataType = Vec<T>>,
+{
+    fn write(&mut self) -> Option<Data<V>> {
+        Some(Data::new(
+            self.data.iter().map(|&x| x * self.scale).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use interface::UID;
+
+    #[derive(UID)]
+    #[uid(data = Vec<f32>, units = "m")]
+    enum Meters {}
+    #[derive(UID)]
+    #[uid(data = Vec<f32>, units = "mm")]
+    enum Millimeters {}
+    #[derive(UID)]
+    #[uid(data = Vec<f32>, units = "s")]
+    enum Seconds {}
+    #[derive(UID)]
+    #[uid(data = Vec<f32>)]
+    enum Unitless {}
+
+    #[test]
+    fn single_precision() {
+        let mut convert = Convert::<f32, Meters, Millimeters>::new().unwrap();
+        <Convert<f32, Meters, Millimeters> as Read<Meters>>::read(
+            &mut convert,
+            Data::new(vec![1e-3, 2e-3]),
+        );
+        let data =
+            <Convert<f32, Meters, Millimeters> as Write<Millimeters>>::write(&mut convert).unwrap();
+        assert!(data.iter().zip([1., 2.]).all(|(x, y)| (x - y).abs() < 1e-6));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Convert::<f32, Meters, Seconds>::new(),
+            Err(ConvertError::Units(..))
+        ));
+        assert!(matches!(
+            Convert::<f32, Meters, Unitless>::new(),
+            Err(ConvertError::Undefined(..))
+        ));
+    }
+}
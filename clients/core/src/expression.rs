@@ -0,0 +1,654 @@
+//! # Expression client
+//!
+//! An [Expression] client evaluates an expression tree [Expr] over the data of its inputs
+//! and writes the result to its output.
+//!
+//! The leaves of the tree are the inputs, given by their UIDs, and constants,
+//! the nodes are element-wise additions, subtractions, multiplications and divisions,
+//! negations, concatenations, slicing with ranges named by the [Expression] client, reshapes and transpositions.
+//! A scalar or a single element vector is broadcasted to the length of the other operand
+//! of an element-wise operation.
+//! The tree is made of data only, so an [Expression] can be serialized with the `serde` feature.
+//!
+//! The expression is validated when the client is built: the ranges must be defined and,
+//! for the inputs which length is given to the [ExpressionBuilder], the dimensions of the operands must match.
+//! An input that has not been read yet, or an operation on vectors which dimensions do not match,
+//! is an error when the expression is evaluated; the client then stops writing its output
+//! and the error is given by [Expression::error].
+//!
+//! The client reads any input and writes any output which data type is `Vec<f64>`,
+//! so it can be used in `actorscript!` models like any other client.
+//!
+//! # Example
+//! ```
+//! use gmt_dos_clients::expression::{Expr, Expression};
+//! use interface::{Read, Update, Write, UID};
+//!
+//! #[derive(UID)]
+//! enum A {}
+//! #[derive(UID)]
+//! enum B {}
+//! #[derive(UID)]
+//! enum C {}
+//! #[derive(UID)]
+//! enum Y {}
+//!
+//! // y = [2a + b[tip] / c, -a]
+//! let expr = Expr::concat([
+//!     2. * Expr::input::<A>() + Expr::input::<B>().slice("tip") / Expr::input::<C>(),
+//!     -Expr::input::<A>(),
+//! ]);
+//! let mut client = Expression::builder(expr)
+//!     .range("tip", 1..3)
+//!     .size::<A>(2)
+//!     .size::<C>(1)
+//!     .build()?;
+//!
+//! <Expression as Read<A>>::read(&mut client, vec![1., 2.].into());
+//! <Expression as Read<B>>::read(&mut client, vec![0., 3., 4.].into());
+//! <Expression as Read<C>>::read(&mut client, vec![2.].into());
+//! client.update();
+//! let y = <Expression as Write<Y>>::write(&mut client).unwrap();
+//! assert_eq!(*y, vec![3.5, 6., -1., -2.]);
+//! # Ok::<(), gmt_dos_clients::expression::ExpressionError>(())
+//! ```
+
+use interface::{Data, Quote, Read, UniqueIdentifier, Update, Write};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Range, Sub},
+    sync::Arc,
+};
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExpressionError {
+    #[error("the input {0} has not been read")]
+    Unset(String),
+    #[error("the input {0} is not in the expression")]
+    UnknownInput(String),
+    #[error("cannot apply {0} to vectors of length {1} and {2}")]
+    Dimensions(char, usize, usize),
+    #[error("the range {0} is not defined")]
+    UnknownRange(String),
+    #[error("the range {0} ({1:?}) is out of the bounds of a vector of length {2}")]
+    OutOfBounds(String, Range<usize>, usize),
+    #[error("cannot transpose a vector of length {0} with {1} rows")]
+    Transpose(usize, usize),
+    #[error("cannot reshape a vector of length {0} into a {1}x{2} matrix")]
+    Reshape(usize, usize, usize),
+}
+pub type Result<T> = std::result::Result<T, ExpressionError>;
+
+/// Expression tree
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr {
+    /// Input data given by the name of the input UID
+    Input(String),
+    /// Scalar constant
+    Scalar(f64),
+    /// Vector constant
+    Vector(Vec<f64>),
+    /// Element-wise addition
+    Add(Box<Expr>, Box<Expr>),
+    /// Element-wise subtraction
+    Sub(Box<Expr>, Box<Expr>),
+    /// Element-wise multiplication
+    Mul(Box<Expr>, Box<Expr>),
+    /// Element-wise division
+    Div(Box<Expr>, Box<Expr>),
+    /// Negation
+    Neg(Box<Expr>),
+    /// Concatenation
+    Concat(Vec<Expr>),
+    /// Slice given by the name of a range of the [Expression] client
+    Slice(Box<Expr>, String),
+    /// Transposition of a matrix with the given number of rows
+    ///
+    /// The matrix is stored in column-major order, the result is the transposed matrix
+    /// with `len/rows` rows, stored in column-major order as well
+    Transpose(Box<Expr>, usize),
+    /// Reshape into a matrix with the given numbers of rows and columns
+    ///
+    /// The matrices are stored in column-major order, so the data is unchanged
+    Reshape(Box<Expr>, usize, usize),
+}
+
+impl Expr {
+    /// Creates the leaf of the input `U`
+    pub fn input<U: UniqueIdentifier>() -> Self {
+        Self::Input(<U as Quote>::quote())
+    }
+    /// Creates the weighted sum of expressions
+    pub fn weighted_sum(terms: impl IntoIterator<Item = (f64, Expr)>) -> Self {
+        terms
+            .into_iter()
+            .map(|(weight, expr)| weight * expr)
+            .reduce(|sum, expr| sum + expr)
+            .unwrap_or(Expr::Scalar(0.))
+    }
+    /// Concatenates expressions
+    pub fn concat(exprs: impl IntoIterator<Item = Expr>) -> Self {
+        Self::Concat(exprs.into_iter().collect())
+    }
+    /// Slices the expression with the range `name` of the [Expression] client
+    pub fn slice<S: Into<String>>(self, name: S) -> Self {
+        Self::Slice(Box::new(self), name.into())
+    }
+    /// Transposes the expression, a matrix with `rows` rows
+    pub fn transpose(self, rows: usize) -> Self {
+        Self::Transpose(Box::new(self), rows)
+    }
+    /// Reshapes the expression into a matrix with `rows` rows and `cols` columns
+    pub fn reshape(self, rows: usize, cols: usize) -> Self {
+        Self::Reshape(Box::new(self), rows, cols)
+    }
+    /// Returns the names of the inputs
+    pub fn inputs(&self) -> Vec<&str> {
+        match self {
+            Expr::Input(name) => vec![name.as_str()],
+            Expr::Scalar(_) | Expr::Vector(_) => vec![],
+            Expr::Add(left, right)
+            | Expr::Sub(left, right)
+            | Expr::Mul(left, right)
+            | Expr::Div(left, right) => {
+                let mut inputs = left.inputs();
+                inputs.extend(right.inputs());
+                inputs
+            }
+            Expr::Neg(expr)
+            | Expr::Slice(expr, _)
+            | Expr::Transpose(expr, _)
+            | Expr::Reshape(expr, _, _) => expr.inputs(),
+            Expr::Concat(exprs) => exprs.iter().flat_map(|expr| expr.inputs()).collect(),
+        }
+    }
+    /// Validates the expression, returning the length of the result if it is known
+    ///
+    /// The length of an input is known if it is in `sizes`
+    fn validate(
+        &self,
+        sizes: &HashMap<String, usize>,
+        ranges: &HashMap<String, Range<usize>>,
+    ) -> Result<Option<usize>> {
+        Ok(match self {
+            Expr::Input(name) => sizes.get(name).copied(),
+            Expr::Scalar(_) => Some(1),
+            Expr::Vector(value) => Some(value.len()),
+            Expr::Add(left, right) => broadcast(
+                '+',
+                left.validate(sizes, ranges)?,
+                right.validate(sizes, ranges)?,
+            )?,
+            Expr::Sub(left, right) => broadcast(
+                '-',
+                left.validate(sizes, ranges)?,
+                right.validate(sizes, ranges)?,
+            )?,
+            Expr::Mul(left, right) => broadcast(
+                '*',
+                left.validate(sizes, ranges)?,
+                right.validate(sizes, ranges)?,
+            )?,
+            Expr::Div(left, right) => broadcast(
+                '/',
+                left.validate(sizes, ranges)?,
+                right.validate(sizes, ranges)?,
+            )?,
+            Expr::Neg(expr) => expr.validate(sizes, ranges)?,
+            Expr::Concat(exprs) => exprs
+                .iter()
+                .map(|expr| expr.validate(sizes, ranges))
+                .collect::<Result<Option<Vec<_>>>>()?
+                .map(|lens| lens.into_iter().sum()),
+            Expr::Slice(expr, name) => {
+                let range = ranges
+                    .get(name)
+                    .ok_or_else(|| ExpressionError::UnknownRange(name.clone()))?;
+                match expr.validate(sizes, ranges)? {
+                    Some(n) if range.start > range.end || range.end > n => {
+                        return Err(ExpressionError::OutOfBounds(name.clone(), range.clone(), n))
+                    }
+                    _ => Some(range.len()),
+                }
+            }
+            Expr::Transpose(expr, rows) => {
+                let n = expr.validate(sizes, ranges)?;
+                if *rows == 0 || n.is_some_and(|n| n % rows > 0) {
+                    return Err(ExpressionError::Transpose(n.unwrap_or_default(), *rows));
+                }
+                n
+            }
+            Expr::Reshape(expr, rows, cols) => match expr.validate(sizes, ranges)? {
+                Some(n) if n != rows * cols => {
+                    return Err(ExpressionError::Reshape(n, *rows, *cols))
+                }
+                _ => Some(rows * cols),
+            },
+        })
+    }
+    fn evaluate(
+        &self,
+        inputs: &HashMap<String, Arc<Vec<f64>>>,
+        ranges: &HashMap<String, Range<usize>>,
+    ) -> Result<Vec<f64>> {
+        Ok(match self {
+            Expr::Input(name) => inputs
+                .get(name)
+                .map(|data| data.to_vec())
+                .ok_or_else(|| ExpressionError::Unset(name.clone()))?,
+            Expr::Scalar(value) => vec![*value],
+            Expr::Vector(value) => value.clone(),
+            Expr::Add(left, right) => elementwise(
+                '+',
+                left.evaluate(inputs, ranges)?,
+                right.evaluate(inputs, ranges)?,
+                |l, r| l + r,
+            )?,
+            Expr::Sub(left, right) => elementwise(
+                '-',
+                left.evaluate(inputs, ranges)?,
+                right.evaluate(inputs, ranges)?,
+                |l, r| l - r,
+            )?,
+            Expr::Mul(left, right) => elementwise(
+                '*',
+                left.evaluate(inputs, ranges)?,
+                right.evaluate(inputs, ranges)?,
+                |l, r| l * r,
+            )?,
+            Expr::Div(left, right) => elementwise(
+                '/',
+                left.evaluate(inputs, ranges)?,
+                right.evaluate(inputs, ranges)?,
+                |l, r| l / r,
+            )?,
+            Expr::Neg(expr) => expr
+                .evaluate(inputs, ranges)?
+                .into_iter()
+                .map(|x| -x)
+                .collect(),
+            Expr::Concat(exprs) => {
+                let mut value = vec![];
+                for expr in exprs {
+                    value.extend(expr.evaluate(inputs, ranges)?);
+                }
+                value
+            }
+            Expr::Slice(expr, name) => {
+                let range = ranges
+                    .get(name)
+                    .ok_or_else(|| ExpressionError::UnknownRange(name.clone()))?;
+                let value = expr.evaluate(inputs, ranges)?;
+                value
+                    .get(range.clone())
+                    .ok_or_else(|| {
+                        ExpressionError::OutOfBounds(name.clone(), range.clone(), value.len())
+                    })?
+                    .to_vec()
+            }
+            Expr::Transpose(expr, rows) => {
+                let value = expr.evaluate(inputs, ranges)?;
+                if *rows == 0 || value.len() % rows > 0 {
+                    return Err(ExpressionError::Transpose(value.len(), *rows));
+                }
+                let cols = value.len() / rows;
+                (0..rows * cols)
+                    .map(|k| value[(k % cols) * rows + k / cols])
+                    .collect()
+            }
+            Expr::Reshape(expr, rows, cols) => {
+                let value = expr.evaluate(inputs, ranges)?;
+                if value.len() != rows * cols {
+                    return Err(ExpressionError::Reshape(value.len(), *rows, *cols));
+                }
+                value
+            }
+        })
+    }
+}
+
+fn elementwise(
+    op: char,
+    left: Vec<f64>,
+    right: Vec<f64>,
+    f: impl Fn(f64, f64) -> f64,
+) -> Result<Vec<f64>> {
+    match (left.len(), right.len()) {
+        (n, m) if n == m => Ok(left.into_iter().zip(right).map(|(l, r)| f(l, r)).collect()),
+        (1, _) => Ok(right.into_iter().map(|r| f(left[0], r)).collect()),
+        (_, 1) => Ok(left.into_iter().map(|l| f(l, right[0])).collect()),
+        (n, m) => Err(ExpressionError::Dimensions(op, n, m)),
+    }
+}
+
+/// Returns the length of the result of an element-wise operation, if it is known
+fn broadcast(op: char, left: Option<usize>, right: Option<usize>) -> Result<Option<usize>> {
+    match (left, right) {
+        (Some(n), Some(m)) if n == m || m == 1 => Ok(Some(n)),
+        (Some(1), m) => Ok(m),
+        (Some(n), Some(m)) => Err(ExpressionError::Dimensions(op, n, m)),
+        (n, Some(1)) => Ok(n),
+        (Some(n), None) | (None, Some(n)) => Ok(Some(n)),
+        (None, None) => Ok(None),
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Input(name) => write!(f, "{name}"),
+            Expr::Scalar(value) => write!(f, "{value}"),
+            Expr::Vector(value) => write!(f, "{value:?}"),
+            Expr::Add(left, right) => write!(f, "({left} + {right})"),
+            Expr::Sub(left, right) => write!(f, "({left} - {right})"),
+            Expr::Mul(left, right) => write!(f, "{left} * {right}"),
+            Expr::Div(left, right) => write!(f, "{left} / {right}"),
+            Expr::Neg(expr) => write!(f, "-{expr}"),
+            Expr::Concat(exprs) => write!(
+                f,
+                "concat({})",
+                exprs
+                    .iter()
+                    .map(|expr| expr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Expr::Slice(expr, name) => write!(f, "{expr}[{name}]"),
+            Expr::Transpose(expr, rows) => write!(f, "transpose({expr}, {rows})"),
+            Expr::Reshape(expr, rows, cols) => write!(f, "reshape({expr}, {rows}, {cols})"),
+        }
+    }
+}
+
+impl From<f64> for Expr {
+    fn from(value: f64) -> Self {
+        Expr::Scalar(value)
+    }
+}
+impl From<Vec<f64>> for Expr {
+    fn from(value: Vec<f64>) -> Self {
+        Expr::Vector(value)
+    }
+}
+
+impl<R: Into<Expr>> Add<R> for Expr {
+    type Output = Expr;
+
+    fn add(self, rhs: R) -> Self::Output {
+        Expr::Add(Box::new(self), Box::new(rhs.into()))
+    }
+}
+impl<R: Into<Expr>> Sub<R> for Expr {
+    type Output = Expr;
+
+    fn sub(self, rhs: R) -> Self::Output {
+        Expr::Sub(Box::new(self), Box::new(rhs.into()))
+    }
+}
+impl<R: Into<Expr>> Mul<R> for Expr {
+    type Output = Expr;
+
+    fn mul(self, rhs: R) -> Self::Output {
+        Expr::Mul(Box::new(self), Box::new(rhs.into()))
+    }
+}
+impl<R: Into<Expr>> Div<R> for Expr {
+    type Output = Expr;
+
+    fn div(self, rhs: R) -> Self::Output {
+        Expr::Div(Box::new(self), Box::new(rhs.into()))
+    }
+}
+impl Mul<Expr> for f64 {
+    type Output = Expr;
+
+    fn mul(self, rhs: Expr) -> Self::Output {
+        Expr::Mul(Box::new(self.into()), Box::new(rhs))
+    }
+}
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Self::Output {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+/// [Expression] client builder
+#[derive(Debug, Clone)]
+pub struct ExpressionBuilder {
+    expr: Expr,
+    ranges: HashMap<String, Range<usize>>,
+    sizes: HashMap<String, usize>,
+}
+impl ExpressionBuilder {
+    /// Names a range used to [slice](Expr::slice) the expression
+    pub fn range<S: Into<String>>(mut self, name: S, range: Range<usize>) -> Self {
+        self.ranges.insert(name.into(), range);
+        self
+    }
+    /// Sets the length of the input `U`, the dimensions of the expression are checked against it
+    pub fn size<U: UniqueIdentifier>(mut self, len: usize) -> Self {
+        self.sizes.insert(<U as Quote>::quote(), len);
+        self
+    }
+    /// Validates the expression and builds the client
+    pub fn build(self) -> Result<Expression> {
+        let inputs = self.expr.inputs();
+        if let Some(name) = self
+            .sizes
+            .keys()
+            .find(|name| !inputs.contains(&name.as_str()))
+        {
+            return Err(ExpressionError::UnknownInput(name.clone()));
+        }
+        self.expr.validate(&self.sizes, &self.ranges)?;
+        Ok(Expression {
+            expr: self.expr,
+            ranges: self.ranges,
+            inputs: HashMap::new(),
+            output: Default::default(),
+            error: None,
+        })
+    }
+}
+
+/// Expression client
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expression {
+    expr: Expr,
+    ranges: HashMap<String, Range<usize>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    inputs: HashMap<String, Arc<Vec<f64>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output: Arc<Vec<f64>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    error: Option<ExpressionError>,
+}
+
+impl Expression {
+    /// Creates a new expression client, the expression is validated
+    pub fn new(expr: Expr) -> Result<Self> {
+        Self::builder(expr).build()
+    }
+    /// Creates a new expression client builder
+    pub fn builder(expr: Expr) -> ExpressionBuilder {
+        ExpressionBuilder {
+            expr,
+            ranges: HashMap::new(),
+            sizes: HashMap::new(),
+        }
+    }
+    /// Returns the expression tree
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+    /// Evaluates the expression with the last data read from the inputs
+    pub fn evaluate(&self) -> Result<Vec<f64>> {
+        self.expr.evaluate(&self.inputs, &self.ranges)
+    }
+    /// Returns the error of the evaluation that stopped the client, if any
+    pub fn error(&self) -> Option<&ExpressionError> {
+        self.error.as_ref()
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.expr.fmt(f)
+    }
+}
+
+impl Update for Expression {
+    fn update(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.evaluate() {
+            Ok(value) => self.output = Arc::new(value),
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+impl<U: UniqueIdentifier<DataType = Vec<f64>>> Read<U> for Expression {
+    fn read(&mut self, data: Data<U>) {
+        self.inputs.insert(<U as Quote>::quote(), data.into_arc());
+    }
+}
+
+impl<U: UniqueIdentifier<DataType = Vec<f64>>> Write<U> for Expression {
+    fn write(&mut self) -> Option<Data<U>> {
+        self.error.is_none().then(|| self.output.clone().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum A {}
+    impl UniqueIdentifier for A {
+        type DataType = Vec<f64>;
+    }
+    enum B {}
+    impl UniqueIdentifier for B {
+        type DataType = Vec<f64>;
+    }
+    enum C {}
+    impl UniqueIdentifier for C {
+        type DataType = Vec<f64>;
+    }
+
+    #[test]
+    fn weighted_sum() {
+        let expr = Expr::weighted_sum([
+            (0.5, Expr::input::<A>()),
+            (2., Expr::input::<B>()),
+            (-1., Expr::input::<C>()),
+        ]);
+        assert_eq!(expr.inputs(), vec!["A", "B", "C"]);
+        let mut client = Expression::new(expr).unwrap();
+        <Expression as Read<A>>::read(&mut client, vec![2., 4.].into());
+        <Expression as Read<B>>::read(&mut client, vec![1., -1.].into());
+        assert!(matches!(
+            client.evaluate(),
+            Err(ExpressionError::Unset(name)) if name == "C"
+        ));
+        <Expression as Read<C>>::read(&mut client, vec![0., 0.].into());
+        client.update();
+        let y = <Expression as Write<A>>::write(&mut client).unwrap();
+        assert_eq!(*y, vec![3., 0.]);
+        <Expression as Read<C>>::read(&mut client, vec![1., 2., 3.].into());
+        assert!(matches!(
+            client.evaluate(),
+            Err(ExpressionError::Dimensions('+', 2, 3))
+        ));
+        client.update();
+        assert!(client.error().is_some());
+        assert!(<Expression as Write<A>>::write(&mut client).is_none());
+    }
+
+    #[test]
+    fn transpose() {
+        let expr = Expr::concat([Expr::input::<A>().transpose(2), Expr::input::<A>()]);
+        let mut client = Expression::builder(expr.clone().slice("last"))
+            .range("last", 6..9)
+            .build()
+            .unwrap();
+        <Expression as Read<A>>::read(&mut client, vec![1., 2., 3., 4., 5., 6.].into());
+        assert!(matches!(
+            Expression::new(expr).unwrap().evaluate(),
+            Err(ExpressionError::Unset(_))
+        ));
+        assert_eq!(client.evaluate().unwrap(), vec![1., 2., 3.]);
+        let mut client = Expression::builder(client.expr().clone())
+            .range("last", 9..13)
+            .build()
+            .unwrap();
+        <Expression as Read<A>>::read(&mut client, vec![1., 2., 3., 4., 5., 6.].into());
+        assert!(matches!(
+            client.evaluate(),
+            Err(ExpressionError::OutOfBounds(_, _, 12))
+        ));
+        let mut client = Expression::new(Expr::input::<A>().transpose(2)).unwrap();
+        <Expression as Read<A>>::read(&mut client, vec![1., 2., 3., 4., 5., 6.].into());
+        assert_eq!(client.evaluate().unwrap(), vec![1., 3., 5., 2., 4., 6.]);
+    }
+
+    #[test]
+    fn reshape() {
+        let expr = Expr::input::<A>().reshape(3, 2).transpose(3);
+        let mut client = Expression::new(expr.clone()).unwrap();
+        <Expression as Read<A>>::read(&mut client, vec![1., 2., 3., 4., 5., 6.].into());
+        assert_eq!(client.evaluate().unwrap(), vec![1., 4., 2., 5., 3., 6.]);
+        <Expression as Read<A>>::read(&mut client, vec![1., 2., 3., 4.].into());
+        assert!(matches!(
+            client.evaluate(),
+            Err(ExpressionError::Reshape(4, 3, 2))
+        ));
+        assert!(matches!(
+            Expression::builder(expr).size::<A>(4).build(),
+            Err(ExpressionError::Reshape(4, 3, 2))
+        ));
+    }
+
+    #[test]
+    fn validation() {
+        let expr = Expr::input::<A>() + Expr::input::<B>().slice("tip");
+        assert!(matches!(
+            Expression::new(expr.clone()),
+            Err(ExpressionError::UnknownRange(name)) if name == "tip"
+        ));
+        assert!(matches!(
+            Expression::builder(expr.clone())
+                .range("tip", 0..2)
+                .size::<B>(1)
+                .build(),
+            Err(ExpressionError::OutOfBounds(_, _, 1))
+        ));
+        assert!(matches!(
+            Expression::builder(expr.clone())
+                .range("tip", 0..2)
+                .size::<A>(3)
+                .build(),
+            Err(ExpressionError::Dimensions('+', 3, 2))
+        ));
+        assert!(matches!(
+            Expression::builder(expr.clone())
+                .range("tip", 0..2)
+                .size::<C>(3)
+                .build(),
+            Err(ExpressionError::UnknownInput(name)) if name == "C"
+        ));
+        assert!(Expression::builder(expr)
+            .range("tip", 0..2)
+            .size::<A>(2)
+            .size::<B>(3)
+            .build()
+            .is_ok());
+    }
+}
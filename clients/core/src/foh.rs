@@ -1,6 +1,10 @@
 //! # Fists-order hold
 //!
 //! Linear interpolation between 2 successive samples
+//!
+//! The output sampling rate is either an integer divisor of the input sampling rate, set with `NI/NO`,
+//! or any sampling rate, set with [FirstOrderHold::sampling_frequencies]
+//! for rate transitions between [clock domains](https://docs.rs/gmt_dos-actors/latest/gmt_dos_actors/clock/index.html)
 
 use std::fmt::Debug;
 use std::ops::{Add, Mul, Sub};
@@ -15,6 +19,9 @@ use interface::{Data, Read, UniqueIdentifier, Update, Write};
 pub struct FirstOrderHold<T, const NI: usize, const NO: usize> {
     samples: (Option<Arc<Vec<T>>>, Option<Arc<Vec<T>>>),
     step: usize,
+    frequencies: Option<(f64, f64)>,
+    reads: usize,
+    writes: usize,
 }
 impl<T, const NI: usize, const NO: usize> FirstOrderHold<T, NI, NO>
 where
@@ -41,19 +48,41 @@ where
             ..Default::default()
         }
     }
+    /// Sets the input and output sampling frequencies
+    ///
+    /// The output sample `m` is interpolated at the time `m` x `input` / `output`
+    /// between the last 2 input samples
+    pub fn sampling_frequencies(mut self, input: f64, output: f64) -> Self {
+        self.frequencies = Some((input, output));
+        self
+    }
     #[inline]
     fn rate(&self) -> usize {
         NI / NO
     }
+    /// Fractional delta sample of the next output
+    fn delta(&mut self) -> f64 {
+        match self.frequencies {
+            Some((input, output)) => {
+                // output sample time in input sampling index unit
+                let x = self.writes as f64 * input / output;
+                self.writes += 1;
+                (x - (self.reads as f64 - 2.)).clamp(0., 1.)
+            }
+            None => {
+                // input sampling index
+                let i = self.step % (self.rate());
+                self.step += 1;
+                i as f64 / self.rate() as f64
+            }
+        }
+    }
     fn sample(&mut self) -> Option<Arc<Vec<T>>> {
-        match &self.samples {
+        match self.samples.clone() {
             // linear interpolation
             (Some(y0), Some(y1)) => {
-                // input sampling index
-                let i = self.step % (self.rate());
                 // fractional delta sample
-                let u = i as f64 / self.rate() as f64;
-                self.step += 1;
+                let u = self.delta();
                 Some(Arc::new(
                     y0.iter()
                         .zip(y1.iter())
@@ -62,7 +91,10 @@ where
                 ))
             }
             // // first sample passes through
-            (None, Some(y0)) => Some(y0.clone()),
+            (None, Some(y0)) => {
+                self.writes += 1;
+                Some(y0)
+            }
             _ => None,
         }
     }
@@ -97,6 +129,7 @@ where
     fn read(&mut self, data: Data<U>) {
         self.samples.0 = self.samples.1.clone();
         self.samples.1 = Some(data.into_arc());
+        self.reads += 1;
     }
 }
 
@@ -189,4 +222,22 @@ mod test {
         // foh.update();
         // assert!(<FirstOrderHold<f64, 2, 1> as Write<V>>::write(&mut foh).is_none());
     }
+
+    #[test]
+    fn test_foh_frequencies() {
+        let mut foh = FirstOrderHold::<f64, 1, 1>::new().sampling_frequencies(2., 3.);
+        let mut data = vec![];
+        for (k, n) in [1, 1, 2].into_iter().enumerate() {
+            <FirstOrderHold<f64, 1, 1> as Read<V>>::read(&mut foh, Data::new(vec![k as f64]));
+            foh.update();
+            for _ in 0..n {
+                data.push(<FirstOrderHold<f64, 1, 1> as Write<V>>::write(&mut foh).unwrap()[0]);
+            }
+        }
+        let expected = [0., 2. / 3., 4. / 3., 2.];
+        assert!(data
+            .iter()
+            .zip(expected)
+            .all(|(x, y)| (x - y).abs() < 1e-12));
+    }
 }
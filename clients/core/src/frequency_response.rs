@@ -0,0 +1,558 @@
+//! # Frequency response estimator client
+//!
+//! A [FrequencyResponse] client estimates the frequency responses from an excitation
+//! to one or several responses with the Welch method and the H1 estimator:
+//! the excitation `x` and the responses `y` are split into segments overlapping by half,
+//! each segment is windowed with a Hann window and the auto and cross spectra
+//! are averaged over all the segments, giving the frequency response `H1=Pxy/Pxx`
+//! and the coherence `|Pxy|²/(Pxx Pyy)`.
+//!
+//! The spectra are accumulated online, as soon as a segment is complete,
+//! so the estimates, given by [FrequencyResponse::bode], are available at any time.
+//! With the `parquet` feature, the estimates are written to a [Parquet](https://docs.rs/parquet) file
+//! when the client is dropped, if a [filename](FrequencyResponse::filename) is given.
+//!
+//! The excitation is typically a [Multisine] client or a [chirp](crate::signals::Signal::Chirp)
+//! [Signals](crate::signals::Signals) client.
+//! The phases of the [Multisine] sinusoids are deterministic, they are not redrawn when the model is seeded.
+//! The first element of the excitation data is the excitation,
+//! any other input is a response and each element of the response data gets its own frequency response.
+//!
+//! # Example
+//! ```
+//! use gmt_dos_clients::frequency_response::{FrequencyResponse, Multisine};
+//! use interface::UID;
+//!
+//! #[derive(UID)]
+//! enum Excitation {}
+//!
+//! let frf = FrequencyResponse::new::<Excitation>(1e3, 1024)?.skip(2048);
+//! // a multisine on every fourth frequency bin of the estimator,
+//! // the Hann window spreads each frequency over the 2 adjacent bins
+//! let frequencies_hz: Vec<_> = frf.frequencies().into_iter().skip(4).step_by(4).collect();
+//! let excitation = Multisine::new(1e-3, 1e3, frequencies_hz, 10_000);
+//! # Ok::<(), gmt_dos_clients::frequency_response::FrequencyResponseError>(())
+//! ```
+
+use interface::{trim_type_name, Data, Entry, Read, TimerMarker, UniqueIdentifier, Update, Write};
+use num_complex::Complex64;
+use std::{f64::consts::PI, sync::Arc};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FrequencyResponseError {
+    #[error("the segment length {0} is not a power of 2")]
+    NotPowerOfTwo(usize),
+    #[cfg(feature = "parquet")]
+    #[error("cannot create the parquet file")]
+    IO(#[from] std::io::Error),
+    #[cfg(feature = "parquet")]
+    #[error("failed to build the Arrow record")]
+    Arrow(#[from] apache_arrow::error::ArrowError),
+    #[cfg(feature = "parquet")]
+    #[error("failed to write the parquet file")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+/// Multisine excitation client
+///
+/// A sum of cosines of the same amplitude with phases following Schroeder's rule
+/// to keep the crest factor of the signal low.
+/// Frequencies on the frequency grid of a [FrequencyResponse] estimator
+/// make the multisine periodic within the estimator segments.
+///
+/// Unlike the colored noise of [Signals](crate::signals::Signals),
+/// the phases are fixed and seeding the model leaves the excitation unchanged.
+#[derive(Debug, Clone)]
+pub struct Multisine {
+    amplitude: f64,
+    sampling_frequency_hz: f64,
+    frequencies_hz: Vec<f64>,
+    phases: Vec<f64>,
+    step: usize,
+    n_step: usize,
+}
+impl Multisine {
+    /// Creates a multisine of `n_step` samples with sinusoids of the same `amplitude` at the given frequencies
+    pub fn new(
+        amplitude: f64,
+        sampling_frequency_hz: f64,
+        frequencies_hz: Vec<f64>,
+        n_step: usize,
+    ) -> Self {
+        let n = frequencies_hz.len() as f64;
+        let phases = (0..frequencies_hz.len())
+            .map(|k| -PI * (k * (k + 1)) as f64 / n)
+            .collect();
+        Self {
+            amplitude,
+            sampling_frequency_hz,
+            frequencies_hz,
+            phases,
+            step: 0,
+            n_step,
+        }
+    }
+    /// Returns the frequencies of the sinusoids
+    pub fn frequencies(&self) -> &[f64] {
+        &self.frequencies_hz
+    }
+    /// Returns the phases of the sinusoids in radians
+    pub fn phases(&self) -> &[f64] {
+        &self.phases
+    }
+    /// Returns the multisine value at step `i`
+    pub fn get(&self, i: usize) -> f64 {
+        let t = i as f64 / self.sampling_frequency_hz;
+        self.frequencies_hz
+            .iter()
+            .zip(&self.phases)
+            .map(|(f, phase)| self.amplitude * (2. * PI * f * t + phase).cos())
+            .sum()
+    }
+}
+impl TimerMarker for Multisine {}
+/// The multisine is deterministic, the model seed is ignored
+impl Update for Multisine {}
+impl<U: UniqueIdentifier<DataType = Vec<f64>>> Write<U> for Multisine {
+    fn write(&mut self) -> Option<Data<U>> {
+        if self.step < self.n_step {
+            let data = vec![self.get(self.step)];
+            self.step += 1;
+            Some(Data::new(data))
+        } else {
+            None
+        }
+    }
+}
+
+/// Frequency response of a response channel
+#[derive(Debug, Clone, Default)]
+pub struct Bode {
+    /// Response name
+    pub name: String,
+    /// Frequencies
+    pub frequency_hz: Vec<f64>,
+    /// Complex frequency response (H1 estimate)
+    pub response: Vec<Complex64>,
+    /// Magnitude squared coherence
+    pub coherence: Vec<f64>,
+}
+impl Bode {
+    /// Returns the magnitude of the frequency response
+    pub fn magnitude(&self) -> Vec<f64> {
+        self.response.iter().map(|h| h.norm()).collect()
+    }
+    /// Returns the phase of the frequency response in radians
+    pub fn phase(&self) -> Vec<f64> {
+        self.response.iter().map(|h| h.arg()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Response {
+    name: String,
+    data: Arc<Vec<f64>>,
+    segments: Vec<Vec<f64>>,
+    pxy: Vec<Vec<Complex64>>,
+    pyy: Vec<Vec<f64>>,
+}
+impl Response {
+    /// Creates a new response channel
+    ///
+    /// The segments are padded with `n_sample` zeros to match the length of the excitation segment
+    fn new(name: String, size: usize, n_frequency: usize, n_sample: usize) -> Self {
+        Self {
+            name,
+            data: Arc::new(vec![0f64; size]),
+            segments: vec![vec![0f64; n_sample]; size],
+            pxy: vec![vec![Complex64::from(0.); n_frequency]; size],
+            pyy: vec![vec![0f64; n_frequency]; size],
+        }
+    }
+}
+
+/// Frequency response estimator client
+#[derive(Debug)]
+pub struct FrequencyResponse {
+    sampling_frequency_hz: f64,
+    nfft: usize,
+    window: Vec<f64>,
+    excitation: String,
+    input: Arc<Vec<f64>>,
+    segment: Vec<f64>,
+    pxx: Vec<f64>,
+    responses: Vec<Response>,
+    skip: usize,
+    step: usize,
+    n_segment: usize,
+    #[cfg(feature = "parquet")]
+    filename: Option<String>,
+}
+
+impl FrequencyResponse {
+    /// Creates a new frequency response estimator
+    ///
+    /// The excitation is the first element of the data of the input `U`,
+    /// the spectra are estimated on segments of `nfft` samples, `nfft` must be a power of 2
+    pub fn new<U: UniqueIdentifier>(
+        sampling_frequency_hz: f64,
+        nfft: usize,
+    ) -> Result<Self, FrequencyResponseError> {
+        if !nfft.is_power_of_two() || nfft < 2 {
+            return Err(FrequencyResponseError::NotPowerOfTwo(nfft));
+        }
+        let window = (0..nfft)
+            .map(|i| (PI * i as f64 / nfft as f64).sin().powi(2))
+            .collect();
+        Ok(Self {
+            sampling_frequency_hz,
+            nfft,
+            window,
+            excitation: trim_type_name::<U>(),
+            input: Arc::new(vec![0f64]),
+            segment: Vec::with_capacity(nfft),
+            pxx: vec![0f64; 1 + nfft / 2],
+            responses: vec![],
+            skip: 0,
+            step: 0,
+            n_segment: 0,
+            #[cfg(feature = "parquet")]
+            filename: None,
+        })
+    }
+    /// Discards the first `n` samples, to let the transients die out
+    pub fn skip(mut self, n: usize) -> Self {
+        self.skip = n;
+        self
+    }
+    /// Writes the frequency responses to the given [Parquet](https://docs.rs/parquet) file when the client is dropped
+    #[cfg(feature = "parquet")]
+    pub fn filename<S: Into<String>>(mut self, filename: S) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+    /// Returns the frequencies of the estimates, from 0 to the Nyquist frequency
+    pub fn frequencies(&self) -> Vec<f64> {
+        let df = self.sampling_frequency_hz / self.nfft as f64;
+        (0..=self.nfft / 2).map(|i| i as f64 * df).collect()
+    }
+    /// Returns the number of segments averaged so far
+    pub fn n_segment(&self) -> usize {
+        self.n_segment
+    }
+    /// Returns the frequency responses and the coherences of all the response channels
+    pub fn bode(&self) -> Vec<Bode> {
+        let frequency_hz = self.frequencies();
+        self.responses
+            .iter()
+            .flat_map(|response| {
+                let n = response.pxy.len();
+                response
+                    .pxy
+                    .iter()
+                    .zip(&response.pyy)
+                    .enumerate()
+                    .map(|(i, (pxy, pyy))| Bode {
+                        name: if n > 1 {
+                            format!("{}#{}", response.name, i)
+                        } else {
+                            response.name.clone()
+                        },
+                        frequency_hz: frequency_hz.clone(),
+                        response: pxy.iter().zip(&self.pxx).map(|(xy, xx)| xy / xx).collect(),
+                        coherence: pxy
+                            .iter()
+                            .zip(&self.pxx)
+                            .zip(pyy)
+                            .map(|((xy, xx), yy)| xy.norm_sqr() / (xx * yy))
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+    /// Adds the spectra of the current segments to the estimates
+    fn accumulate(&mut self) {
+        let x = spectrum(&self.segment, &self.window);
+        self.pxx
+            .iter_mut()
+            .zip(&x)
+            .for_each(|(pxx, x)| *pxx += x.norm_sqr());
+        for response in self.responses.iter_mut() {
+            for ((segment, pxy), pyy) in response
+                .segments
+                .iter()
+                .zip(response.pxy.iter_mut())
+                .zip(response.pyy.iter_mut())
+            {
+                let y = spectrum(segment, &self.window);
+                pxy.iter_mut()
+                    .zip(pyy.iter_mut())
+                    .zip(x.iter().zip(&y))
+                    .for_each(|((pxy, pyy), (x, y))| {
+                        *pxy += x.conj() * y;
+                        *pyy += y.norm_sqr();
+                    });
+            }
+        }
+        self.n_segment += 1;
+    }
+    /// Saves the frequency responses to a [Parquet](https://docs.rs/parquet) data file
+    ///
+    /// The file has a column with the frequencies and, for each response channel,
+    /// the magnitude, the phase in radians and the coherence columns.
+    /// The file is saved in the current directory
+    /// unless the environment variable `DATA_REPO` is set to another directory.
+    #[cfg(feature = "parquet")]
+    pub fn to_parquet<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), FrequencyResponseError> {
+        use apache_arrow::{
+            array::{ArrayRef, Float64Array},
+            datatypes::{DataType, Field, Schema},
+            record_batch::RecordBatch,
+        };
+        use parquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+
+        let mut fields = vec![Field::new("frequency_hz", DataType::Float64, false)];
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(Float64Array::from(self.frequencies()))];
+        for bode in self.bode() {
+            for (column, data) in [
+                ("magnitude", bode.magnitude()),
+                ("phase_rad", bode.phase()),
+                ("coherence", bode.coherence),
+            ] {
+                fields.push(Field::new(
+                    format!("{} {}", bode.name, column),
+                    DataType::Float64,
+                    false,
+                ));
+                columns.push(Arc::new(Float64Array::from(data)));
+            }
+        }
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+        let root_env = std::env::var("DATA_REPO").unwrap_or_else(|_| ".".to_string());
+        let root = std::path::Path::new(&root_env)
+            .join(&path)
+            .with_extension("parquet");
+        let file = std::fs::File::create(&root)?;
+        let props = WriterProperties::builder().build();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        log::info!("frequency responses saved to {root:?}");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl Drop for FrequencyResponse {
+    fn drop(&mut self) {
+        if let Some(filename) = self.filename.take() {
+            if let Err(e) = self.to_parquet(&filename) {
+                interface::print_info(format!("failed to save {filename}"), Some(&e));
+            }
+        }
+    }
+}
+
+/// One-sided spectrum of the windowed `segment`
+fn spectrum(segment: &[f64], window: &[f64]) -> Vec<Complex64> {
+    let mut data: Vec<Complex64> = segment
+        .iter()
+        .zip(window)
+        .map(|(x, w)| Complex64::from(x * w))
+        .collect();
+    fft(&mut data);
+    data.truncate(1 + segment.len() / 2);
+    data
+}
+
+/// In-place radix-2 decimation in time Fast Fourier Transform
+fn fft(data: &mut [Complex64]) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let w = Complex64::from_polar(1., -2. * PI / len as f64);
+        for chunk in data.chunks_mut(len) {
+            let (even, odd) = chunk.split_at_mut(len / 2);
+            let mut wk = Complex64::from(1.);
+            for (e, o) in even.iter_mut().zip(odd.iter_mut()) {
+                let t = wk * *o;
+                *o = *e - t;
+                *e += t;
+                wk *= w;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+impl Update for FrequencyResponse {
+    fn update(&mut self) {
+        self.step += 1;
+        if self.step <= self.skip {
+            return;
+        }
+        self.segment.push(self.input[0]);
+        for response in self.responses.iter_mut() {
+            response
+                .segments
+                .iter_mut()
+                .zip(response.data.iter())
+                .for_each(|(segment, y)| segment.push(*y));
+        }
+        if self.segment.len() == self.nfft {
+            self.accumulate();
+            // segments overlap by half
+            let hop = self.nfft / 2;
+            self.segment.drain(..hop);
+            for response in self.responses.iter_mut() {
+                response.segments.iter_mut().for_each(|segment| {
+                    segment.drain(..hop);
+                });
+            }
+        }
+    }
+}
+
+impl<U> Entry<U> for FrequencyResponse
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn entry(&mut self, size: usize) {
+        let name = trim_type_name::<U>();
+        if name != self.excitation {
+            self.responses.push(Response::new(
+                name,
+                size,
+                1 + self.nfft / 2,
+                self.segment.len(),
+            ));
+        }
+    }
+}
+
+impl<U> Read<U> for FrequencyResponse
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn read(&mut self, data: Data<U>) {
+        let name = trim_type_name::<U>();
+        if name == self.excitation {
+            self.input = data.into_arc();
+            return;
+        }
+        let data = data.into_arc();
+        let n_frequency = 1 + self.nfft / 2;
+        let index = match self.responses.iter().position(|r| r.name == name) {
+            Some(index) => index,
+            None => {
+                // a response read after the excitation has started is null until then
+                self.responses.push(Response::new(
+                    name,
+                    data.len(),
+                    n_frequency,
+                    self.segment.len(),
+                ));
+                self.responses.len() - 1
+            }
+        };
+        self.responses[index].data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Excitation {}
+    impl UniqueIdentifier for Excitation {
+        type DataType = Vec<f64>;
+    }
+    enum Response {}
+    impl UniqueIdentifier for Response {
+        type DataType = Vec<f64>;
+    }
+
+    #[test]
+    fn delayed_gain() {
+        // y[k] = 2 x[k-1]
+        let fs = 1e3;
+        let mut frf = FrequencyResponse::new::<Excitation>(fs, 256)
+            .unwrap()
+            .skip(256);
+        // the Hann window spreads a frequency over 3 bins, only 1 in 4 bins is excited
+        let frequencies: Vec<_> = frf.frequencies().into_iter().skip(4).step_by(4).collect();
+        let signal = Multisine::new(1., fs, frequencies, 256 * 10);
+        let mut x = 0f64;
+        for i in 0..256 * 10 {
+            let y = 2. * x;
+            x = signal.get(i);
+            <FrequencyResponse as Read<Excitation>>::read(&mut frf, vec![x].into());
+            <FrequencyResponse as Read<Response>>::read(&mut frf, vec![y].into());
+            frf.update();
+        }
+        assert_eq!(frf.n_segment(), 17);
+        let bode = &frf.bode()[0];
+        assert_eq!(bode.name, "Response");
+        for ((f, h), c) in bode
+            .frequency_hz
+            .iter()
+            .zip(&bode.response)
+            .zip(&bode.coherence)
+            .skip(4)
+            .step_by(4)
+        {
+            let expected = 2. * Complex64::from_polar(1., -2. * PI * f / fs);
+            assert!((h - expected).norm() < 1e-6, "{f}: {h} vs {expected}");
+            assert!((c - 1.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn deterministic_multisine() {
+        use interface::Seed;
+        let fs = 1e3;
+        let frequencies = vec![10., 20., 30.];
+        let mut multisine = Multisine::new(1., fs, frequencies.clone(), 100);
+        let samples: Vec<f64> = (0..100).map(|i| multisine.get(i)).collect();
+        multisine.seed(Seed::new(7));
+        assert_eq!(
+            multisine.phases(),
+            Multisine::new(1., fs, frequencies, 100).phases()
+        );
+        for sample in samples {
+            let data = <Multisine as Write<Excitation>>::write(&mut multisine).unwrap();
+            assert_eq!(data[0], sample);
+        }
+        assert!(<Multisine as Write<Excitation>>::write(&mut multisine).is_none());
+    }
+
+    #[test]
+    fn late_response() {
+        let mut frf = FrequencyResponse::new::<Excitation>(1e3, 16).unwrap();
+        for i in 0..64 {
+            <FrequencyResponse as Read<Excitation>>::read(&mut frf, vec![i as f64].into());
+            if i >= 5 {
+                <FrequencyResponse as Read<Response>>::read(&mut frf, vec![i as f64].into());
+            }
+            frf.update();
+        }
+        assert_eq!(frf.n_segment(), 7);
+        assert!(frf.bode()[0].response.iter().all(|h| h.is_finite()));
+    }
+}
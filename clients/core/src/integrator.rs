@@ -188,6 +188,7 @@ where
 pub struct Offset<O>(PhantomData<O>);
 impl<O: UniqueIdentifier> UniqueIdentifier for Offset<O> {
     type DataType = Option<O::DataType>;
+    const UNITS: Option<&'static str> = O::UNITS;
 }
 
 impl<T, U, O> Read<Offset<O>> for Integrator<U>
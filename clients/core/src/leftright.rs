@@ -257,12 +257,14 @@ where
 pub struct Left<U: UniqueIdentifier>(PhantomData<U>);
 impl<U: UniqueIdentifier> UniqueIdentifier for Left<U> {
     type DataType = U::DataType;
+    const UNITS: Option<&'static str> = U::UNITS;
 }
 
 /// Identifier for the right part of the splitted data
 pub struct Right<U: UniqueIdentifier>(PhantomData<U>);
 impl<U: UniqueIdentifier> UniqueIdentifier for Right<U> {
     type DataType = U::DataType;
+    const UNITS: Option<&'static str> = U::UNITS;
 }
 
 impl<T, U, V> Write<Left<U>> for LeftRight<U, Split, V>
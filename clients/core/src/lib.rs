@@ -98,8 +98,9 @@ println!(
  * `noise` : enable noisy signals
  * `serde` : enable serialization
  * `gif` : enable the gif client
- * `faer` : enable matrix gain
- * `nalgebra` : enable matrix gain with [nalgebra](https://docs.rs/nalgebra) matrix input
+ * `faer` : enable matrix gain and [faer](https://docs.rs/faer) matrices in state-space models
+ * `nalgebra` : enable matrix gain, state-space and transfer function clients with [nalgebra](https://docs.rs/nalgebra) matrices
+ * `parquet` : save the frequency responses estimates to [Parquet](https://docs.rs/parquet) files
 
 [Actor]: https://docs.rs/gmt_dos-actors
 [Update]: https://docs.rs/gmt_dos-actors-clients_interface/latest/gmt_dos_actors-clients_interface/struct.Update.html
@@ -113,8 +114,11 @@ use interface::{Data, Read, TimerMarker, UniqueIdentifier, Update, Write};
 use std::mem::take;
 
 pub mod average;
+pub mod convert;
+pub mod expression;
 pub mod fill;
 pub mod foh;
+pub mod frequency_response;
 pub mod fun;
 pub mod gain;
 #[cfg(feature = "gif")]
@@ -133,7 +137,11 @@ pub mod sampler;
 pub mod select;
 pub mod signals;
 pub mod smooth;
+#[cfg(feature = "nalgebra")]
+pub mod state_space;
 pub mod timer;
+#[cfg(feature = "nalgebra")]
+pub mod transfer_function;
 
 /// Concatenates data into a [Vec]
 pub struct Concat<T>(Vec<T>);
@@ -88,6 +88,8 @@ where
     type DataType = <U as UniqueIdentifier>::DataType;
 
     const PORT: u16 = <U as UniqueIdentifier>::PORT;
+    const UNITS: Option<&'static str> = <U as UniqueIdentifier>::UNITS;
+    const LEN: Option<usize> = <U as UniqueIdentifier>::LEN;
 }
 
 pub struct Right<U: UniqueIdentifier>(PhantomData<U>);
@@ -98,6 +100,8 @@ where
     type DataType = <U as UniqueIdentifier>::DataType;
 
     const PORT: u16 = <U as UniqueIdentifier>::PORT;
+    const UNITS: Option<&'static str> = <U as UniqueIdentifier>::UNITS;
+    const LEN: Option<usize> = <U as UniqueIdentifier>::LEN;
 }
 
 impl<T, U> Read<Left<U>> for Operator<T>
@@ -1,10 +1,30 @@
 use super::{Data, TimerMarker, UniqueIdentifier, Update, Write};
 // use linya::{Bar, Progress};
-use std::ops::Add;
+use std::{f64::consts::PI, fs, ops::Add, path::Path};
 
+#[cfg(feature = "noise")]
+use interface::Seed;
+#[cfg(feature = "noise")]
+use rand::{rngs::StdRng, Rng, SeedableRng};
 #[cfg(feature = "noise")]
 use rand_distr::{Distribution, Normal, NormalError};
 
+// Random number generator of the white noise signals until the model seed is set
+#[cfg(feature = "noise")]
+fn default_rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+/// Frequency sweep of a [Signal::Chirp]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sweep {
+    /// The frequency increases linearly with time
+    Linear,
+    /// The frequency increases exponentially with time
+    Logarithmic,
+}
+
 /// Signal types
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
@@ -29,6 +49,44 @@ pub enum Signal {
     #[cfg(feature = "noise")]
     #[cfg_attr(feature = "serde", serde(skip))]
     WhiteNoise(Normal<f64>),
+    /// A swept sine from `start_frequency_hz` to `end_frequency_hz` in `duration_s`, null afterwards
+    Chirp {
+        amplitude: f64,
+        sampling_frequency_hz: f64,
+        start_frequency_hz: f64,
+        end_frequency_hz: f64,
+        duration_s: f64,
+        sweep: Sweep,
+    },
+    /// A pseudo-random binary sequence of ±`amplitude` starting at sample `start`,
+    /// each bit is held for `hold` samples, see [Signal::prbs]
+    Prbs {
+        amplitude: f64,
+        hold: usize,
+        start: usize,
+        bits: Vec<bool>,
+    },
+    /// A step of height `amplitude` at sample `start`
+    Step { amplitude: f64, start: usize },
+    /// A single sample of height `amplitude` at sample `start`
+    Impulse { amplitude: f64, start: usize },
+    /// A pulse of height `amplitude` from sample `start` followed by a pulse of height `-amplitude`,
+    /// each pulse is `width` samples long
+    Doublet {
+        amplitude: f64,
+        start: usize,
+        width: usize,
+    },
+    /// A sum of sinusoids, see [Signal::colored_noise]
+    ColoredNoise {
+        sampling_frequency_hz: f64,
+        amplitudes: Vec<f64>,
+        frequencies_hz: Vec<f64>,
+        phases: Vec<f64>,
+    },
+    /// A sequence of samples, null after the last sample unless the sequence is looping,
+    /// see [Signal::from_csv]
+    Sequence { samples: Vec<f64>, looping: bool },
     /// A symphony?
     Composite(Vec<Signal>),
 }
@@ -38,6 +96,132 @@ impl From<f64> for Signal {
         Signal::Constant(val)
     }
 }
+impl From<Vec<f64>> for Signal {
+    fn from(samples: Vec<f64>) -> Self {
+        Signal::Sequence {
+            samples,
+            looping: false,
+        }
+    }
+}
+
+/// Feedback taps of maximal length linear feedback shift registers
+const PRBS_TAPS: [&[u32]; 19] = [
+    &[2, 1],
+    &[3, 2],
+    &[4, 3],
+    &[5, 3],
+    &[6, 5],
+    &[7, 6],
+    &[8, 6, 5, 4],
+    &[9, 5],
+    &[10, 7],
+    &[11, 9],
+    &[12, 6, 4, 1],
+    &[13, 4, 3, 1],
+    &[14, 5, 3, 1],
+    &[15, 14],
+    &[16, 15, 13, 4],
+    &[17, 14],
+    &[18, 11],
+    &[19, 6, 2, 1],
+    &[20, 17],
+];
+
+impl Signal {
+    /// Creates a pseudo-random binary sequence of ±`amplitude`
+    ///
+    /// The sequence is generated with a maximal length linear feedback shift register of `order` bits,
+    /// it repeats itself every 2<sup>order</sup>-1 bits and each bit is held for `hold` samples.
+    /// The order is between 2 and 20
+    pub fn prbs(order: u32, amplitude: f64, hold: usize) -> Result<Self, SignalsError> {
+        let taps = (2..=20)
+            .contains(&order)
+            .then(|| PRBS_TAPS[order as usize - 2])
+            .ok_or(SignalsError::PrbsOrder(order))?;
+        let mask = (1u32 << order) - 1;
+        let mut state = mask;
+        let bits = (0..mask)
+            .map(|_| {
+                let bit = taps.iter().fold(0, |bit, tap| bit ^ (state >> (tap - 1))) & 1;
+                state = ((state << 1) | bit) & mask;
+                bit == 1
+            })
+            .collect();
+        Ok(Signal::Prbs {
+            amplitude,
+            hold,
+            start: 0,
+            bits,
+        })
+    }
+    /// Delays the start of a [Signal::Prbs], [Signal::Step], [Signal::Impulse] or [Signal::Doublet] to sample `at`
+    pub fn start(self, at: usize) -> Self {
+        match self {
+            Signal::Prbs {
+                amplitude,
+                hold,
+                bits,
+                ..
+            } => Signal::Prbs {
+                amplitude,
+                hold,
+                start: at,
+                bits,
+            },
+            Signal::Step { amplitude, .. } => Signal::Step {
+                amplitude,
+                start: at,
+            },
+            Signal::Impulse { amplitude, .. } => Signal::Impulse {
+                amplitude,
+                start: at,
+            },
+            Signal::Doublet {
+                amplitude, width, ..
+            } => Signal::Doublet {
+                amplitude,
+                start: at,
+                width,
+            },
+            _ => self,
+        }
+    }
+    /// Repeats a [Signal::Sequence] after its last sample
+    pub fn looping(self) -> Self {
+        if let Signal::Sequence { samples, .. } = self {
+            Signal::Sequence {
+                samples,
+                looping: true,
+            }
+        } else {
+            self
+        }
+    }
+    /// Creates a [Signal::Sequence] from the `column` of a CSV file
+    ///
+    /// The first line of the file is the header with the columns names.
+    /// A column of a Parquet file written by the Arrow logger can be turned into a [Signal::Sequence]
+    /// by collecting the column iterator `Arrow::iter` into a [Vec] and converting it into a [Signal]
+    pub fn from_csv(path: impl AsRef<Path>, column: &str) -> Result<Self, SignalsError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let idx = lines
+            .next()
+            .and_then(|header| header.split(',').position(|name| name.trim() == column))
+            .ok_or_else(|| SignalsError::Column(column.into(), path.display().to_string()))?;
+        let samples = lines
+            .map(|line| {
+                let value = line.split(',').nth(idx).unwrap_or_default().trim();
+                value
+                    .parse::<f64>()
+                    .map_err(|_| SignalsError::Parse(value.into()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples.into())
+    }
+}
 
 #[cfg(feature = "noise")]
 impl Signal {
@@ -61,9 +245,81 @@ impl Signal {
             Ok(self)
         }
     }
+    /// Returns the signal value at step `i`, drawing the noise samples from the random number generator `rng`
+    pub fn sample<R: Rng>(&self, i: usize, rng: &mut R) -> f64 {
+        match self {
+            Signal::WhiteNoise(noise) => noise.sample(rng),
+            Signal::Composite(signals) => signals.iter().map(|signal| signal.sample(i, rng)).sum(),
+            _ => self.get(i),
+        }
+    }
+    /// Draws new random phases for [Signal::ColoredNoise] from the random number generator `rng`
+    pub fn reseed<R: Rng>(&mut self, rng: &mut R) {
+        match self {
+            Signal::ColoredNoise { phases, .. } => phases
+                .iter_mut()
+                .for_each(|phase| *phase = 2. * PI * rng.gen::<f64>()),
+            Signal::Composite(signals) => signals.iter_mut().for_each(|signal| signal.reseed(rng)),
+            _ => (),
+        }
+    }
+    /// Creates a noise with the one-sided power spectral density `psd` (units²/Hz)
+    ///
+    /// The noise is the sum of `n_frequency` sinusoids with random phases,
+    /// with frequencies randomly spread within `n_frequency` bins evenly distributed from 0 to the Nyquist frequency.
+    /// The same `seed` gives the same noise
+    pub fn colored_noise<F>(
+        psd: F,
+        sampling_frequency_hz: f64,
+        n_frequency: usize,
+        seed: u64,
+    ) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let df = 0.5 * sampling_frequency_hz / n_frequency as f64;
+        let frequencies_hz: Vec<_> = (0..n_frequency)
+            .map(|i| (i as f64 + rng.gen::<f64>()) * df)
+            .collect();
+        let amplitudes = frequencies_hz
+            .iter()
+            .map(|&f| (2. * psd(f).max(0.) * df).sqrt())
+            .collect();
+        let phases = (0..n_frequency)
+            .map(|_| 2. * PI * rng.gen::<f64>())
+            .collect();
+        Signal::ColoredNoise {
+            sampling_frequency_hz,
+            amplitudes,
+            frequencies_hz,
+            phases,
+        }
+    }
+    /// Creates a noise with a flat spectrum between the frequencies `band_hz` and the standard deviation `std_dev`
+    ///
+    /// See [Signal::colored_noise]
+    pub fn band_limited_noise(
+        std_dev: f64,
+        band_hz: (f64, f64),
+        sampling_frequency_hz: f64,
+        n_frequency: usize,
+        seed: u64,
+    ) -> Self {
+        let (low, high) = band_hz;
+        let level = std_dev * std_dev / (high - low);
+        Self::colored_noise(
+            |f| if (low..=high).contains(&f) { level } else { 0. },
+            sampling_frequency_hz,
+            n_frequency,
+            seed,
+        )
+    }
 }
 impl Signal {
     /// Returns the signal value at step `i`
+    ///
+    /// The white noise is drawn from the thread random number generator, use [Signal::sample] for a reproducible noise
     pub fn get(&self, i: usize) -> f64 {
         use Signal::*;
         match self {
@@ -91,6 +347,88 @@ impl Signal {
             }
             #[cfg(feature = "noise")]
             WhiteNoise(noise) => noise.sample(&mut rand::thread_rng()),
+            Chirp {
+                amplitude,
+                sampling_frequency_hz,
+                start_frequency_hz: f0,
+                end_frequency_hz: f1,
+                duration_s,
+                sweep,
+            } => {
+                let t = i as f64 / sampling_frequency_hz;
+                if t > *duration_s {
+                    return 0f64;
+                }
+                let phase = match sweep {
+                    Sweep::Linear => f0 * t + 0.5 * (f1 - f0) * t * t / duration_s,
+                    Sweep::Logarithmic => {
+                        let k = (f1 / f0).ln();
+                        f0 * duration_s * ((k * t / duration_s).exp() - 1.) / k
+                    }
+                };
+                amplitude * (2. * PI * phase).sin()
+            }
+            Prbs {
+                amplitude,
+                hold,
+                start,
+                bits,
+            } => {
+                if i < *start || bits.is_empty() {
+                    return 0f64;
+                }
+                if bits[((i - start) / hold.max(&1)) % bits.len()] {
+                    *amplitude
+                } else {
+                    -amplitude
+                }
+            }
+            Step { amplitude, start } => {
+                if i >= *start {
+                    *amplitude
+                } else {
+                    0f64
+                }
+            }
+            Impulse { amplitude, start } => {
+                if i == *start {
+                    *amplitude
+                } else {
+                    0f64
+                }
+            }
+            Doublet {
+                amplitude,
+                start,
+                width,
+            } => match i.checked_sub(*start) {
+                Some(k) if k < *width => *amplitude,
+                Some(k) if k < 2 * width => -amplitude,
+                _ => 0f64,
+            },
+            ColoredNoise {
+                sampling_frequency_hz,
+                amplitudes,
+                frequencies_hz,
+                phases,
+            } => {
+                let t = i as f64 / sampling_frequency_hz;
+                amplitudes
+                    .iter()
+                    .zip(frequencies_hz)
+                    .zip(phases)
+                    .map(|((a, f), p)| a * (2. * PI * f * t + p).cos())
+                    .sum()
+            }
+            Sequence { samples, looping } => {
+                if samples.is_empty() {
+                    0f64
+                } else if *looping {
+                    samples[i % samples.len()]
+                } else {
+                    samples.get(i).copied().unwrap_or_default()
+                }
+            }
             Composite(signals) => signals.iter().map(|signal| signal.get(i)).sum(),
         }
     }
@@ -103,6 +441,8 @@ pub struct Signals {
     pub signals: Vec<Signal>,
     pub step: usize,
     pub n_step: usize,
+    #[cfg(feature = "noise")]
+    rng: StdRng,
 }
 impl Signals {
     /// Create a signal generator with `n` channels for `n_step` iterations
@@ -115,6 +455,8 @@ impl Signals {
             signals,
             step: 0,
             n_step,
+            #[cfg(feature = "noise")]
+            rng: default_rng(),
         }
     }
     // pub fn progress(&mut self) {
@@ -209,12 +551,31 @@ impl Add for Signal {
     }
 }
 impl TimerMarker for Signals {}
-impl Update for Signals {}
+/// The white noise signals are sampled from a random number generator seeded with the [Seed]
+/// and new random phases are drawn for the colored noise signals,
+/// the random number generator is seeded from the operating system entropy if the model seed is not set
+impl Update for Signals {
+    #[cfg(feature = "noise")]
+    fn seed(&mut self, seed: Seed) {
+        let mut rng = StdRng::seed_from_u64(seed.value());
+        self.signals
+            .iter_mut()
+            .for_each(|signal| signal.reseed(&mut rng));
+        self.rng = rng;
+    }
+}
 impl<U: UniqueIdentifier<DataType = Vec<f64>>> Write<U> for Signals {
     fn write(&mut self) -> Option<Data<U>> {
         // log::debug!("write {:?}", self.size);
         if self.step < self.n_step {
             let i = self.step;
+            #[cfg(feature = "noise")]
+            let data = self
+                .signals
+                .iter()
+                .map(|signal| signal.sample(i, &mut self.rng))
+                .collect();
+            #[cfg(not(feature = "noise"))]
             let data = self.signals.iter().map(|signal| signal.get(i)).collect();
             self.step += 1;
             Some(Data::new(data))
@@ -228,6 +589,14 @@ impl<U: UniqueIdentifier<DataType = Vec<f64>>> Write<U> for Signals {
 pub enum SignalsError {
     #[error("Two many signal channels, should be only 1")]
     OneSignal,
+    #[error("PRBS order {0} is not supported, it should be between 2 and 20")]
+    PrbsOrder(u32),
+    #[error("cannot read the signal file")]
+    IO(#[from] std::io::Error),
+    #[error("column {0} not found in {1}")]
+    Column(String, String),
+    #[error("failed to parse {0:?} into a number")]
+    Parse(String),
 }
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
@@ -235,6 +604,9 @@ pub struct OneSignal {
     pub signal: Signal,
     pub step: usize,
     pub n_step: usize,
+    #[cfg(feature = "noise")]
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_rng"))]
+    rng: StdRng,
 }
 impl TryFrom<Signals> for OneSignal {
     type Error = SignalsError;
@@ -246,15 +618,28 @@ impl TryFrom<Signals> for OneSignal {
                 signal: signals.signals.remove(0),
                 step: signals.step,
                 n_step: signals.n_step,
+                #[cfg(feature = "noise")]
+                rng: signals.rng,
             })
         }
     }
 }
-impl Update for OneSignal {}
+/// See [Signals]
+impl Update for OneSignal {
+    #[cfg(feature = "noise")]
+    fn seed(&mut self, seed: Seed) {
+        let mut rng = StdRng::seed_from_u64(seed.value());
+        self.signal.reseed(&mut rng);
+        self.rng = rng;
+    }
+}
 impl<U: UniqueIdentifier<DataType = f64>> Write<U> for OneSignal {
     fn write(&mut self) -> Option<Data<U>> {
         if self.step < self.n_step {
             let i = self.step;
+            #[cfg(feature = "noise")]
+            let data = self.signal.sample(i, &mut self.rng);
+            #[cfg(not(feature = "noise"))]
             let data = self.signal.get(i);
             self.step += 1;
             Some(Data::new(data))
@@ -263,3 +648,118 @@ impl<U: UniqueIdentifier<DataType = f64>> Write<U> for OneSignal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prbs() {
+        for order in 2..=16 {
+            let Signal::Prbs { bits, .. } = Signal::prbs(order, 1., 1).unwrap() else {
+                unreachable!()
+            };
+            let n = bits.len();
+            let windows: std::collections::HashSet<Vec<bool>> = (0..n)
+                .map(|i| (0..order as usize).map(|j| bits[(i + j) % n]).collect())
+                .collect();
+            assert_eq!(windows.len(), n, "PRBS of order {order} is not maximal");
+        }
+        let prbs = Signal::prbs(3, 2., 2).unwrap().start(1);
+        let signal: Vec<_> = (0..16).map(|i| prbs.get(i)).collect();
+        assert_eq!(signal[0], 0.);
+        assert_eq!(signal[1], signal[2]);
+        assert_eq!(signal[1], signal[15]);
+        assert_eq!(signal.iter().map(|x| x.abs()).sum::<f64>(), 30.);
+    }
+
+    #[test]
+    fn pulses() {
+        let signal = Signal::Step {
+            amplitude: 1.,
+            start: 0,
+        }
+        .start(2)
+            + Signal::Impulse {
+                amplitude: 1.,
+                start: 1,
+            }
+            + Signal::Doublet {
+                amplitude: 1.,
+                start: 3,
+                width: 2,
+            };
+        let samples: Vec<_> = (0..8).map(|i| signal.get(i)).collect();
+        assert_eq!(samples, vec![0., 1., 1., 2., 2., 0., 0., 1.]);
+    }
+
+    #[test]
+    fn chirp() {
+        let chirp = |sweep| Signal::Chirp {
+            amplitude: 1.,
+            sampling_frequency_hz: 1e3,
+            start_frequency_hz: 1.,
+            end_frequency_hz: 100.,
+            duration_s: 1.,
+            sweep,
+        };
+        // number of cycles: (f0+f1)T/2 for a linear sweep and f0T(f1/f0-1)/ln(f1/f0) for a logarithmic sweep
+        for (sweep, n_cycle) in [
+            (Sweep::Linear, 50.5),
+            (Sweep::Logarithmic, 99. / 100f64.ln()),
+        ] {
+            let signal = chirp(sweep);
+            let n = (0..1000)
+                .filter(|&i| signal.get(i) * signal.get(i + 1) < 0.)
+                .count();
+            assert!((n as f64 - 2. * n_cycle).abs() <= 1.);
+            assert_eq!(signal.get(1001), 0.);
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let path = std::env::temp_dir().join("gmt_dos-clients_signals.csv");
+        fs::write(&path, "t, x\n0, 1.5\n1, -2\n\n").unwrap();
+        let signal = Signal::from_csv(&path, "x").unwrap().looping();
+        let samples: Vec<_> = (0..3).map(|i| signal.get(i)).collect();
+        assert_eq!(samples, vec![1.5, -2., 1.5]);
+        assert!(matches!(
+            Signal::from_csv(&path, "y"),
+            Err(SignalsError::Column(..))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "noise")]
+    #[test]
+    fn band_limited_noise() {
+        let noise = Signal::band_limited_noise(2., (10., 50.), 1e3, 500, 7);
+        let samples: Vec<_> = (0..100_000).map(|i| noise.get(i)).collect();
+        let var = samples.iter().map(|x| x * x).sum::<f64>() / samples.len() as f64;
+        assert!((var.sqrt() / 2. - 1.).abs() < 0.05);
+        let other = Signal::band_limited_noise(2., (10., 50.), 1e3, 500, 7);
+        assert_eq!(other.get(123), noise.get(123));
+    }
+
+    #[cfg(feature = "noise")]
+    #[test]
+    fn white_noise_seed() {
+        #[derive(interface::UID)]
+        enum Noise {}
+        let noise = || Signals::new(1, 10).channels(Signal::white_noise().unwrap());
+        let samples = |mut signals: Signals| {
+            (0..10)
+                .map(|_| <Signals as Write<Noise>>::write(&mut signals).unwrap()[0])
+                .collect::<Vec<_>>()
+        };
+        // unseeded noises are independent
+        assert_ne!(samples(noise()), samples(noise()));
+        let seeded = || {
+            let mut signals = noise();
+            signals.seed(Seed::new(42).derive("noise"));
+            signals
+        };
+        assert_eq!(samples(seeded()), samples(seeded()));
+    }
+}
@@ -0,0 +1,358 @@
+//! # Discrete-time state-space controller client
+//!
+//! A [StateSpace] client implements the MIMO linear time-invariant system
+//! ```text
+//! x[k+1] = A x[k] + B u[k]
+//!   y[k] = C x[k] + D u[k]
+//! ```
+//! at the rate of the actor it belongs to.
+//!
+//! The matrices are [nalgebra](https://docs.rs/nalgebra) matrices, which can also be built from rows of [Vec]s
+//! or, with the `faer` feature, from [faer](https://docs.rs/faer) matrices.
+//! The outputs can be bounded with [StateSpace::saturation] and the states protected from windup with [StateSpace::anti_windup].
+//!
+//! # Example
+//! A discrete integrator with an output saturated to ±1
+//! ```
+//! use gmt_dos_clients::state_space::{AntiWindup, StateSpace};
+//! let integrator = StateSpace::new(vec![vec![1.]], vec![vec![1.]], vec![vec![1.]], vec![vec![0.]])?
+//!     .saturation(-1., 1.)
+//!     .anti_windup(AntiWindup::Clamping)?;
+//! # Ok::<(), gmt_dos_clients::state_space::StateSpaceError>(())
+//! ```
+
+use interface::{Data, Read, UniqueIdentifier, Update, Write};
+use nalgebra::{DMatrix, DVector, DVectorView};
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StateSpaceError {
+    #[error("matrix {0} is {1}x{2}, expected {3}x{4}")]
+    Dimensions(char, usize, usize, usize, usize),
+    #[error("matrix rows have different lengths")]
+    Ragged,
+    #[error("back-calculation anti-windup requires as many inputs ({0}) as outputs ({1})")]
+    BackCalculation(usize, usize),
+}
+
+/// Conversion into the matrices of a [StateSpace] model
+pub trait IntoMatrix {
+    fn into_matrix(self) -> Result<DMatrix<f64>, StateSpaceError>;
+}
+impl IntoMatrix for DMatrix<f64> {
+    fn into_matrix(self) -> Result<DMatrix<f64>, StateSpaceError> {
+        Ok(self)
+    }
+}
+impl IntoMatrix for Vec<Vec<f64>> {
+    /// Converts the rows of a matrix
+    fn into_matrix(self) -> Result<DMatrix<f64>, StateSpaceError> {
+        let ncols = self.first().map_or(0, |row| row.len());
+        if self.iter().any(|row| row.len() != ncols) {
+            return Err(StateSpaceError::Ragged);
+        }
+        Ok(DMatrix::from_row_iterator(
+            self.len(),
+            ncols,
+            self.into_iter().flatten(),
+        ))
+    }
+}
+#[cfg(feature = "faer")]
+impl IntoMatrix for faer::Mat<f64> {
+    fn into_matrix(self) -> Result<DMatrix<f64>, StateSpaceError> {
+        Ok(DMatrix::from_fn(self.nrows(), self.ncols(), |i, j| {
+            self[(i, j)]
+        }))
+    }
+}
+
+/// Anti-windup strategies of a saturated [StateSpace] model
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AntiWindup {
+    /// The states are updated regardless of the saturation
+    #[default]
+    None,
+    /// The states are frozen while their update drives further any saturated output (conditional integration)
+    Clamping,
+    /// The saturation excess, scaled by the given gain, is fed back to the inputs:
+    /// `x[k+1] = A x[k] + B (u[k] + g (sat(y[k]) - y[k]))`
+    BackCalculation(f64),
+}
+
+/// Discrete-time state-space client
+///
+/// The states, the outputs and the intermediate results are stored in buffers allocated once
+#[derive(Debug, Clone)]
+pub struct StateSpace {
+    a: DMatrix<f64>,
+    b: DMatrix<f64>,
+    c: DMatrix<f64>,
+    d: DMatrix<f64>,
+    x: DVector<f64>,
+    u: Arc<Vec<f64>>,
+    y: DVector<f64>,
+    saturation: Option<(f64, f64)>,
+    anti_windup: AntiWindup,
+    /// next states
+    x_next: DVector<f64>,
+    /// unsaturated outputs
+    y_free: DVector<f64>,
+    /// outputs change due to the states update
+    dy: DVector<f64>,
+    /// inputs corrected by the back-calculation anti-windup
+    u_aw: DVector<f64>,
+}
+
+impl StateSpace {
+    /// Creates a new state-space model from the `A`, `B`, `C` and `D` matrices
+    ///
+    /// The matrices dimensions are checked against the number of states (`A` rows),
+    /// the number of inputs (`B` columns) and the number of outputs (`C` rows)
+    pub fn new<M: IntoMatrix>(a: M, b: M, c: M, d: M) -> Result<Self, StateSpaceError> {
+        let (a, b, c, d) = (
+            a.into_matrix()?,
+            b.into_matrix()?,
+            c.into_matrix()?,
+            d.into_matrix()?,
+        );
+        let (n_state, n_input, n_output) = (a.nrows(), b.ncols(), c.nrows());
+        for (name, mat, shape) in [
+            ('A', &a, (n_state, n_state)),
+            ('B', &b, (n_state, n_input)),
+            ('C', &c, (n_output, n_state)),
+            ('D', &d, (n_output, n_input)),
+        ] {
+            if mat.shape() != shape {
+                return Err(StateSpaceError::Dimensions(
+                    name,
+                    mat.nrows(),
+                    mat.ncols(),
+                    shape.0,
+                    shape.1,
+                ));
+            }
+        }
+        Ok(Self {
+            a,
+            b,
+            c,
+            d,
+            x: DVector::zeros(n_state),
+            u: Arc::new(vec![0f64; n_input]),
+            y: DVector::zeros(n_output),
+            saturation: None,
+            anti_windup: AntiWindup::None,
+            x_next: DVector::zeros(n_state),
+            y_free: DVector::zeros(n_output),
+            dy: DVector::zeros(n_output),
+            u_aw: DVector::zeros(n_input),
+        })
+    }
+    /// Bounds all the outputs to the interval [`lower`,`upper`]
+    pub fn saturation(mut self, lower: f64, upper: f64) -> Self {
+        self.saturation = Some((lower, upper));
+        self
+    }
+    /// Sets the anti-windup strategy, it has no effect without [saturation](StateSpace::saturation)
+    pub fn anti_windup(mut self, anti_windup: AntiWindup) -> Result<Self, StateSpaceError> {
+        if let AntiWindup::BackCalculation(_) = anti_windup {
+            if self.n_input() != self.n_output() {
+                return Err(StateSpaceError::BackCalculation(
+                    self.n_input(),
+                    self.n_output(),
+                ));
+            }
+        }
+        self.anti_windup = anti_windup;
+        Ok(self)
+    }
+    /// Sets the initial state
+    pub fn initial_state(mut self, x: Vec<f64>) -> Self {
+        assert_eq!(
+            x.len(),
+            self.x.len(),
+            "gmt_dos-clients::StateSpace initial state size error:\nexpected {}, found {}!",
+            self.x.len(),
+            x.len()
+        );
+        self.x = DVector::from_vec(x);
+        self
+    }
+    /// Resets the states and the outputs to zero
+    pub fn reset(&mut self) {
+        self.x.fill(0f64);
+        self.y.fill(0f64);
+    }
+    /// Returns the number of states
+    pub fn n_state(&self) -> usize {
+        self.a.nrows()
+    }
+    /// Returns the number of inputs
+    pub fn n_input(&self) -> usize {
+        self.b.ncols()
+    }
+    /// Returns the number of outputs
+    pub fn n_output(&self) -> usize {
+        self.c.nrows()
+    }
+    pub(crate) fn a(&self) -> &DMatrix<f64> {
+        &self.a
+    }
+    pub(crate) fn b(&self) -> &DMatrix<f64> {
+        &self.b
+    }
+    pub(crate) fn c(&self) -> &DMatrix<f64> {
+        &self.c
+    }
+    pub(crate) fn d(&self) -> &DMatrix<f64> {
+        &self.d
+    }
+    /// Returns the states
+    pub fn state(&self) -> &[f64] {
+        self.x.as_slice()
+    }
+    /// Computes the outputs for the inputs `u` and advances the states by one sample
+    pub(crate) fn step(&mut self, u: &[f64]) -> &[f64] {
+        let u = DVectorView::from_slice(u, u.len());
+        // y = C x + D u
+        self.y_free.gemv(1f64, &self.c, &self.x, 0f64);
+        self.y_free.gemv(1f64, &self.d, &u, 1f64);
+        let Some((lower, upper)) = self.saturation else {
+            self.advance(&u);
+            self.y.copy_from(&self.y_free);
+            return self.y.as_slice();
+        };
+        self.y.copy_from(&self.y_free);
+        self.y.apply(|y| *y = y.clamp(lower, upper));
+        match self.anti_windup {
+            AntiWindup::None => self.advance(&u),
+            AntiWindup::Clamping => {
+                self.x_next.gemv(1f64, &self.a, &self.x, 0f64);
+                self.x_next.gemv(1f64, &self.b, &u, 1f64);
+                // change of the outputs due to the state update: C (x[k+1] - x[k])
+                self.dy.gemv(1f64, &self.c, &self.x_next, 0f64);
+                self.dy.gemv(-1f64, &self.c, &self.x, 1f64);
+                // the states are only updated if they do not push further the saturated outputs
+                let windup = self
+                    .y_free
+                    .iter()
+                    .zip(self.y.iter())
+                    .zip(self.dy.iter())
+                    .any(|((y, y_sat), dy)| (y - y_sat) * dy > 0f64);
+                if !windup {
+                    std::mem::swap(&mut self.x, &mut self.x_next);
+                }
+            }
+            AntiWindup::BackCalculation(gain) => {
+                // u + g (sat(y) - y)
+                self.u_aw.copy_from(&u);
+                self.u_aw.axpy(gain, &self.y, 1f64);
+                self.u_aw.axpy(-gain, &self.y_free, 1f64);
+                self.x_next.gemv(1f64, &self.a, &self.x, 0f64);
+                self.x_next.gemv(1f64, &self.b, &self.u_aw, 1f64);
+                std::mem::swap(&mut self.x, &mut self.x_next);
+            }
+        }
+        self.y.as_slice()
+    }
+    // x[k+1] = A x[k] + B u[k]
+    fn advance(&mut self, u: &DVectorView<f64>) {
+        self.x_next.gemv(1f64, &self.a, &self.x, 0f64);
+        self.x_next.gemv(1f64, &self.b, u, 1f64);
+        std::mem::swap(&mut self.x, &mut self.x_next);
+    }
+}
+
+impl Update for StateSpace {
+    fn update(&mut self) {
+        let u = self.u.clone();
+        self.step(&u);
+    }
+}
+
+impl<U> Read<U> for StateSpace
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn read(&mut self, data: Data<U>) {
+        let u = data.into_arc();
+        assert_eq!(
+            u.len(),
+            self.n_input(),
+            "gmt_dos-clients::StateSpace input size error:\nexpected {}, found {}!",
+            self.n_input(),
+            u.len()
+        );
+        self.u = u;
+    }
+}
+
+impl<U> Write<U> for StateSpace
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn write(&mut self) -> Option<Data<U>> {
+        Some(Data::new(self.y.as_slice().to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integrator() -> StateSpace {
+        StateSpace::new(
+            vec![vec![1.]],
+            vec![vec![1.]],
+            vec![vec![1.]],
+            vec![vec![0.]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn dimensions() {
+        assert!(matches!(
+            StateSpace::new(
+                vec![vec![1.]],
+                vec![vec![1., 0.]],
+                vec![vec![1.]],
+                vec![vec![0.]]
+            ),
+            Err(StateSpaceError::Dimensions('D', 1, 1, 1, 2))
+        ));
+    }
+
+    #[test]
+    fn anti_windup() {
+        let outputs = |mut ss: StateSpace| -> Vec<f64> {
+            [1.; 5]
+                .into_iter()
+                .chain([-1.; 3])
+                .map(|u| ss.step(&[u])[0])
+                .collect()
+        };
+        let saturated = integrator().saturation(-2., 2.);
+        assert_eq!(
+            outputs(saturated.clone()),
+            vec![0., 1., 2., 2., 2., 2., 2., 2.]
+        );
+        assert_eq!(
+            outputs(saturated.anti_windup(AntiWindup::Clamping).unwrap()),
+            vec![0., 1., 2., 2., 2., 2., 2., 1.]
+        );
+    }
+
+    #[test]
+    fn back_calculation() {
+        let mut ss = integrator()
+            .saturation(-2., 2.)
+            .anti_windup(AntiWindup::BackCalculation(1.))
+            .unwrap();
+        let y: Vec<f64> = [1.; 5].into_iter().map(|u| ss.step(&[u])[0]).collect();
+        // the excess over the saturation is fed back: x[k+1] = x[k] + u + (sat(x[k]) - x[k])
+        assert_eq!(y, vec![0., 1., 2., 2., 2.]);
+        assert_eq!(ss.state(), &[3.]);
+    }
+}
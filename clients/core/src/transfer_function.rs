@@ -0,0 +1,385 @@
+//! # Transfer function controller client
+//!
+//! A [TransferFunction] client applies continuous-time transfer functions,
+//! given by their zeros, poles and gain ([ZeroPoleGain]),
+//! to each one of its inputs.
+//! The transfer functions are discretized at the sampling frequency of the actor
+//! the client belongs to, according to one of the [Discretization] methods.
+//!
+//! Each channel is realized as a [StateSpace] model, so the outputs can be bounded
+//! and the states protected from windup the same way.
+//!
+//! # Example
+//! A PI controller `k(s+a)/s` sampled at 1kHz on 7 channels
+//! ```
+//! use gmt_dos_clients::transfer_function::{Discretization, TransferFunction, ZeroPoleGain};
+//! use gmt_dos_clients::state_space::AntiWindup;
+//! let pi = ZeroPoleGain::new(vec![(-10f64).into()], vec![0f64.into()], 0.5)?;
+//! let controller = TransferFunction::new(pi, 1e3, Discretization::Tustin)?
+//!     .channels(7)
+//!     .saturation(-1., 1.)
+//!     .anti_windup(AntiWindup::Clamping)?;
+//! # Ok::<(), gmt_dos_clients::transfer_function::TransferFunctionError>(())
+//! ```
+
+use crate::state_space::{AntiWindup, StateSpace, StateSpaceError};
+use interface::{Data, Read, UniqueIdentifier, Update, Write};
+use nalgebra::DMatrix;
+pub use num_complex::Complex64;
+use std::{f64::consts::PI, sync::Arc};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransferFunctionError {
+    #[error("transfer function with {0} zeros and {1} poles is not proper")]
+    Improper(usize, usize),
+    #[error("complex root {0} is missing its conjugate")]
+    Conjugate(Complex64),
+    #[error("failed to build the state-space realization")]
+    StateSpace(#[from] StateSpaceError),
+}
+
+/// Discretization methods of a continuous-time transfer function
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Discretization {
+    /// Bilinear transform `s = 2/T (z-1)/(z+1)`
+    #[default]
+    Tustin,
+    /// Exact discretization of the system driven by a zero-order hold input
+    ZeroOrderHold,
+    /// Zeros and poles mapped with `z = exp(sT)`, the zeros at infinity,
+    /// but one, are mapped to `z = -1` and the gain is matched at low frequency
+    MatchedPoleZero,
+}
+
+/// Continuous-time transfer function in zero-pole-gain form
+///
+/// `H(s) = k (s-z_1)...(s-z_m) / (s-p_1)...(s-p_n)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroPoleGain {
+    zeros: Vec<Complex64>,
+    poles: Vec<Complex64>,
+    gain: f64,
+}
+
+impl ZeroPoleGain {
+    /// Creates a new transfer function from its zeros, poles and gain
+    ///
+    /// The transfer function must be proper and the complex zeros and poles come in conjugate pairs
+    pub fn new(
+        zeros: Vec<Complex64>,
+        poles: Vec<Complex64>,
+        gain: f64,
+    ) -> Result<Self, TransferFunctionError> {
+        if zeros.len() > poles.len() {
+            return Err(TransferFunctionError::Improper(zeros.len(), poles.len()));
+        }
+        paired(&zeros)?;
+        paired(&poles)?;
+        Ok(Self { zeros, poles, gain })
+    }
+    /// Evaluates the transfer function at `s`
+    pub fn eval(&self, s: Complex64) -> Complex64 {
+        eval(&self.zeros, &self.poles, self.gain, s)
+    }
+    /// Discretizes the transfer function at the given sampling frequency
+    pub fn discretize(
+        &self,
+        sampling_frequency_hz: f64,
+        method: Discretization,
+    ) -> Result<StateSpace, TransferFunctionError> {
+        let t = sampling_frequency_hz.recip();
+        let relative_degree = self.poles.len() - self.zeros.len();
+        match method {
+            Discretization::Tustin => {
+                // s - a = (2/T - a) (z - (2/T + a)/(2/T - a)) / (z + 1)
+                let w = 2. / t;
+                let map = |roots: &[Complex64]| -> Vec<Complex64> {
+                    roots.iter().map(|r| (w + r) / (w - r)).collect()
+                };
+                let gain = self.gain
+                    * (self.zeros.iter().map(|z| w - z).product::<Complex64>()
+                        / self.poles.iter().map(|p| w - p).product::<Complex64>())
+                    .re;
+                let mut zeros = map(&self.zeros);
+                zeros.extend(vec![Complex64::from(-1.); relative_degree]);
+                Ok(realization(
+                    &polynomial(&zeros, gain),
+                    &polynomial(&map(&self.poles), 1.),
+                )?)
+            }
+            Discretization::MatchedPoleZero => {
+                let map = |roots: &[Complex64]| -> Vec<Complex64> {
+                    roots.iter().map(|r| (r * t).exp()).collect()
+                };
+                let mut zeros = map(&self.zeros);
+                zeros.extend(vec![
+                    Complex64::from(-1.);
+                    relative_degree.saturating_sub(1)
+                ]);
+                let poles = map(&self.poles);
+                // the gain is matched at DC or, if there is a root at the origin,
+                // at a hundredth of the Nyquist frequency
+                let at_origin = self
+                    .zeros
+                    .iter()
+                    .chain(&self.poles)
+                    .any(|r| r.norm() < f64::EPSILON);
+                let s = if at_origin {
+                    Complex64::new(0., PI / t / 100.)
+                } else {
+                    Complex64::from(0.)
+                };
+                let ratio = self.eval(s) / eval(&zeros, &poles, 1., (s * t).exp());
+                let gain = ratio.norm().copysign(self.gain);
+                Ok(realization(
+                    &polynomial(&zeros, gain),
+                    &polynomial(&poles, 1.),
+                )?)
+            }
+            Discretization::ZeroOrderHold => {
+                let continuous = realization(
+                    &polynomial(&self.zeros, self.gain),
+                    &polynomial(&self.poles, 1.),
+                )?;
+                let n = continuous.n_state();
+                // exp([A B; 0 0] T) = [Ad Bd; 0 I]
+                let mut augmented = DMatrix::zeros(n + 1, n + 1);
+                augmented
+                    .view_mut((0, 0), (n, n))
+                    .copy_from(&(continuous.a() * t));
+                augmented
+                    .view_mut((0, n), (n, 1))
+                    .copy_from(&(continuous.b() * t));
+                let exp = augmented.exp();
+                Ok(StateSpace::new(
+                    exp.view((0, 0), (n, n)).into_owned(),
+                    exp.view((0, n), (n, 1)).into_owned(),
+                    continuous.c().clone(),
+                    continuous.d().clone(),
+                )?)
+            }
+        }
+    }
+}
+
+/// Checks that the complex roots come in conjugate pairs
+fn paired(roots: &[Complex64]) -> Result<(), TransferFunctionError> {
+    let count = |x: Complex64| {
+        roots
+            .iter()
+            .filter(|r| (**r - x).norm() <= 1e-9 * x.norm().max(1.))
+            .count()
+    };
+    match roots
+        .iter()
+        .find(|r| r.im != 0f64 && count(**r) != count(r.conj()))
+    {
+        Some(root) => Err(TransferFunctionError::Conjugate(*root)),
+        None => Ok(()),
+    }
+}
+
+fn eval(zeros: &[Complex64], poles: &[Complex64], gain: f64, s: Complex64) -> Complex64 {
+    gain * zeros.iter().map(|z| s - z).product::<Complex64>()
+        / poles.iter().map(|p| s - p).product::<Complex64>()
+}
+
+/// Real coefficients, highest degree first, of the polynomial `k (x-r_1)...(x-r_n)`
+fn polynomial(roots: &[Complex64], k: f64) -> Vec<f64> {
+    roots
+        .iter()
+        .fold(vec![Complex64::from(k)], |coefs, root| {
+            let mut next = coefs.clone();
+            next.push(Complex64::from(0.));
+            coefs
+                .iter()
+                .enumerate()
+                .for_each(|(i, c)| next[i + 1] -= c * root);
+            next
+        })
+        .into_iter()
+        .map(|c| c.re)
+        .collect()
+}
+
+/// Controllable canonical realization of the transfer function `num/den`
+///
+/// `den` is monic and the degree of `num` is less or equal to the degree of `den`
+fn realization(num: &[f64], den: &[f64]) -> Result<StateSpace, StateSpaceError> {
+    let n = den.len() - 1;
+    let mut b_coefs = vec![0f64; den.len() - num.len()];
+    b_coefs.extend_from_slice(num);
+    let d = b_coefs[0];
+    let mut a = DMatrix::zeros(n, n);
+    let mut b = DMatrix::zeros(n, 1);
+    let mut c = DMatrix::zeros(1, n);
+    for i in 0..n {
+        a[(0, i)] = -den[i + 1];
+        c[(0, i)] = b_coefs[i + 1] - den[i + 1] * d;
+        if i > 0 {
+            a[(i, i - 1)] = 1f64;
+        }
+    }
+    if n > 0 {
+        b[(0, 0)] = 1f64;
+    }
+    StateSpace::new(a, b, c, DMatrix::from_element(1, 1, d))
+}
+
+/// Transfer function client
+///
+/// The client applies a discrete transfer function to each one of its inputs
+#[derive(Debug, Clone)]
+pub struct TransferFunction {
+    channels: Vec<StateSpace>,
+    u: Arc<Vec<f64>>,
+    y: Vec<f64>,
+}
+
+impl TransferFunction {
+    /// Creates a single input single output transfer function client
+    pub fn new(
+        zpk: ZeroPoleGain,
+        sampling_frequency_hz: f64,
+        method: Discretization,
+    ) -> Result<Self, TransferFunctionError> {
+        Self::diagonal(vec![zpk], sampling_frequency_hz, method)
+    }
+    /// Creates a client with a different transfer function for each input
+    pub fn diagonal(
+        zpks: Vec<ZeroPoleGain>,
+        sampling_frequency_hz: f64,
+        method: Discretization,
+    ) -> Result<Self, TransferFunctionError> {
+        let channels = zpks
+            .iter()
+            .map(|zpk| zpk.discretize(sampling_frequency_hz, method))
+            .collect::<Result<Vec<_>, _>>()?;
+        let n = channels.len();
+        Ok(Self {
+            channels,
+            u: Arc::new(vec![0f64; n]),
+            y: vec![0f64; n],
+        })
+    }
+    /// Replicates the transfer function of the first input to `n` inputs
+    pub fn channels(mut self, n: usize) -> Self {
+        self.channels = vec![self.channels[0].clone(); n];
+        self.u = Arc::new(vec![0f64; n]);
+        self.y = vec![0f64; n];
+        self
+    }
+    /// Bounds all the outputs to the interval [`lower`,`upper`]
+    pub fn saturation(mut self, lower: f64, upper: f64) -> Self {
+        self.channels = self
+            .channels
+            .into_iter()
+            .map(|channel| channel.saturation(lower, upper))
+            .collect();
+        self
+    }
+    /// Sets the anti-windup strategy, it has no effect without [saturation](TransferFunction::saturation)
+    pub fn anti_windup(mut self, anti_windup: AntiWindup) -> Result<Self, TransferFunctionError> {
+        self.channels = self
+            .channels
+            .into_iter()
+            .map(|channel| channel.anti_windup(anti_windup))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self)
+    }
+    /// Resets the states and the outputs to zero
+    pub fn reset(&mut self) {
+        self.channels.iter_mut().for_each(|channel| channel.reset());
+        self.y.iter_mut().for_each(|y| *y = 0f64);
+    }
+}
+
+impl Update for TransferFunction {
+    fn update(&mut self) {
+        self.channels
+            .iter_mut()
+            .zip(self.u.iter())
+            .zip(self.y.iter_mut())
+            .for_each(|((channel, u), y)| *y = channel.step(&[*u])[0]);
+    }
+}
+
+impl<U> Read<U> for TransferFunction
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn read(&mut self, data: Data<U>) {
+        let u = data.into_arc();
+        assert_eq!(
+            u.len(),
+            self.channels.len(),
+            "gmt_dos-clients::TransferFunction input size error:\nexpected {}, found {}!",
+            self.channels.len(),
+            u.len()
+        );
+        self.u = u;
+    }
+}
+
+impl<U> Write<U> for TransferFunction
+where
+    U: UniqueIdentifier<DataType = Vec<f64>>,
+{
+    fn write(&mut self) -> Option<Data<U>> {
+        Some(Data::new(self.y.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step_response(zpk: &ZeroPoleGain, method: Discretization, n: usize) -> Vec<f64> {
+        let mut ss = zpk.discretize(1e3, method).unwrap();
+        (0..n).map(|_| ss.step(&[1.])[0]).collect()
+    }
+
+    #[test]
+    fn low_pass() {
+        // 10Hz first order low-pass filter with unit DC gain
+        let w = 2. * PI * 10.;
+        let zpk = ZeroPoleGain::new(vec![], vec![(-w).into()], w).unwrap();
+        for method in [
+            Discretization::Tustin,
+            Discretization::ZeroOrderHold,
+            Discretization::MatchedPoleZero,
+        ] {
+            let y = step_response(&zpk, method, 1000);
+            assert!((y[999] - 1.).abs() < 1e-9, "{method:?}: {}", y[999]);
+            // after one time constant
+            let tau = (1e3 / w).round() as usize;
+            assert!((y[tau] - (1. - (-1f64).exp())).abs() < 5e-2, "{method:?}");
+        }
+        // ZOH is exact at the sampling instants
+        let y = step_response(&zpk, Discretization::ZeroOrderHold, 10);
+        y.iter()
+            .enumerate()
+            .for_each(|(k, y)| assert!((y - (1. - (-w * k as f64 * 1e-3).exp())).abs() < 1e-12));
+    }
+
+    #[test]
+    fn integrator() {
+        let zpk = ZeroPoleGain::new(vec![], vec![0f64.into()], 2.).unwrap();
+        let y = step_response(&zpk, Discretization::ZeroOrderHold, 5);
+        y.iter()
+            .enumerate()
+            .for_each(|(k, y)| assert!((y - 2e-3 * k as f64).abs() < 1e-12));
+    }
+
+    #[test]
+    fn conjugate() {
+        let p = Complex64::new(-10., 10.);
+        assert!(matches!(
+            ZeroPoleGain::new(vec![], vec![p], 1.),
+            Err(TransferFunctionError::Conjugate(_))
+        ));
+        let zpk = ZeroPoleGain::new(vec![], vec![p, p.conj()], 200.).unwrap();
+        let y = step_response(&zpk, Discretization::Tustin, 10_000);
+        assert!((y[9_999] - 1.).abs() < 1e-6);
+    }
+}
@@ -21,7 +21,7 @@ use gmt_dos_clients_io::{
         M1GlobalTipTilt, M1Modes, M1State, M2GlobalTipTilt, M2Modes, M2State, SegmentD7Piston,
     },
 };
-use interface::{Data, Read, UniqueIdentifier, Units, Update, Write};
+use interface::{Data, Read, Seed, UniqueIdentifier, Units, Update, Write};
 
 pub mod builder;
 mod imaging;
@@ -125,6 +125,11 @@ impl<T: SensorPropagation> Update for OpticalModel<T> {
             sensor.propagate(&mut self.src);
         }
     }
+    fn seed(&mut self, seed: Seed) {
+        if let Some(sensor) = &mut self.sensor {
+            sensor.seed(seed);
+        }
+    }
 }
 
 impl<T: SensorPropagation, const SID: u8> Read<RBM<SID>> for OpticalModel<T> {
@@ -16,7 +16,7 @@ mod segment_gradient;
 mod segment_piston;
 mod wave_sensor;
 
-pub use camera::Camera;
+pub use camera::{Camera, DetectorNoise};
 pub use dispersed_fringe_sensor::DispersedFringeSensor;
 pub use no_sensor::NoSensor;
 pub use segment_gradient::SegmentGradientSensor;
@@ -27,6 +27,8 @@ pub use wave_sensor::WaveSensor;
 pub trait SensorPropagation {
     /// Propagates a [Source] through a sensor
     fn propagate(&mut self, src: &mut Source);
+    /// Seeds the random number generators of the sensor, if any
+    fn seed(&mut self, _seed: interface::Seed) {}
 }
 
 impl<T: Propagation> SensorPropagation for T {
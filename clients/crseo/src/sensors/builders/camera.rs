@@ -4,7 +4,10 @@ use crseo::{
     Builder,
 };
 
-use crate::{sensors::Camera, OpticalModel, OpticalModelBuilder, OpticalModelError};
+use crate::{
+    sensors::{Camera, DetectorNoise},
+    OpticalModel, OpticalModelBuilder, OpticalModelError,
+};
 
 use super::SensorBuilderProperty;
 
@@ -26,7 +29,10 @@ use super::SensorBuilderProperty;
 /// # Ok::<(),Box<dyn std::error::Error>>(())
 /// ```
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct CameraBuilder<const I: usize = 1>(pub(crate) ImagingBuilder);
+pub struct CameraBuilder<const I: usize = 1>(
+    pub(crate) ImagingBuilder,
+    #[serde(default)] pub(crate) Option<DetectorNoise>,
+);
 
 impl<const I: usize> From<OpticalModelBuilder<CameraBuilder<I>>>
     for OpticalModelBuilder<ImagingBuilder>
@@ -80,7 +86,7 @@ impl<const I: usize> Builder for CameraBuilder<I> {
     type Component = Camera<I>;
 
     fn build(self) -> crseo::Result<Self::Component> {
-        Ok(Camera(self.0.build()?))
+        Ok(Camera(self.0.build()?, self.1))
     }
 }
 
@@ -155,9 +161,14 @@ impl<const I: usize> CameraBuilder<I> {
         self.0 = ImagingBuilder::lenslet_flux(self.0, threshold);
         self
     }
+    /// Sets the [DetectorNoise] added to the frames
+    pub fn detector_noise(mut self, noise: DetectorNoise) -> Self {
+        self.1 = Some(noise);
+        self
+    }
     /// Clones the [CameraBuilder] into another [CameraBuilder] with a different frame integration value
     pub fn clone_into<const CO: usize>(&self) -> CameraBuilder<CO> {
-        CameraBuilder(self.0.clone())
+        CameraBuilder(self.0.clone(), self.1.clone())
     }
 }
 
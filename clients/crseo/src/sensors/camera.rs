@@ -11,12 +11,15 @@ use crate::OpticalModel;
 use super::{builders::CameraBuilder, SensorPropagation};
 
 mod interface;
+mod noise;
+pub use noise::DetectorNoise;
 
 /// Optical model camera
 ///
 /// [Camera] is a newtype around [crseo Imaging](https://docs.rs/crseo/latest/crseo/imaging).
 ///
 /// The number of frames that are co-added before resetting the camera is given by `I`.
+/// A [DetectorNoise] is added to the frames if it is set with [CameraBuilder::detector_noise].
 ///
 /// # Examples:
 ///
@@ -28,7 +31,7 @@ mod interface;
 /// let cam = Camera::<1>::builder().build()?;
 /// # Ok::<(),Box<dyn std::error::Error>>(())
 /// ```
-pub struct Camera<const I: usize = 1>(pub(super) Imaging);
+pub struct Camera<const I: usize = 1>(pub(super) Imaging, pub(super) Option<DetectorNoise>);
 
 impl<const I: usize> Deref for Camera<I> {
     type Target = Imaging;
@@ -56,6 +59,11 @@ impl<const I: usize> SensorPropagation for Camera<I> {
         }
         src.through(&mut self.0);
     }
+    fn seed(&mut self, seed: ::interface::Seed) {
+        if let Some(noise) = self.1.as_mut() {
+            noise.seed(seed);
+        }
+    }
 }
 
 impl<const I: usize> Display for Camera<I> {
@@ -14,11 +14,15 @@ impl<const I: usize> Write<Frame<Dev>> for OpticalModel<Camera<I>> {
     }
 }
 
+/// The [detector noise](super::DetectorNoise), if any, is added to the frame
 impl<const I: usize> Write<Frame<Host>> for OpticalModel<Camera<I>> {
     fn write(&mut self) -> Option<Data<Frame<Host>>> {
         self.sensor.as_mut().map(|imgr| {
             {
-                let frame = Vec::<f32>::from(&mut imgr.frame());
+                let mut frame = Vec::<f32>::from(&mut imgr.frame());
+                if let Some(noise) = imgr.1.as_mut() {
+                    noise.apply(&mut frame);
+                }
                 frame
             }
             .into()
@@ -0,0 +1,80 @@
+use interface::Seed;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, Normal, Poisson};
+use serde::{Deserialize, Serialize};
+
+/// Detector noise of a [Camera](super::Camera)
+///
+/// The noise is added to the frames copied to the host memory, the pixel values are given in photo-electrons.
+/// The noise is drawn from a random number generator seeded with the model [Seed]
+/// or from the operating system entropy if the model seed is not set.
+///
+/// ```
+/// use gmt_dos_clients_crseo::sensors::{Camera, DetectorNoise};
+/// use crseo::{Builder, FromBuilder};
+///
+/// let cam = Camera::<1>::builder()
+///     .detector_noise(DetectorNoise::default().photon().read_out(0.5))
+///     .build()?;
+/// # Ok::<(),Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectorNoise {
+    photon: bool,
+    read_out_rms: f64,
+    #[serde(skip, default = "default_rng")]
+    rng: StdRng,
+}
+
+fn default_rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+impl Default for DetectorNoise {
+    fn default() -> Self {
+        Self {
+            photon: false,
+            read_out_rms: 0.,
+            rng: default_rng(),
+        }
+    }
+}
+
+impl PartialEq for DetectorNoise {
+    fn eq(&self, other: &Self) -> bool {
+        self.photon == other.photon && self.read_out_rms == other.read_out_rms
+    }
+}
+
+impl DetectorNoise {
+    /// Adds the photon noise
+    pub fn photon(mut self) -> Self {
+        self.photon = true;
+        self
+    }
+    /// Adds the read-out noise with a standard deviation of `rms` photo-electrons
+    pub fn read_out(mut self, rms: f64) -> Self {
+        self.read_out_rms = rms;
+        self
+    }
+    /// Seeds the random number generator
+    pub fn seed(&mut self, seed: Seed) {
+        self.rng = StdRng::seed_from_u64(seed.value());
+    }
+    /// Adds the noise to the pixels of a `frame`
+    pub fn apply(&mut self, frame: &mut [f32]) {
+        if self.photon {
+            frame.iter_mut().filter(|px| **px > 0.).for_each(|px| {
+                *px = Poisson::new(*px as f64)
+                    .map_or(*px, |poisson| poisson.sample(&mut self.rng) as f32)
+            });
+        }
+        if let Ok(read_out) = Normal::new(0., self.read_out_rms) {
+            if self.read_out_rms > 0. {
+                frame
+                    .iter_mut()
+                    .for_each(|px| *px += read_out.sample(&mut self.rng) as f32);
+            }
+        }
+    }
+}
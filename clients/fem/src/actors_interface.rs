@@ -33,6 +33,25 @@ pub use rbm_removal::RbmRemoval;
 
 impl<S> Units for DiscreteModalSolver<S> where S: Solver + Default {}
 
+impl<S> DiscreteModalSolver<S>
+where
+    S: Solver + Default,
+{
+    /// Copies the output `U` into a recycled buffer of the output `V`
+    fn pooled<U, V>(&mut self) -> Option<Data<V>>
+    where
+        U: 'static + UniqueIdentifier,
+        V: 'static + UniqueIdentifier<DataType = Vec<f64>>,
+    {
+        let range = self
+            .outs
+            .iter()
+            .find(|&x| x.as_any().is::<crate::fem_io::SplitFem<U>>())
+            .map(|io| io.range())?;
+        Some(self.buffers.pool::<V>().from_slice(&self.y[range]))
+    }
+}
+
 impl<S> Update for DiscreteModalSolver<S>
 where
     DiscreteModalSolver<S>: Iterator,
@@ -86,7 +105,7 @@ where
     U: 'static,
 {
     fn write(&mut self) -> Option<Data<U>> {
-        <DiscreteModalSolver<S> as Get<U>>::get(self).map(|data| Data::new(data))
+        self.pooled::<U, U>()
     }
 }
 
@@ -99,7 +118,7 @@ where
     U: 'static,
 {
     fn write(&mut self) -> Option<Data<operator::Left<U>>> {
-        <DiscreteModalSolver<S> as Get<U>>::get(self).map(|data| Data::new(data))
+        self.pooled::<U, operator::Left<U>>()
     }
 }
 
@@ -112,6 +131,6 @@ where
     U: 'static,
 {
     fn write(&mut self) -> Option<Data<operator::Right<U>>> {
-        <DiscreteModalSolver<S> as Get<U>>::get(self).map(|data| Data::new(data))
+        self.pooled::<U, operator::Right<U>>()
     }
 }
@@ -6,7 +6,7 @@ use crate::{
 };
 
 use gmt_fem::{Result, FEM};
-use interface::TimerMarker;
+use interface::{BufferPools, TimerMarker};
 use nalgebra as na;
 use rayon::prelude::*;
 use std::{
@@ -64,6 +64,9 @@ pub struct DiscreteModalSolver<T: Solver + Default> {
     pub outs: Vec<Box<dyn GetOut>>,
     pub facesheet_nodes: Option<RbmRemoval>,
     pub m1_figure_nodes: Option<RbmRemoval>,
+    /// Recycled buffers of the outputs data
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub buffers: BufferPools<Vec<f64>>,
 }
 impl<T: Solver + Default> DiscreteModalSolver<T> {
     /*
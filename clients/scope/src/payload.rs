@@ -36,6 +36,18 @@ pub(crate) enum Payload {
     },
 }
 
+/// Returns the tag of the data: the UID name followed by the data physical units
+///
+/// The units are not known anymore if the data is scaled
+#[cfg(feature = "server")]
+fn tag<U: UniqueIdentifier>(scale: Option<f64>) -> String {
+    let name = <U as interface::Quote>::quote();
+    match (<U as UniqueIdentifier>::UNITS, scale) {
+        (Some(units), None) => format!("{name} [{units}]"),
+        _ => name,
+    }
+}
+
 #[cfg(feature = "server")]
 impl Payload {
     /// Creates a new [Payload] for a signal
@@ -52,13 +64,13 @@ impl Payload {
     {
         if let Some(idx) = idx {
             data.get(idx).map(|&v| Self::Signal {
-                tag: <U as interface::Quote>::quote(),
+                tag: tag::<U>(scale),
                 tau,
                 value: scale.map_or_else(|| v.into(), |s| f64::from(v) * s),
             })
         } else {
             Some(Self::Signals {
-                tag: <U as interface::Quote>::quote(),
+                tag: tag::<U>(None),
                 tau,
                 value: data.iter().map(|v| f64::from(*v)).collect(),
             })
@@ -78,7 +90,7 @@ impl Payload {
         f64: From<T>,
     {
         Some(Self::Image {
-            tag: <U as interface::Quote>::quote(),
+            tag: tag::<U>(scale),
             tau,
             size,
             pixels: Vec::from(data)
@@ -104,7 +116,7 @@ impl Payload {
     {
         let (pixels, mask) = std::ops::Deref::deref(&data).clone();
         Some(Self::Image {
-            tag: <U as interface::Quote>::quote(),
+            tag: tag::<U>(scale),
             tau,
             size,
             pixels: Vec::from(pixels)
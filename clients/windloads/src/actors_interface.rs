@@ -1,9 +1,13 @@
 use gmt_dos_clients_io::cfd_wind_loads::{CFDM1WindLoads, CFDM2WindLoads, CFDMountWindLoads};
-use interface::{Data, Size, Update, Write, UID};
+use interface::{Data, Seed, Size, Update, Write, UID};
 
 use crate::{CfdLoads, FOH, ZOH};
 
-impl Update for CfdLoads<ZOH> {}
+impl Update for CfdLoads<ZOH> {
+    fn seed(&mut self, seed: Seed) {
+        self.skip_random_start(seed);
+    }
+}
 impl Update for CfdLoads<FOH> {
     fn update(&mut self) {
         if self.step > self.max_step {
@@ -12,6 +16,9 @@ impl Update for CfdLoads<FOH> {
         self.upsampling.update(self.step);
         self.step += 1;
     }
+    fn seed(&mut self, seed: Seed) {
+        self.skip_random_start(seed);
+    }
 }
 
 #[derive(UID)]
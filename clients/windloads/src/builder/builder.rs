@@ -418,6 +418,7 @@ impl<S> Builder<S> {
             step: 0,
             upsampling: self.upsampling,
             max_step: usize::MAX,
+            random_start: None,
         })
     }
 }
@@ -686,6 +687,7 @@ impl<S> Builder<S> {
             step: 0,
             upsampling: self.upsampling,
             max_step: usize::MAX,
+            random_start: None,
         })
     }
 }
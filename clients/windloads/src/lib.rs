@@ -51,7 +51,7 @@ let cfd_loads = CfdLoads::foh(".", 1000)
 */
 
 use geotrans::{Segment, SegmentTrait, Transform, M1, M2};
-use interface::filing::Codec;
+use interface::{filing::Codec, Seed};
 use parse_monitors::Vector;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -139,6 +139,10 @@ pub struct CfdLoads<S> {
     step: usize,
     upsampling: S,
     max_step: usize,
+    // not serialized to keep the layout of the encoded wind loads files,
+    // the samples are skipped as soon as the client is seeded
+    #[serde(skip)]
+    random_start: Option<usize>,
 }
 
 impl<S: Serialize + for<'de> Deserialize<'de>> Codec for CfdLoads<S> {}
@@ -179,6 +183,34 @@ impl<S> CfdLoads<S> {
         self.step = step + 1;
         self
     }
+    /// Skips a random number of CFD samples, up to `max_sample`, at the start of the wind loads
+    ///
+    /// The number of skipped samples is derived from the [Seed] given to the client
+    /// when the model seed is set
+    pub fn random_start(&mut self, max_sample: usize) -> &mut Self {
+        self.random_start = Some(max_sample);
+        self
+    }
+}
+
+impl<S> CfdLoads<S> {
+    // Skips the random number of samples set with `random_start`
+    fn skip_random_start(&mut self, seed: Seed) {
+        let Some(max_sample) = self.random_start else {
+            return;
+        };
+        let k = (seed.value() % (max_sample as u64 + 1)) as usize;
+        let n_fm = self.n_fm;
+        for (data, n) in [
+            (self.oss.as_mut(), n_fm),
+            (self.m1.as_mut(), 42),
+            (self.m2.as_mut(), 42),
+        ] {
+            if let Some(data) = data {
+                data.drain(..(k * n).min(data.len()));
+            }
+        }
+    }
 }
 impl<S> fmt::Display for CfdLoads<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
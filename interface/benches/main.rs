@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use gmt_dos_actors_clients_interface::{Data, UniqueIdentifier};
+use gmt_dos_actors_clients_interface::{BufferPool, Data, UniqueIdentifier};
 
 const N: usize = 100_000;
 
@@ -37,5 +37,23 @@ pub fn as_arc(c: &mut Criterion) {
     c.bench_function("AsArc", |b| b.iter(|| client.as_arc(&data)));
 }
 
-criterion_group!(benches, move_arc, as_arc);
+// A writer sends new data every step to a reader that holds on to the previous data until it reads the new one
+pub fn allocate(c: &mut Criterion) {
+    let mut client = Client::default();
+    let y = vec![1f64; N];
+    c.bench_function("Allocate", |b| {
+        b.iter(|| client.into_arc(Data::new(y.clone())))
+    });
+}
+
+pub fn recycle(c: &mut Criterion) {
+    let mut client = Client::default();
+    let mut pool = BufferPool::new();
+    let y = vec![1f64; N];
+    c.bench_function("Recycle", |b| {
+        b.iter(|| client.into_arc(pool.from_slice(&y)))
+    });
+}
+
+criterion_group!(benches, move_arc, as_arc, allocate, recycle);
 criterion_main!(benches);
@@ -9,6 +9,8 @@ Data is passed from the client to the [actor] by invoking [Write::write] from th
 
 The client state may be updated by invoking [Update::update] from the client
 
+Clients writing large data every step may recycle the data buffers with a [BufferPool]
+
 [actor]: https://docs.rs/gmt_dos-actors
 */
 
@@ -16,7 +18,11 @@ use std::any::type_name;
 
 mod data;
 pub use data::Data;
+mod pool;
 pub use dos_uid_derive::UID;
+pub use pool::{BufferPool, BufferPools};
+mod seed;
+pub use seed::Seed;
 pub mod units;
 
 pub mod select;
@@ -50,6 +56,10 @@ where
 /// Defines the data type associated with unique identifier data type
 pub trait UniqueIdentifier: Send + Sync {
     const PORT: u16 = 50_000;
+    /// Physical units of the data, e.g. `N` or `m`
+    const UNITS: Option<&'static str> = None;
+    /// Number of elements of the data, if it is known ahead of time
+    const LEN: Option<usize> = None;
     type DataType: Send + Sync;
 }
 pub trait Quote {
@@ -79,6 +89,11 @@ impl UniqueIdentifier for () {
 /// Actor client state update interface
 pub trait Update: Send + Sync {
     fn update(&mut self) {}
+    /// Seeds the client random number generators
+    ///
+    /// Called for every client of a model when the [model seed](Seed) is set,
+    /// the default implementation does nothing
+    fn seed(&mut self, _seed: Seed) {}
 }
 /// Client input data reader interface
 pub trait Read<U: UniqueIdentifier>: Update {
@@ -0,0 +1,206 @@
+use std::{any::TypeId, collections::HashMap, fmt, sync::Arc};
+
+use crate::{Data, UniqueIdentifier};
+
+/// Pool of recycled data buffers
+///
+/// A writer gets the [Data] of its output from the pool instead of allocating a new buffer every step,
+/// a buffer of the pool is reused as soon as all the readers have dropped their clones of the [Data]
+/// it has been sent with, otherwise a new buffer is allocated.
+/// The pool keeps at most [size](BufferPool::size) buffers, the buffers allocated beyond this limit are not recycled.
+///
+/// ```
+/// use gmt_dos_actors_clients_interface::{BufferPool, Data, UniqueIdentifier};
+///
+/// enum U {}
+/// impl UniqueIdentifier for U {
+///     type DataType = Vec<f64>;
+/// }
+///
+/// let mut pool = BufferPool::<Vec<f64>>::new();
+/// let data: Data<U> = pool.from_slice(&[1., 2., 3.]);
+/// assert_eq!(*data, vec![1., 2., 3.]);
+/// drop(data);
+/// let data: Data<U> = pool.from_slice(&[4., 5., 6.]);
+/// assert_eq!(*data, vec![4., 5., 6.]);
+/// assert_eq!(pool.allocated(), 1);
+/// assert_eq!(pool.recycled(), 1);
+/// ```
+pub struct BufferPool<T> {
+    buffers: Vec<Arc<T>>,
+    size: usize,
+    allocated: usize,
+    recycled: usize,
+}
+
+impl<T> Default for BufferPool<T> {
+    fn default() -> Self {
+        Self {
+            buffers: Vec::new(),
+            size: 4,
+            allocated: 0,
+            recycled: 0,
+        }
+    }
+}
+
+impl<T> fmt::Debug for BufferPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPool")
+            .field("buffers", &self.buffers.len())
+            .field("size", &self.size)
+            .field("allocated", &self.allocated)
+            .field("recycled", &self.recycled)
+            .finish()
+    }
+}
+
+impl<T> BufferPool<T> {
+    /// Creates a new pool of 4 buffers
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Sets the maximum number of buffers in the pool
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size.max(1);
+        self
+    }
+    /// Returns the number of buffers that have been allocated
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+    /// Returns the number of buffers that have been recycled
+    pub fn recycled(&self) -> usize {
+        self.recycled
+    }
+}
+
+impl<T: Default> BufferPool<T> {
+    /// Fills a buffer of the pool and returns it as [Data]
+    ///
+    /// A recycled buffer holds the data it was last filled with,
+    /// `fill` must overwrite the buffer content
+    pub fn data<U>(&mut self, fill: impl FnOnce(&mut T)) -> Data<U>
+    where
+        U: UniqueIdentifier<DataType = T>,
+    {
+        if let Some(buffer) = self
+            .buffers
+            .iter_mut()
+            .find_map(|buffer| Arc::get_mut(buffer).is_some().then_some(buffer))
+        {
+            self.recycled += 1;
+            fill(Arc::get_mut(buffer).unwrap());
+            return Data::from(&*buffer);
+        }
+        self.allocated += 1;
+        let mut value = T::default();
+        fill(&mut value);
+        let buffer = Arc::new(value);
+        if self.buffers.len() < self.size {
+            self.buffers.push(Arc::clone(&buffer));
+        }
+        Data::from(buffer)
+    }
+}
+
+impl<T: Clone> BufferPool<Vec<T>> {
+    /// Copies `data` into a buffer of the pool and returns it as [Data]
+    pub fn from_slice<U>(&mut self, data: &[T]) -> Data<U>
+    where
+        U: UniqueIdentifier<DataType = Vec<T>>,
+    {
+        self.data(|buffer| {
+            buffer.clear();
+            buffer.extend_from_slice(data);
+        })
+    }
+    /// Collects `iter` into a buffer of the pool and returns it as [Data]
+    pub fn from_iter<U, I>(&mut self, iter: I) -> Data<U>
+    where
+        U: UniqueIdentifier<DataType = Vec<T>>,
+        I: IntoIterator<Item = T>,
+    {
+        self.data(|buffer| {
+            buffer.clear();
+            buffer.extend(iter);
+        })
+    }
+}
+
+/// Collection of [BufferPool]s, one per UID
+///
+/// For clients that write the same data type to several outputs
+pub struct BufferPools<T> {
+    pools: HashMap<TypeId, BufferPool<T>>,
+    size: usize,
+}
+
+impl<T> Default for BufferPools<T> {
+    fn default() -> Self {
+        Self {
+            pools: HashMap::new(),
+            size: 4,
+        }
+    }
+}
+
+impl<T> fmt::Debug for BufferPools<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPools")
+            .field("pools", &self.pools.len())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<T> BufferPools<T> {
+    /// Creates a new collection of pools of 4 buffers
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Sets the maximum number of buffers in each pool
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size.max(1);
+        self
+    }
+    /// Returns the pool of the UID `U`
+    pub fn pool<U>(&mut self) -> &mut BufferPool<T>
+    where
+        U: 'static + UniqueIdentifier<DataType = T>,
+    {
+        let size = self.size;
+        self.pools
+            .entry(TypeId::of::<U>())
+            .or_insert_with(|| BufferPool::new().size(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum U {}
+    impl UniqueIdentifier for U {
+        type DataType = Vec<f64>;
+    }
+
+    #[test]
+    fn recycling() {
+        let mut pool = BufferPool::<Vec<f64>>::new().size(2);
+        let a: Data<U> = pool.from_slice(&[1.; 8]);
+        let ptr = a.as_ptr();
+        let b: Data<U> = pool.from_slice(&[2.; 8]);
+        let c: Data<U> = pool.from_slice(&[3.; 8]);
+        assert_eq!(pool.allocated(), 3);
+        drop(a);
+        let d: Data<U> = pool.from_slice(&[4.; 8]);
+        assert_eq!(d.as_ptr(), ptr);
+        assert_eq!(*d, vec![4.; 8]);
+        drop((b, c, d));
+        let _: Data<U> = pool.from_iter((0..8).map(|x| x as f64));
+        let _: Data<U> = pool.from_iter((0..8).map(|x| x as f64));
+        assert_eq!(pool.allocated(), 3);
+        assert_eq!(pool.recycled(), 3);
+    }
+}
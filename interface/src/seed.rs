@@ -0,0 +1,70 @@
+/// Seed of the random number generators of a model
+///
+/// The clients get their seed with [Update::seed](crate::Update::seed).
+/// A [Seed] holds the model seed and the seed of a random stream derived from the model seed,
+/// each actor of a model gets its own stream derived from the model seed and the actor name,
+/// so adding or removing an actor does not perturb the random streams of the other actors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Seed {
+    model: u64,
+    stream: u64,
+}
+
+impl Seed {
+    /// Creates a new seed from the model seed
+    pub fn new(model: u64) -> Self {
+        Self {
+            model,
+            stream: model,
+        }
+    }
+    /// Returns a new seed which stream is derived from the stream of this seed and the given `name`
+    ///
+    /// The stream of a [Seed::new] seed is the model seed
+    pub fn derive(&self, name: &str) -> Self {
+        // FNV-1a hash of the name, stable across platforms and compiler versions
+        let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        Self {
+            model: self.model,
+            stream: splitmix64(self.stream ^ hash),
+        }
+    }
+    /// Returns the model seed
+    pub fn model(&self) -> u64 {
+        self.model
+    }
+    /// Returns the seed of the random stream
+    pub fn value(&self) -> u64 {
+        self.stream
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive() {
+        let seed = Seed::new(42);
+        assert_eq!(seed.derive("noise"), Seed::new(42).derive("noise"));
+        assert_ne!(seed.derive("noise").value(), seed.derive("wind").value());
+        assert_ne!(
+            seed.derive("noise").value(),
+            Seed::new(7).derive("noise").value()
+        );
+        assert_eq!(seed.derive("noise").model(), 42);
+        assert_ne!(
+            seed.derive("1").derive("noise").value(),
+            seed.derive("noise").value()
+        );
+    }
+}
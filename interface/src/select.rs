@@ -44,6 +44,8 @@ impl<U: UniqueIdentifier, const IDX: usize> UniqueIdentifier for Select<U, IDX>
     type DataType = <U as UniqueIdentifier>::DataType;
 
     const PORT: u16 = <U as UniqueIdentifier>::PORT + 11 * IDX as u16;
+    const UNITS: Option<&'static str> = <U as UniqueIdentifier>::UNITS;
+    const LEN: Option<usize> = Some(1);
 }
 
 impl<U: UniqueIdentifier, const IDX: usize> Update for Select<U, IDX> {}
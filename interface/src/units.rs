@@ -10,6 +10,8 @@
 //! `U` must implements the [UniqueIdentifier] trait with `Vec` as [UniqueIdentifier::DataType]
 //! i.e. the bound on `U` is  `U: UniqueIdentifier<DataType = Vec<T>>`
 //!
+//! The physical units of the converted data, i.e. [UniqueIdentifier::UNITS], are set to the units of the conversion type,
+//! e.g. `nm` for [NM].
 //!
 //! [MKS]: https://en.wikipedia.org/wiki/MKS_system_of_units
 
@@ -20,13 +22,14 @@ use crate::{Size, Units};
 use super::{Data, UniqueIdentifier, Write};
 
 macro_rules! converter {
-    ( $( ($u:literal:$t:ident,$l:expr) ),* ) => {
+    ( $( ($u:literal:$t:ident,$s:literal,$l:expr) ),* ) => {
         $(
         #[doc = "Conversion to "]
         #[doc = $u]
         pub struct $t<U: UniqueIdentifier>(PhantomData<U>);
         impl<U: UniqueIdentifier> UnitsConversion for $t<U> {
             const UNITS: f64 = $l;
+            const SYMBOL: &'static str = $s;
             type ID = U;
         }
         /// Blanket implementation of [Write] for clients that implement [Write] and [Units]
@@ -57,12 +60,12 @@ macro_rules! converter {
 }
 
 converter!(
-//  ( Units            : Type  , Conversion factor   )
-    ("nanometers"      : NM    ,                  1e9),
-    ("micrometers"     : MuM   ,                  1e6),
-    ("degrees"         : Deg,  180. / PI),
-    ("arcseconds"      : Arcsec,  (180. * 3600.) / PI),
-    ("milli-arcseconds": Mas   , (180. * 3600e3) / PI)
+//  ( Units            : Type  , Symbol  , Conversion factor   )
+    ("nanometers"      : NM    , "nm"    ,                  1e9),
+    ("micrometers"     : MuM   , "um"    ,                  1e6),
+    ("degrees"         : Deg   , "deg"   ,            180. / PI),
+    ("arcseconds"      : Arcsec, "arcsec",  (180. * 3600.) / PI),
+    ("milli-arcseconds": Mas   , "mas"   , (180. * 3600e3) / PI)
 );
 /*
 ------------------------------------------------------------------------------------------
@@ -77,13 +80,64 @@ where
     W: UnitsConversion<ID = U> + Send + Sync,
 {
     const PORT: u16 = <U as UniqueIdentifier>::PORT;
+    const UNITS: Option<&'static str> = Some(<W as UnitsConversion>::SYMBOL);
+    const LEN: Option<usize> = <U as UniqueIdentifier>::LEN;
     type DataType = <U as UniqueIdentifier>::DataType;
 }
 
+/// Returns the scale factor that converts data in the units `from` into the units `to`
+///
+/// The units are either the same, or an angle (`rad`, `deg`, `arcsec`, `mas`),
+/// or the same units with a different SI prefix, e.g. `m` and `nm` or `N` and `kN`,
+/// otherwise [None] is returned
+pub fn scale(from: &str, to: &str) -> Option<f64> {
+    fn angle(units: &str) -> Option<f64> {
+        match units {
+            "rad" => Some(1.),
+            "deg" => Some(PI / 180.),
+            "arcsec" => Some(PI / (180. * 3600.)),
+            "mas" => Some(PI / (180. * 3600e3)),
+            _ => None,
+        }
+    }
+    fn prefix(units: &str) -> Vec<(f64, &str)> {
+        let mut chars = units.chars();
+        let factor = match chars.next() {
+            Some('G') => 1e9,
+            Some('M') => 1e6,
+            Some('k') => 1e3,
+            Some('c') => 1e-2,
+            Some('m') => 1e-3,
+            Some('u') | Some('µ') => 1e-6,
+            Some('n') => 1e-9,
+            Some('p') => 1e-12,
+            _ => return vec![(1., units)],
+        };
+        match chars.as_str() {
+            "" => vec![(1., units)],
+            base => vec![(1., units), (factor, base)],
+        }
+    }
+    if from == to {
+        return Some(1.);
+    }
+    if let (Some(a), Some(b)) = (angle(from), angle(to)) {
+        return Some(a / b);
+    }
+    prefix(from).into_iter().find_map(|(a, from)| {
+        prefix(to)
+            .into_iter()
+            .find(|(_, to)| *to == from)
+            .map(|(b, _)| a / b)
+    })
+}
+
 /// Trait performing the units conversion
 pub trait UnitsConversion {
     /// Conversion scale factor
     const UNITS: f64;
+    /// Units symbol
+    const SYMBOL: &'static str;
     type ID: UniqueIdentifier;
 
     /// Converts data given in MKSA system
@@ -99,7 +153,7 @@ pub trait UnitsConversion {
             type_name::<T>(),
             type_name::<Self>()
         );
-        let s: T = T::try_from(Self::UNITS).map_err(|_| msg)?;
+        let s: T = T::try_from(<Self as UnitsConversion>::UNITS).map_err(|_| msg)?;
         let data: Vec<_> = Into::<&[T]>::into(data).iter().map(|x| *x * s).collect();
         Ok(data.into())
     }
@@ -167,4 +221,16 @@ mod tests {
         let data = <ClientAngle as Write<Mas<W>>>::write(&mut client);
         dbg!(data);
     }
+
+    #[test]
+    fn units_scale() {
+        let close = |from, to, s: f64| (scale(from, to).unwrap() / s - 1.).abs() < 1e-12;
+        assert!(close("N", "N", 1.));
+        assert!(close("m", "nm", 1e9));
+        assert!(close("kN", "N", 1e3));
+        assert!(close("mm", "um", 1e3));
+        assert!(close("arcsec", "mas", 1e3));
+        assert_eq!(scale("N", "m"), None);
+        assert_eq!(<NM<W> as UniqueIdentifier>::UNITS, Some("nm"));
+    }
 }
@@ -69,6 +69,8 @@ impl Expand for Attributes {
                 quote! {
                     impl #impl_generics ::interface::UniqueIdentifier for #ident #ty_generics #where_clause {
                         const PORT: u16 = #port;
+                        const UNITS: Option<&'static str> = <#name as ::interface::UniqueIdentifier>::UNITS;
+                        const LEN: Option<usize> = <#name as ::interface::UniqueIdentifier>::LEN;
                         type DataType = <#name as ::interface::UniqueIdentifier>::DataType;
                     }
                 }
@@ -76,6 +78,8 @@ impl Expand for Attributes {
                 quote! {
                     impl #impl_generics ::interface::UniqueIdentifier for #ident #ty_generics #where_clause {
                         const PORT: u16 = <#name as ::interface::UniqueIdentifier>::PORT;
+                        const UNITS: Option<&'static str> = <#name as ::interface::UniqueIdentifier>::UNITS;
+                        const LEN: Option<usize> = <#name as ::interface::UniqueIdentifier>::LEN;
                         type DataType = <#name as ::interface::UniqueIdentifier>::DataType;
                     }
                 }
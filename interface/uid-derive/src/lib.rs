@@ -26,7 +26,30 @@ enum ID {}
 enum TU {}
 ```
 
-An alias is a type that implements the [Read], [Write] or [Size] trait of another type that implements the same traits for the same client:
+The physical units of the data are set with:
+```
+use interface::{UID, UniqueIdentifier};
+
+#[derive(UID)]
+#[uid(units = "N")]
+enum Force {}
+
+assert_eq!(<Force as UniqueIdentifier>::UNITS, Some("N"));
+```
+
+and the number of elements of the data with:
+```
+use interface::{UID, UniqueIdentifier};
+
+#[derive(UID)]
+#[uid(len = 42)]
+enum Modes {}
+
+assert_eq!(<Modes as UniqueIdentifier>::LEN, Some(42));
+```
+
+An alias is a type that implements the [Read], [Write] or [Size] trait of another type that implements the same traits for the same client,
+the alias has the same units and number of elements than the other type:
 ```
 use interface::{UID, Data, Read, Size, Update, Write};
 # struct Q<T>(std::marker::PhantomData<T>);
@@ -2,19 +2,21 @@ use proc_macro2::Span;
 use quote::quote;
 use syn::{
     parse::{Parse, ParseStream},
-    DeriveInput, Generics, Ident, LitInt, Token,
+    DeriveInput, Generics, Ident, LitInt, LitStr, Token,
 };
 
 use crate::{Expand, Expanded};
 
 /// UID attributes
 ///
-/// #[uid(data = <type>, port = <u32>)]
+/// #[uid(data = <type>, port = <u32>, units = "<units>", len = <usize>)]
 #[derive(Debug, Clone)]
 pub struct Attributes {
     pub ident: Ident,
     pub port: LitInt,
     generics: Generics,
+    units: Option<LitStr>,
+    len: Option<LitInt>,
 }
 
 impl Default for Attributes {
@@ -25,6 +27,8 @@ impl Default for Attributes {
             ident,
             port: LitInt::new("50_000", Span::call_site()),
             generics,
+            units: None,
+            len: None,
         }
     }
 }
@@ -43,6 +47,12 @@ impl Parse for Attributes {
                     uid_attrs.port = port;
                 });
             }
+            if key == "units" {
+                uid_attrs.units = Some(input.parse::<LitStr>()?);
+            }
+            if key == "len" {
+                uid_attrs.len = Some(input.parse::<LitInt>()?);
+            }
             let Ok(_) = input.parse::<Token!(,)>() else {
                 return Ok(uid_attrs);
             };
@@ -60,12 +70,22 @@ impl Expand for Attributes {
             ident: name,
             port,
             generics: name_generics,
+            units,
+            len,
         } = self;
         let (_name_impl_generics, name_ty_generics, _name_where_clause) =
             name_generics.split_for_impl();
+        let units = units
+            .as_ref()
+            .map(|units| quote!(const UNITS: Option<&'static str> = Some(#units);));
+        let len = len
+            .as_ref()
+            .map(|len| quote!(const LEN: Option<usize> = Some(#len);));
         quote! {
             impl #impl_generics ::interface::UniqueIdentifier for #ident #ty_generics #where_clause {
                 const PORT: u16 = #port;
+                #units
+                #len
                 type DataType = #name #name_ty_generics;
             }
         }